@@ -0,0 +1,67 @@
+//! `roz approve` and `roz reject` command implementations.
+//!
+//! Human overrides for when the agent and reviewer are stuck. The decision is
+//! recorded with `decided_by: human` and the hooks treat it like a reviewer's.
+
+use crate::cli::decide::record_human_decision;
+use crate::cli::record::record_command;
+use crate::core::state::Decision;
+use crate::error::Result;
+use crate::storage::file::{FileBackend, get_roz_home};
+use serde_json::json;
+
+/// Run the approve command.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails or the session is not found.
+pub fn approve(session_id: &str, reason: &str) -> Result<()> {
+    let store = FileBackend::new(get_roz_home())?;
+
+    let result = record_human_decision(
+        &store,
+        session_id,
+        Decision::Complete {
+            summary: reason.to_string(),
+            second_opinions: None,
+        },
+    );
+    record_command(
+        "approve",
+        session_id,
+        json!({ "session_id": session_id, "reason": reason }),
+        &result,
+    );
+    result?;
+
+    println!("Approved session {session_id} (decided by human)");
+    Ok(())
+}
+
+/// Run the reject command.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails or the session is not found.
+pub fn reject(session_id: &str, message: &str) -> Result<()> {
+    let store = FileBackend::new(get_roz_home())?;
+
+    let result = record_human_decision(
+        &store,
+        session_id,
+        Decision::Issues {
+            summary: message.to_string(),
+            message_to_agent: Some(message.to_string()),
+        },
+    );
+    record_command(
+        "reject",
+        session_id,
+        json!({ "session_id": session_id, "message": message }),
+        &result,
+    );
+    result?;
+
+    println!("Rejected session {session_id} (decided by human)");
+    Ok(())
+}
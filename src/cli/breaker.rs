@@ -0,0 +1,357 @@
+//! `roz breaker` command implementation.
+//!
+//! Manual circuit breaker controls: inspect a session's breaker, reset it
+//! without waiting out the cooldown, or trip it by hand. Manual actions go
+//! through the same `circuit_breaker::reset`/`trip` as the hooks and are
+//! traced as `manual_breaker_action`.
+
+use crate::cli::format::{CsvRecord, OutputFormat, print_machine};
+use crate::cli::status::{BreakerStatus, breaker_status, render_breaker, resolve_session};
+use crate::config::{Config, load_config};
+use crate::core::circuit_breaker;
+use crate::core::context::Context;
+use crate::core::state::{EventType, SessionState, TraceEvent, TripCause};
+use crate::error::{Error, Result};
+use crate::storage::MessageStore;
+use crate::storage::file::{FileBackend, get_roz_home};
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::json;
+use std::path::Path;
+
+/// Breaker state for a session (`roz breaker status --format json` schema).
+#[derive(Debug, Serialize)]
+pub struct BreakerReport {
+    /// Session identifier.
+    pub session_id: String,
+
+    /// Circuit breaker state.
+    #[serde(flatten)]
+    pub breaker: BreakerStatus,
+}
+
+impl CsvRecord for BreakerReport {
+    const HEADERS: &'static [&'static str] = &[
+        "session_id",
+        "tripped",
+        "trip_cause",
+        "trip_count",
+        "not_spawned_blocks",
+        "issues_blocks",
+        "cooldown_remaining_seconds",
+        "tripped_gates",
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        let b = &self.breaker;
+        vec![
+            self.session_id.clone(),
+            b.tripped.to_string(),
+            b.trip_cause.map_or("", TripCause::as_str).to_string(),
+            b.trip_count.to_string(),
+            b.not_spawned_blocks.to_string(),
+            b.issues_blocks.to_string(),
+            b.cooldown_remaining_seconds
+                .map(|n| n.to_string())
+                .unwrap_or_default(),
+            b.tripped_gates.join(";"),
+        ]
+    }
+}
+
+/// Run `roz breaker status`.
+///
+/// Shows the breaker for `session_id`, or for the most recent session
+/// recorded in the current directory.
+///
+/// # Errors
+///
+/// Returns an error if no matching session exists or storage fails.
+pub fn status(session_id: Option<&str>, format: OutputFormat) -> Result<()> {
+    let store = FileBackend::new(get_roz_home())?;
+    let config = load_config()?;
+    let cwd = std::env::current_dir()?;
+    let report = collect(&store, &config, session_id, &cwd)?;
+
+    if print_machine(format, &report, std::slice::from_ref(&report))? {
+        return Ok(());
+    }
+
+    let breaker = &report.breaker;
+    println!("Session:   {}", report.session_id);
+    println!(
+        "Blocks:    not spawned {}/{}, issues {}/{}",
+        breaker.not_spawned_blocks,
+        breaker.max_not_spawned_blocks,
+        breaker.issues_blocks,
+        breaker.max_issues_blocks
+    );
+    render_breaker(breaker);
+    Ok(())
+}
+
+/// Collect the breaker report for a session.
+///
+/// # Errors
+///
+/// Returns `SessionNotFound` if no session matches, or a storage error.
+pub fn collect(
+    store: &dyn MessageStore,
+    config: &Config,
+    session_id: Option<&str>,
+    cwd: &Path,
+) -> Result<BreakerReport> {
+    let state = resolve_session(store, session_id, cwd)?;
+    Ok(BreakerReport {
+        session_id: state.session_id.clone(),
+        breaker: breaker_status(&state, config, Utc::now()),
+    })
+}
+
+/// Run `roz breaker reset`.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails or the session is not found.
+pub fn reset(session_id: &str) -> Result<()> {
+    let store = FileBackend::new(get_roz_home())?;
+    reset_breaker(&store, session_id)?;
+    println!("Reset circuit breaker for session {session_id}");
+    Ok(())
+}
+
+/// Run `roz breaker trip`.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails or the session is not found.
+pub fn trip(session_id: &str, reason: &str) -> Result<()> {
+    let store = FileBackend::new(get_roz_home())?;
+    let config = load_config()?;
+    trip_breaker(&store, &config, session_id, reason)?;
+    println!("Tripped circuit breaker for session {session_id}");
+    Ok(())
+}
+
+/// Reset the session breaker, block counters and gate breakers of a session.
+///
+/// A tripped breaker goes through `circuit_breaker::reset`, which also
+/// re-enables review. `trip_count` is kept so a later trip still backs off.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails or the session is not found.
+pub fn reset_breaker(store: &dyn MessageStore, session_id: &str) -> Result<()> {
+    reset_breaker_with_context(store, session_id, &Context::system())
+}
+
+/// Reset a session's breakers with an explicit clock and randomness source.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails or the session is not found.
+pub fn reset_breaker_with_context(
+    store: &dyn MessageStore,
+    session_id: &str,
+    ctx: &Context,
+) -> Result<()> {
+    let mut state = load_session(store, session_id)?;
+
+    let was_tripped = state.review.circuit_breaker_tripped;
+    if was_tripped {
+        circuit_breaker::reset(&mut state, ctx);
+    } else {
+        state.review.block_count = 0;
+        state.review.not_spawned_blocks = 0;
+        state.review.issues_blocks = 0;
+    }
+    let gates: Vec<String> = state.review.gate_tripped_at.keys().cloned().collect();
+    state.review.gate_denials.clear();
+    state.review.gate_tripped_at.clear();
+
+    trace_manual_action(
+        &mut state,
+        json!({
+            "action": "reset",
+            "was_tripped": was_tripped,
+            "gates_reset": gates,
+        }),
+        ctx,
+    );
+    store.put_session(&state)?;
+    Ok(())
+}
+
+/// Trip the session breaker by hand with cause `manual`.
+///
+/// The hooks then apply `on_trip` until the cooldown elapses or a human
+/// decides (`roz approve` / `roz reject`).
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails or the session is not found.
+pub fn trip_breaker(
+    store: &dyn MessageStore,
+    config: &Config,
+    session_id: &str,
+    reason: &str,
+) -> Result<()> {
+    trip_breaker_with_context(store, config, session_id, reason, &Context::system())
+}
+
+/// Trip a session's breaker with an explicit clock and randomness source.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails or the session is not found.
+pub fn trip_breaker_with_context(
+    store: &dyn MessageStore,
+    config: &Config,
+    session_id: &str,
+    reason: &str,
+    ctx: &Context,
+) -> Result<()> {
+    let mut state = load_session(store, session_id)?;
+
+    circuit_breaker::trip(&mut state, &config.circuit_breaker, TripCause::Manual, ctx);
+    trace_manual_action(
+        &mut state,
+        json!({
+            "action": "trip",
+            "reason": reason,
+        }),
+        ctx,
+    );
+    store.put_session(&state)?;
+    Ok(())
+}
+
+/// Load a session or fail with `SessionNotFound`.
+fn load_session(store: &dyn MessageStore, session_id: &str) -> Result<SessionState> {
+    store
+        .get_session(session_id)?
+        .ok_or_else(|| Error::SessionNotFound(session_id.to_string()))
+}
+
+/// Record a manual breaker action in the trace.
+fn trace_manual_action(state: &mut SessionState, payload: serde_json::Value, ctx: &Context) {
+    let now = ctx.now();
+    state.trace.push(TraceEvent {
+        id: ctx.generate_id(),
+        timestamp: now,
+        event_type: EventType::ManualBreakerAction,
+        payload,
+    });
+    state.updated_at = now;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TripAction;
+    use crate::core::context::{FixedClock, SeededRng};
+    use crate::storage::MemoryBackend;
+    use chrono::{Duration, TimeZone};
+
+    fn tripped_session(store: &MemoryBackend, session_id: &str) {
+        let mut state = SessionState::new(session_id);
+        state.review.enabled = false;
+        state.review.block_count = 3;
+        state.review.not_spawned_blocks = 3;
+        state.review.circuit_breaker_tripped = true;
+        state.review.circuit_breaker_tripped_at = Some(Utc::now());
+        state.review.trip_cause = Some(TripCause::ReviewerNotSpawned);
+        state.review.trip_count = 1;
+        state
+            .review
+            .gate_tripped_at
+            .insert("Bash:git push*".to_string(), Utc::now());
+        store.put_session(&state).unwrap();
+    }
+
+    #[test]
+    fn reset_clears_breaker_and_gates() {
+        let store = MemoryBackend::new();
+        tripped_session(&store, "test-reset");
+
+        reset_breaker(&store, "test-reset").unwrap();
+
+        let state = store.get_session("test-reset").unwrap().unwrap();
+        assert!(!state.review.circuit_breaker_tripped);
+        assert!(state.review.enabled);
+        assert_eq!(state.review.not_spawned_blocks, 0);
+        assert_eq!(state.review.trip_count, 1);
+        assert!(state.review.gate_tripped_at.is_empty());
+
+        let event = state.trace.last().unwrap();
+        assert_eq!(event.event_type, EventType::ManualBreakerAction);
+        assert_eq!(event.payload["action"], "reset");
+        assert_eq!(event.payload["was_tripped"], true);
+        assert_eq!(event.payload["gates_reset"][0], "Bash:git push*");
+    }
+
+    #[test]
+    fn trip_uses_manual_cause_and_records_reason() {
+        let store = MemoryBackend::new();
+        store.put_session(&SessionState::new("test-trip")).unwrap();
+        let mut config = Config::default();
+        config.circuit_breaker.on_trip = TripAction::Block;
+
+        trip_breaker(&store, &config, "test-trip", "agent is looping").unwrap();
+
+        let state = store.get_session("test-trip").unwrap().unwrap();
+        assert!(state.review.circuit_breaker_tripped);
+        assert_eq!(state.review.trip_cause, Some(TripCause::Manual));
+        assert!(
+            state
+                .trace
+                .iter()
+                .any(|e| e.event_type == EventType::CircuitBreakerTripped)
+        );
+        let event = state.trace.last().unwrap();
+        assert_eq!(event.event_type, EventType::ManualBreakerAction);
+        assert_eq!(event.payload["reason"], "agent is looping");
+
+        let report = collect(&store, &config, Some("test-trip"), Path::new("/")).unwrap();
+        assert!(report.breaker.tripped);
+        assert_eq!(report.csv_fields()[2], "manual");
+    }
+
+    #[test]
+    fn manual_actions_follow_the_context_clock() {
+        let store = MemoryBackend::new();
+        store.put_session(&SessionState::new("test-clock")).unwrap();
+        let t0 = Utc.with_ymd_and_hms(2026, 1, 1, 10, 0, 0).unwrap();
+        let clock = FixedClock::new(t0);
+        let rng = SeededRng::new(7);
+        let ctx = Context::new(&clock, &rng);
+
+        trip_breaker_with_context(&store, &Config::default(), "test-clock", "why", &ctx).unwrap();
+        let state = store.get_session("test-clock").unwrap().unwrap();
+        assert_eq!(state.review.circuit_breaker_tripped_at, Some(t0));
+        let event = state.trace.last().unwrap();
+        assert_eq!(event.timestamp, t0);
+        assert_eq!(state.updated_at, t0);
+        let trip_id = event.id.clone();
+
+        clock.advance(Duration::minutes(5));
+        reset_breaker_with_context(&store, "test-clock", &ctx).unwrap();
+        let state = store.get_session("test-clock").unwrap().unwrap();
+        let event = state.trace.last().unwrap();
+        assert_eq!(event.timestamp, t0 + Duration::minutes(5));
+        assert_ne!(event.id, trip_id);
+    }
+
+    #[test]
+    fn unknown_session_errors() {
+        let store = MemoryBackend::new();
+        assert!(matches!(
+            reset_breaker(&store, "missing"),
+            Err(Error::SessionNotFound(_))
+        ));
+        assert!(matches!(
+            trip_breaker(&store, &Config::default(), "missing", "why"),
+            Err(Error::SessionNotFound(_))
+        ));
+    }
+}
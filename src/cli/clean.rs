@@ -1,19 +1,46 @@
 //! `roz clean` command implementation.
 
+use crate::cli::format::{CsvRecord, OutputFormat, print_machine};
 use crate::core::state::Decision;
 use crate::error::Result;
 use crate::storage::MessageStore;
 use crate::storage::file::{FileBackend, get_roz_home};
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// A session removed by `roz clean`.
+#[derive(Debug, Serialize)]
+pub struct RemovedSession {
+    /// Session ID.
+    pub session_id: String,
+}
+
+impl CsvRecord for RemovedSession {
+    const HEADERS: &'static [&'static str] = &["session_id"];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![self.session_id.clone()]
+    }
+}
+
+/// Result of `roz clean`.
+#[derive(Debug, Serialize)]
+pub struct CleanReport {
+    /// Sessions created before this time were eligible for removal.
+    pub cutoff: DateTime<Utc>,
+    /// Sessions that were removed.
+    pub removed: Vec<RemovedSession>,
+}
 
 /// Run the clean command.
 ///
-/// Removes sessions older than the specified duration.
+/// Removes sessions older than the specified duration, along with their
+/// hook recordings.
 ///
 /// # Errors
 ///
 /// Returns an error if the storage backend fails.
-pub fn run(before: &str, all: bool) -> Result<()> {
+pub fn run(before: &str, all: bool, format: OutputFormat) -> Result<()> {
     let store = FileBackend::new(get_roz_home())?;
 
     let duration = if all {
@@ -22,8 +49,13 @@ pub fn run(before: &str, all: bool) -> Result<()> {
         parse_duration(before)?
     };
 
-    let removed = clean_sessions(&store, duration)?;
+    let report = collect(&store, Utc::now() - duration)?;
 
+    if print_machine(format, &report, &report.removed)? {
+        return Ok(());
+    }
+
+    let removed = report.removed.len();
     if removed == 0 {
         println!("No sessions to clean.");
     } else {
@@ -38,7 +70,7 @@ pub fn run(before: &str, all: bool) -> Result<()> {
 /// # Errors
 ///
 /// Returns an error if the duration format is invalid.
-fn parse_duration(s: &str) -> Result<Duration> {
+pub(crate) fn parse_duration(s: &str) -> Result<Duration> {
     let s = s.trim();
 
     if s.is_empty() {
@@ -67,11 +99,22 @@ fn parse_duration(s: &str) -> Result<Duration> {
 /// Set high enough to effectively handle any realistic number of sessions.
 const MAX_SESSIONS_TO_PROCESS: usize = 1_000_000;
 
-/// Clean sessions older than the given duration.
-fn clean_sessions(store: &dyn MessageStore, before: Duration) -> Result<usize> {
-    let cutoff = Utc::now() - before;
+/// Remove sessions created before `cutoff` that are not awaiting review.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails.
+pub fn collect(store: &dyn MessageStore, cutoff: DateTime<Utc>) -> Result<CleanReport> {
+    Ok(CleanReport {
+        cutoff,
+        removed: clean_sessions(store, cutoff)?,
+    })
+}
+
+/// Clean sessions created before `cutoff`, returning the removed sessions.
+fn clean_sessions(store: &dyn MessageStore, cutoff: DateTime<Utc>) -> Result<Vec<RemovedSession>> {
     let sessions = store.list_sessions(MAX_SESSIONS_TO_PROCESS)?;
-    let mut removed = 0;
+    let mut removed = Vec::new();
 
     for summary in sessions {
         if summary.created_at >= cutoff {
@@ -86,7 +129,9 @@ fn clean_sessions(store: &dyn MessageStore, before: Duration) -> Result<usize> {
         }
 
         store.delete_session(&summary.session_id)?;
-        removed += 1;
+        removed.push(RemovedSession {
+            session_id: summary.session_id,
+        });
     }
 
     Ok(removed)
@@ -147,9 +192,10 @@ mod tests {
         store.put_session(&recent_state).unwrap();
 
         // Clean sessions older than 7 days
-        let removed = clean_sessions(&store, ChronoDuration::days(7)).unwrap();
+        let removed = clean_sessions(&store, Utc::now() - ChronoDuration::days(7)).unwrap();
 
-        assert_eq!(removed, 1);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].session_id, "old-session");
         assert!(store.get_session("old-session").unwrap().is_none());
         assert!(store.get_session("recent-session").unwrap().is_some());
     }
@@ -166,9 +212,9 @@ mod tests {
         store.put_session(&active_state).unwrap();
 
         // Clean sessions older than 7 days
-        let removed = clean_sessions(&store, ChronoDuration::days(7)).unwrap();
+        let removed = clean_sessions(&store, Utc::now() - ChronoDuration::days(7)).unwrap();
 
-        assert_eq!(removed, 0); // Should not remove active session
+        assert!(removed.is_empty()); // Should not remove active session
         assert!(store.get_session("active-old").unwrap().is_some());
     }
 }
@@ -1,44 +1,173 @@
 //! `roz context` command implementation.
 
+use crate::cli::format::{CsvRecord, OutputFormat, print_machine};
+use crate::core::state::{ClaimedSummary, Decision, GateTrigger, SessionState};
 use crate::error::{Error, Result};
 use crate::storage::MessageStore;
 use crate::storage::file::{FileBackend, get_roz_home};
+use crate::transcript::{TranscriptExcerpt, TranscriptLimits, read_transcript};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+
+/// Review context for a session, as consumed by the roz:roz reviewer.
+///
+/// This is the `roz context --format json` schema. Prompts are included in
+/// full (they are already capped at storage time).
+#[derive(Debug, Serialize)]
+pub struct ContextReport {
+    /// Session identifier.
+    pub session_id: String,
+
+    /// When the session was created.
+    pub created_at: DateTime<Utc>,
+
+    /// When the session was last updated.
+    pub updated_at: DateTime<Utc>,
+
+    /// Whether review is enabled for this session.
+    pub review_enabled: bool,
+
+    /// Current decision (tagged by `type`: `pending`, `complete`, `issues`).
+    pub decision: Decision,
+
+    /// Number of times the stop hook has blocked.
+    pub block_count: u32,
+
+    /// Tool call that triggered a gate, if any.
+    pub gate_trigger: Option<GateTrigger>,
+
+    /// User prompts that requested review, oldest first.
+    pub user_prompts: Vec<String>,
+
+    /// The agent's final message at its most recent Stop.
+    pub claimed_summary: Option<ClaimedSummary>,
+
+    /// Earlier claims in this review, oldest first (one per fix attempt).
+    pub claimed_summary_history: Vec<ClaimedSummary>,
+
+    /// Conversation excerpt from the Claude Code transcript (`--transcript`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<TranscriptExcerpt>,
+
+    /// Why the transcript could not be read (`--transcript`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcript_error: Option<String>,
+}
+
+impl ContextReport {
+    /// Build a context report from session state.
+    #[must_use]
+    pub fn from_state(state: &SessionState) -> Self {
+        Self {
+            session_id: state.session_id.clone(),
+            created_at: state.created_at,
+            updated_at: state.updated_at,
+            review_enabled: state.review.enabled,
+            decision: state.review.decision.clone(),
+            block_count: state.review.block_count,
+            gate_trigger: state.review.gate_trigger.clone(),
+            user_prompts: state.review.user_prompts.clone(),
+            claimed_summary: state.review.claimed_summary.clone(),
+            claimed_summary_history: state.review.claimed_summary_history.clone(),
+            transcript: None,
+            transcript_error: None,
+        }
+    }
+}
+
+impl CsvRecord for ContextReport {
+    const HEADERS: &'static [&'static str] = &[
+        "session_id",
+        "created_at",
+        "updated_at",
+        "review_enabled",
+        "decision",
+        "decision_summary",
+        "block_count",
+        "gate_tool",
+        "gate_pattern",
+        "user_prompts",
+        "claimed_summary",
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        let (decision, summary) = match &self.decision {
+            Decision::Pending => ("pending", ""),
+            Decision::Complete { summary, .. } => ("complete", summary.as_str()),
+            Decision::Issues { summary, .. } => ("issues", summary.as_str()),
+        };
+        vec![
+            self.session_id.clone(),
+            self.created_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            self.updated_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            self.review_enabled.to_string(),
+            decision.to_string(),
+            summary.to_string(),
+            self.block_count.to_string(),
+            self.gate_trigger
+                .as_ref()
+                .map(|t| t.tool_name.clone())
+                .unwrap_or_default(),
+            self.gate_trigger
+                .as_ref()
+                .map(|t| t.pattern_matched.clone())
+                .unwrap_or_default(),
+            self.user_prompts.join("\n"),
+            self.claimed_summary
+                .as_ref()
+                .map(|c| c.summary.clone())
+                .unwrap_or_default(),
+        ]
+    }
+}
 
 /// Run the context command.
 ///
-/// Shows user prompts and session context for review.
+/// Shows user prompts and session context for review, plus an excerpt of the
+/// session's transcript with `transcript`.
 ///
 /// # Errors
 ///
 /// Returns an error if the storage backend fails or the session is not found.
-pub fn run(session_id: &str) -> Result<()> {
+pub fn run(session_id: &str, transcript: bool, format: OutputFormat) -> Result<()> {
     let store = FileBackend::new(get_roz_home())?;
+    let report = if transcript {
+        collect_with_transcript(&store, session_id, &TranscriptLimits::default())?
+    } else {
+        collect(&store, session_id)?
+    };
 
-    let state = store
-        .get_session(session_id)?
-        .ok_or_else(|| Error::SessionNotFound(session_id.to_string()))?;
+    if print_machine(format, &report, std::slice::from_ref(&report))? {
+        return Ok(());
+    }
 
     // Print session header
-    println!("Session: {}", state.session_id);
-    println!("Created: {}", state.created_at.format("%Y-%m-%dT%H:%M:%SZ"));
-    println!("Updated: {}", state.updated_at.format("%Y-%m-%dT%H:%M:%SZ"));
+    println!("Session: {}", report.session_id);
+    println!(
+        "Created: {}",
+        report.created_at.format("%Y-%m-%dT%H:%M:%SZ")
+    );
+    println!(
+        "Updated: {}",
+        report.updated_at.format("%Y-%m-%dT%H:%M:%SZ")
+    );
     println!();
 
     // Print review state
-    println!("Review enabled: {}", state.review.enabled);
+    println!("Review enabled: {}", report.review_enabled);
     println!(
         "Decision: {}",
-        match &state.review.decision {
-            crate::core::Decision::Pending => "Pending".to_string(),
-            crate::core::Decision::Complete { summary, .. } => format!("Complete - {summary}"),
-            crate::core::Decision::Issues { summary, .. } => format!("Issues - {summary}"),
+        match &report.decision {
+            Decision::Pending => "Pending".to_string(),
+            Decision::Complete { summary, .. } => format!("Complete - {summary}"),
+            Decision::Issues { summary, .. } => format!("Issues - {summary}"),
         }
     );
-    println!("Block count: {}", state.review.block_count);
+    println!("Block count: {}", report.block_count);
     println!();
 
     // Print gate trigger info if present
-    if let Some(ref trigger) = state.review.gate_trigger {
+    if let Some(ref trigger) = report.gate_trigger {
         println!("Gate trigger:");
         println!("  Tool: {}", trigger.tool_name);
         println!("  Pattern: {}", trigger.pattern_matched);
@@ -62,18 +191,172 @@ pub fn run(session_id: &str) -> Result<()> {
     }
 
     // Print user prompts
-    if state.review.user_prompts.is_empty() {
+    if report.user_prompts.is_empty() {
         println!("User prompts: (none)");
     } else {
         println!("User prompts:");
-        for (i, prompt) in state.review.user_prompts.iter().enumerate() {
+        for (i, prompt) in report.user_prompts.iter().enumerate() {
             println!("[{}] {}", i + 1, truncate_prompt(prompt, 200));
         }
     }
 
+    print_claims(&report);
+
+    if let Some(error) = &report.transcript_error {
+        println!();
+        println!("Transcript: (unavailable: {error})");
+    }
+    if let Some(transcript) = &report.transcript {
+        println!();
+        print_transcript(transcript);
+    }
+
     Ok(())
 }
 
+/// Print the agent's claimed summary and how it changed across fix attempts.
+fn print_claims(report: &ContextReport) {
+    let Some(claim) = &report.claimed_summary else {
+        return;
+    };
+    println!();
+    println!("Agent's claimed summary:");
+    for line in claim.summary.lines() {
+        println!("  {line}");
+    }
+    if report.claimed_summary_history.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("Earlier claims:");
+    for (i, earlier) in report.claimed_summary_history.iter().enumerate() {
+        let after = earlier
+            .after_issues
+            .as_ref()
+            .map_or_else(String::new, |issues| {
+                format!(" (after ISSUES: {})", truncate_prompt(issues, 80))
+            });
+        println!(
+            "[{}]{after} {}",
+            i + 1,
+            truncate_prompt(&earlier.summary, 200)
+        );
+    }
+    if let Some(issues) = &claim.after_issues {
+        println!(
+            "Latest claim answers ISSUES: {}",
+            truncate_prompt(issues, 200)
+        );
+    }
+}
+
+/// Print the transcript excerpt section.
+fn print_transcript(transcript: &TranscriptExcerpt) {
+    for line in transcript_lines(transcript) {
+        println!("{line}");
+    }
+}
+
+/// Lines of the transcript excerpt section.
+///
+/// Turns and the final message are shown in full: the reader already cut
+/// them to `TranscriptLimits::max_text_chars`.
+fn transcript_lines(transcript: &TranscriptExcerpt) -> Vec<String> {
+    let indented = |text: &str| {
+        text.lines()
+            .map(|line| {
+                if line.is_empty() {
+                    String::new()
+                } else {
+                    format!("  {line}")
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut lines = vec!["Transcript user turns:".to_string()];
+    if transcript.omitted_user_turns > 0 {
+        lines.push(format!(
+            "  ({} earlier turns omitted)",
+            transcript.omitted_user_turns
+        ));
+    }
+    for (i, turn) in transcript.user_turns.iter().enumerate() {
+        lines.push(format!("[{}]", i + 1));
+        lines.extend(indented(&turn.text));
+    }
+    lines.push(String::new());
+
+    lines.push("Final assistant message (review cycle):".to_string());
+    match &transcript.final_summary {
+        Some(summary) => lines.extend(indented(summary)),
+        None => lines.push("  (none)".to_string()),
+    }
+    lines.push(String::new());
+
+    lines.push("Tool calls (review cycle):".to_string());
+    if transcript.omitted_tool_calls > 0 {
+        lines.push(format!(
+            "  ({} earlier calls omitted)",
+            transcript.omitted_tool_calls
+        ));
+    }
+    if transcript.tool_calls.is_empty() {
+        lines.push("  (none)".to_string());
+    }
+    for call in &transcript.tool_calls {
+        let status = match call.is_error {
+            Some(true) => " [error]",
+            _ => "",
+        };
+        lines.push(format!("  {}{status} {}", call.name, call.input));
+    }
+    lines
+}
+
+/// Collect review context for a session.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails or the session is not found.
+pub fn collect(store: &dyn MessageStore, session_id: &str) -> Result<ContextReport> {
+    let state = store
+        .get_session(session_id)?
+        .ok_or_else(|| Error::SessionNotFound(session_id.to_string()))?;
+
+    Ok(ContextReport::from_state(&state))
+}
+
+/// Collect review context with an excerpt of the session's transcript.
+///
+/// Tool calls and the final summary are limited to the current review cycle.
+/// A missing or unreadable transcript is reported in `transcript_error`
+/// rather than failing the command.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails or the session is not found.
+pub fn collect_with_transcript(
+    store: &dyn MessageStore,
+    session_id: &str,
+    limits: &TranscriptLimits,
+) -> Result<ContextReport> {
+    let state = store
+        .get_session(session_id)?
+        .ok_or_else(|| Error::SessionNotFound(session_id.to_string()))?;
+
+    let mut report = ContextReport::from_state(&state);
+    match &state.transcript_path {
+        Some(path) => match read_transcript(path, state.review.cycle_started_at(), limits) {
+            Ok(excerpt) => report.transcript = Some(excerpt),
+            Err(e) => report.transcript_error = Some(format!("{}: {e}", path.display())),
+        },
+        None => report.transcript_error = Some("no transcript recorded".to_string()),
+    }
+    Ok(report)
+}
+
 /// Truncate a prompt for display.
 ///
 /// Handles Unicode properly by truncating at character boundaries.
@@ -100,13 +383,49 @@ fn truncate_prompt(prompt: &str, max_len: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::SessionState;
-    use crate::core::state::{Decision, GateTrigger, TruncatedInput};
+    use crate::core::state::TruncatedInput;
     use crate::storage::MemoryBackend;
-    use crate::storage::MessageStore;
-    use chrono::Utc;
     use serde_json::json;
 
+    #[test]
+    fn transcript_text_shows_whole_turns() {
+        use crate::transcript::{ToolCall, UserTurn};
+
+        let long = "x".repeat(1000);
+        let transcript = TranscriptExcerpt {
+            user_turns: vec![UserTurn {
+                timestamp: None,
+                text: format!("Fix the login bug\n\n{long}"),
+            }],
+            omitted_user_turns: 2,
+            final_summary: Some("Fixed it".to_string()),
+            tool_calls: vec![ToolCall {
+                timestamp: None,
+                name: "Bash".to_string(),
+                input: r#"{"command":"cargo test"}"#.to_string(),
+                is_error: Some(true),
+            }],
+            omitted_tool_calls: 0,
+        };
+        let lines = transcript_lines(&transcript);
+        assert_eq!(
+            lines[..5],
+            [
+                "Transcript user turns:",
+                "  (2 earlier turns omitted)",
+                "[1]",
+                "  Fix the login bug",
+                ""
+            ]
+        );
+        assert_eq!(lines[5], format!("  {long}"));
+        assert!(lines.contains(&"  Fixed it".to_string()));
+        assert_eq!(
+            lines.last().unwrap(),
+            r#"  Bash [error] {"command":"cargo test"}"#
+        );
+    }
+
     #[test]
     fn truncate_short_prompt() {
         let prompt = "Short prompt";
@@ -284,4 +603,126 @@ mod tests {
         assert!(result.ends_with("..."));
         assert_eq!(result.chars().count(), 8); // 5 emoji + 3 for "..."
     }
+
+    #[test]
+    fn context_report_json_shape() {
+        let store = MemoryBackend::new();
+        let mut state = SessionState::new("test-json");
+        state.review.enabled = true;
+        state.review.user_prompts.push("#roz fix it".to_string());
+        state.review.decision = Decision::Issues {
+            summary: "Broken".to_string(),
+            message_to_agent: Some("Fix".to_string()),
+        };
+        store.put_session(&state).unwrap();
+
+        let report = collect(&store, "test-json").unwrap();
+        let json = crate::cli::format::to_json(&report).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed["schema_version"], crate::cli::format::SCHEMA_VERSION);
+        assert_eq!(parsed["session_id"], "test-json");
+        assert_eq!(parsed["review_enabled"], true);
+        assert_eq!(parsed["decision"]["type"], "issues");
+        assert_eq!(parsed["decision"]["message_to_agent"], "Fix");
+        assert_eq!(parsed["user_prompts"][0], "#roz fix it");
+        assert!(parsed["gate_trigger"].is_null());
+        assert!(parsed["claimed_summary"].is_null());
+        assert_eq!(parsed["claimed_summary_history"], json!([]));
+    }
+
+    #[test]
+    fn context_report_includes_claim_history() {
+        let mut state = SessionState::new("test-claims");
+        let now = Utc::now();
+        state.review.record_claim("Fixed the bug", now);
+        state.review.decision = Decision::Issues {
+            summary: "No regression test".to_string(),
+            message_to_agent: None,
+        };
+        state
+            .review
+            .record_claim("Fixed the bug and added a test", now);
+
+        let report = ContextReport::from_state(&state);
+        let claim = report.claimed_summary.as_ref().unwrap();
+        assert_eq!(claim.summary, "Fixed the bug and added a test");
+        assert_eq!(claim.after_issues.as_deref(), Some("No regression test"));
+        assert_eq!(report.claimed_summary_history.len(), 1);
+        assert_eq!(report.claimed_summary_history[0].summary, "Fixed the bug");
+        assert_eq!(report.csv_fields()[10], "Fixed the bug and added a test");
+    }
+
+    #[test]
+    fn context_report_csv_fields() {
+        let mut state = SessionState::new("test-csv");
+        state.review.user_prompts.push("first".to_string());
+        state.review.user_prompts.push("second".to_string());
+
+        let fields = ContextReport::from_state(&state).csv_fields();
+        assert_eq!(fields.len(), ContextReport::HEADERS.len());
+        assert_eq!(fields[4], "pending");
+        assert_eq!(fields[9], "first\nsecond");
+        assert_eq!(fields[10], "");
+    }
+
+    #[test]
+    fn context_with_transcript_limits_tool_calls_to_review_cycle() {
+        let store = MemoryBackend::new();
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("transcript.jsonl");
+        let entries = [
+            json!({"type": "user", "timestamp": "2026-01-01T10:00:00Z",
+                   "message": {"role": "user", "content": "#roz add logout"}}),
+            json!({"type": "assistant", "timestamp": "2026-01-01T10:01:00Z",
+                   "message": {"id": "m1", "content": [{"type": "tool_use", "id": "t1",
+                               "name": "Edit", "input": {"file_path": "a.rs"}}]}}),
+            json!({"type": "assistant", "timestamp": "2026-01-01T10:10:00Z",
+                   "message": {"id": "m2", "content": [{"type": "tool_use", "id": "t2",
+                               "name": "Bash", "input": {"command": "cargo test"}}]}}),
+            json!({"type": "assistant", "timestamp": "2026-01-01T10:11:00Z",
+                   "message": {"id": "m3", "content": [{"type": "text", "text": "Tests pass."}]}}),
+        ];
+        let lines: Vec<String> = entries.iter().map(ToString::to_string).collect();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        // An ISSUES decision at 10:05 starts the fix cycle
+        let mut state = SessionState::new("test-transcript");
+        state.transcript_path = Some(path);
+        state.review.decision = Decision::Issues {
+            summary: "Missing test".to_string(),
+            message_to_agent: None,
+        };
+        state
+            .review
+            .decision_history
+            .push(crate::core::state::DecisionRecord {
+                decision: Decision::Pending,
+                timestamp: "2026-01-01T10:05:00Z".parse().unwrap(),
+                decided_by: None,
+                rejected: false,
+            });
+        store.put_session(&state).unwrap();
+
+        let report =
+            collect_with_transcript(&store, "test-transcript", &TranscriptLimits::default())
+                .unwrap();
+        let transcript = report.transcript.unwrap();
+        assert_eq!(transcript.user_turns[0].text, "#roz add logout");
+        assert_eq!(transcript.final_summary.as_deref(), Some("Tests pass."));
+        assert_eq!(transcript.tool_calls.len(), 1);
+        assert_eq!(transcript.tool_calls[0].name, "Bash");
+
+        // Without a recorded transcript the report says why
+        store
+            .put_session(&SessionState::new("no-transcript"))
+            .unwrap();
+        let report =
+            collect_with_transcript(&store, "no-transcript", &TranscriptLimits::default()).unwrap();
+        assert!(report.transcript.is_none());
+        assert_eq!(
+            report.transcript_error.as_deref(),
+            Some("no transcript recorded")
+        );
+    }
 }
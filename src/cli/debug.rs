@@ -3,6 +3,8 @@
 use crate::error::{Error, Result};
 use crate::storage::MessageStore;
 use crate::storage::file::{FileBackend, get_roz_home};
+use chrono::DateTime;
+use serde_json::Value;
 
 /// Run the debug command.
 ///
@@ -13,18 +15,41 @@ use crate::storage::file::{FileBackend, get_roz_home};
 /// Returns an error if the storage backend fails or the session is not found.
 pub fn run(session_id: &str) -> Result<()> {
     let store = FileBackend::new(get_roz_home())?;
-
-    let state = store
-        .get_session(session_id)?
-        .ok_or_else(|| Error::SessionNotFound(session_id.to_string()))?;
+    let dump = collect(&store, session_id)?;
 
     // Pretty print the full state as JSON
-    let json = serde_json::to_string_pretty(&state)?;
+    let json = serde_json::to_string_pretty(&dump)?;
     println!("{json}");
 
     Ok(())
 }
 
+/// Build the debug dump: the full session state, plus an `incidents` array
+/// when hooks failed open for this session.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails or the session is not found.
+pub fn collect(store: &dyn MessageStore, session_id: &str) -> Result<Value> {
+    let state = store
+        .get_session(session_id)?
+        .ok_or_else(|| Error::SessionNotFound(session_id.to_string()))?;
+
+    let incidents: Vec<_> = store
+        .incidents_since(DateTime::UNIX_EPOCH)?
+        .into_iter()
+        .filter(|i| i.session_id.as_deref() == Some(session_id))
+        .collect();
+
+    let mut dump = serde_json::to_value(&state)?;
+    if !incidents.is_empty() {
+        if let Value::Object(map) = &mut dump {
+            map.insert("incidents".to_string(), serde_json::to_value(incidents)?);
+        }
+    }
+    Ok(dump)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,6 +124,8 @@ mod tests {
             .push(crate::core::state::DecisionRecord {
                 decision: Decision::Pending,
                 timestamp: Utc::now(),
+                decided_by: None,
+                rejected: false,
             });
         state
             .review
@@ -109,6 +136,8 @@ mod tests {
                     message_to_agent: Some("Fix them".to_string()),
                 },
                 timestamp: Utc::now(),
+                decided_by: None,
+                rejected: false,
             });
         state.review.decision = Decision::Complete {
             summary: "Fixed".to_string(),
@@ -123,4 +152,36 @@ mod tests {
         assert!(json.contains("Fix them"));
         assert!(json.contains("Fixed"));
     }
+
+    #[test]
+    fn debug_includes_session_incidents() {
+        use crate::storage::{Incident, IncidentKind};
+
+        let store = MemoryBackend::new();
+        store
+            .put_session(&SessionState::new("test-incidents"))
+            .unwrap();
+
+        for session_id in ["test-incidents", "other-session"] {
+            store
+                .record_incident(&Incident {
+                    timestamp: Utc::now(),
+                    hook: "stop".to_string(),
+                    kind: IncidentKind::StorageWrite,
+                    session_id: Some(session_id.to_string()),
+                    message: "failed to save state: disk full".to_string(),
+                    blocked: false,
+                })
+                .unwrap();
+        }
+
+        let dump = collect(&store, "test-incidents").unwrap();
+        let incidents = dump["incidents"].as_array().unwrap();
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(incidents[0]["kind"], "storage_write");
+
+        store.put_session(&SessionState::new("quiet")).unwrap();
+        let dump = collect(&store, "quiet").unwrap();
+        assert!(dump.get("incidents").is_none());
+    }
 }
@@ -1,15 +1,17 @@
 //! `roz decide` command implementation.
 
-use crate::core::state::{AttemptOutcome, Decision, DecisionRecord, EventType, TraceEvent};
+use crate::cli::record::record_command;
+use crate::core::SessionState;
+use crate::core::context::Context;
+use crate::core::state::{
+    AttemptOutcome, DecidedBy, Decision, DecisionRecord, EventType, SessionActivity, TraceEvent,
+};
 use crate::error::{Error, Result};
-use crate::storage::MessageStore;
 use crate::storage::file::{FileBackend, get_roz_home};
-use chrono::Utc;
+use crate::storage::{MessageStore, UnknownDecision};
+use chrono::{DateTime, Utc};
 use serde_json::json;
-use uuid::Uuid;
-
-/// Maximum number of decision history entries to keep.
-const MAX_DECISION_HISTORY: usize = 50;
+use std::path::Path;
 
 /// Run the decide command.
 ///
@@ -27,12 +29,91 @@ pub fn run(
     opinions: Option<&str>,
 ) -> Result<()> {
     let store = FileBackend::new(get_roz_home())?;
+    let cwd = std::env::current_dir().ok();
 
-    let mut state = store
-        .get_session(session_id)?
-        .ok_or_else(|| Error::SessionNotFound(session_id.to_string()))?;
+    let result = record_decision(&store, session_id, decision, summary, message, opinions);
+    if matches!(result, Err(Error::SessionNotFound(_))) {
+        record_unknown_session(&store, session_id, cwd.as_deref(), &Context::system());
+    }
+    record_command(
+        "decide",
+        session_id,
+        json!({
+            "session_id": session_id,
+            "decision": decision,
+            "summary": summary,
+            "message": message,
+            "opinions": opinions,
+            "cwd": cwd,
+        }),
+        &result,
+    );
+    let decision_upper = result?;
+
+    println!("Decision recorded: {decision_upper} for session {session_id}");
+    Ok(())
+}
+
+/// Record a decision for a session in the given store.
+///
+/// Returns the normalized (uppercase) decision type. A decision posted against
+/// an unknown session ID fails with `SessionNotFound`; pass the attempt to
+/// [`record_unknown_session`] so the subagent-stop hook can classify the
+/// review attempt as `BadSessionId`.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails, the session is not found,
+/// or the decision type is invalid.
+pub fn record_decision(
+    store: &dyn MessageStore,
+    session_id: &str,
+    decision: &str,
+    summary: &str,
+    message: Option<&str>,
+    opinions: Option<&str>,
+) -> Result<String> {
+    record_decision_with_context(
+        store,
+        session_id,
+        decision,
+        summary,
+        message,
+        opinions,
+        &Context::system(),
+    )
+}
+
+/// Record a decision with an explicit clock and randomness source.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails, the session is not found,
+/// or the decision type is invalid.
+pub fn record_decision_with_context(
+    store: &dyn MessageStore,
+    session_id: &str,
+    decision: &str,
+    summary: &str,
+    message: Option<&str>,
+    opinions: Option<&str>,
+    ctx: &Context,
+) -> Result<String> {
+    let now = ctx.now();
+
+    let mut state = match store.get_session(session_id) {
+        Ok(Some(s)) => s,
+        Ok(None) | Err(Error::InvalidState(_)) => {
+            return Err(Error::SessionNotFound(session_id.to_string()));
+        }
+        Err(e) => return Err(e),
+    };
+
+    // Until the subagent-stop hook has checked its evidence, a COMPLETE can
+    // still be rejected; keep everything it is about to reset
+    let mut before = Box::new(state.review.clone());
+    before.before_complete = None;
 
-    let now = Utc::now();
     let decision_upper = decision.to_uppercase();
 
     let new_decision = match decision_upper.as_str() {
@@ -47,17 +128,6 @@ pub fn run(
         other => return Err(Error::InvalidDecision(other.to_string())),
     };
 
-    // Preserve history (capped to prevent unbounded growth)
-    state.review.decision_history.push(DecisionRecord {
-        decision: state.review.decision.clone(),
-        timestamp: now,
-    });
-    // Keep only the most recent entries if over limit
-    if state.review.decision_history.len() > MAX_DECISION_HISTORY {
-        let excess = state.review.decision_history.len() - MAX_DECISION_HISTORY;
-        state.review.decision_history.drain(0..excess);
-    }
-
     // Add trace event
     let mut payload = json!({
         "decision": decision_upper,
@@ -67,40 +137,170 @@ pub fn run(
         payload["second_opinions"] = json!(ops);
     }
     state.trace.push(TraceEvent {
-        id: Uuid::new_v4().to_string(),
+        id: ctx.generate_id(),
         timestamp: now,
         event_type: EventType::RozDecision,
         payload,
     });
 
-    // Track when gate was approved (for approval scope tracking)
-    if decision_upper == "COMPLETE" {
-        state.review.gate_approved_at = Some(now);
+    // Resolve the latest attempt (for stats tracking). An attempt already
+    // classified as NoDecision/BadSessionId is upgraded: the subagent-stop hook
+    // blocked the reviewer, and it recovered and posted a decision.
+    if let Some(attempt) = state.review.attempts.last_mut() {
+        if matches!(
+            attempt.outcome,
+            AttemptOutcome::Pending | AttemptOutcome::NoDecision | AttemptOutcome::BadSessionId
+        ) {
+            attempt.outcome = AttemptOutcome::Success {
+                decision_type: decision_upper.to_lowercase(),
+                blocks_needed: state.review.block_count,
+            };
+        }
     }
 
-    // Update the most recent pending attempt's outcome (for stats tracking)
-    if let Some(attempt) = state
-        .review
-        .attempts
-        .iter_mut()
-        .rev()
-        .find(|a| matches!(a.outcome, AttemptOutcome::Pending))
-    {
-        attempt.outcome = AttemptOutcome::Success {
-            decision_type: decision_upper.to_lowercase(),
-            blocks_needed: state.review.block_count,
-        };
+    let complete = matches!(new_decision, Decision::Complete { .. });
+    apply_decision(&mut state, new_decision, DecidedBy::Reviewer, now);
+    if complete {
+        state.review.before_complete = Some(before);
     }
+    store.put_session(&state)?;
 
-    state.review.decision = new_decision;
-    state.updated_at = now;
+    Ok(decision_upper)
+}
+
+/// Remember a decision posted against an unknown session ID from `cwd` (the
+/// reviewer's working directory). Failures are only warned about.
+pub fn record_unknown_session(
+    store: &dyn MessageStore,
+    session_id: &str,
+    cwd: Option<&Path>,
+    ctx: &Context,
+) {
+    let record = UnknownDecision {
+        session_id: session_id.to_string(),
+        timestamp: ctx.now(),
+        cwd: cwd.map(Path::to_path_buf),
+    };
+    if let Err(e) = store.record_unknown_decision(&record) {
+        eprintln!("roz: warning: failed to record unknown session: {e}");
+    }
+}
 
+/// Record a human override (`roz approve` / `roz reject`) for a session.
+///
+/// The decision is marked `decided_by: human` and honored by the hooks exactly
+/// like a reviewer's. Review is re-enabled and a tripped circuit breaker is
+/// cleared, so a rejection blocks the agent even after the breaker gave up.
+/// Pending review attempts are marked `Overridden`.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails or the session is not found.
+pub fn record_human_decision(
+    store: &dyn MessageStore,
+    session_id: &str,
+    decision: Decision,
+) -> Result<()> {
+    record_human_decision_with_context(store, session_id, decision, &Context::system())
+}
+
+/// Record a human override with an explicit clock and randomness source.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails or the session is not found.
+pub fn record_human_decision_with_context(
+    store: &dyn MessageStore,
+    session_id: &str,
+    decision: Decision,
+    ctx: &Context,
+) -> Result<()> {
+    let mut state = store
+        .get_session(session_id)?
+        .ok_or_else(|| Error::SessionNotFound(session_id.to_string()))?;
+
+    let now = ctx.now();
+
+    let payload = match &decision {
+        Decision::Complete { summary, .. } => json!({
+            "decision": "COMPLETE",
+            "reason": summary,
+            "decided_by": DecidedBy::Human,
+        }),
+        Decision::Issues { summary, .. } => json!({
+            "decision": "ISSUES",
+            "message": summary,
+            "decided_by": DecidedBy::Human,
+        }),
+        Decision::Pending => {
+            return Err(Error::InvalidDecision("PENDING".to_string()));
+        }
+    };
+    state.trace.push(TraceEvent {
+        id: ctx.generate_id(),
+        timestamp: now,
+        event_type: EventType::HumanDecision,
+        payload,
+    });
+
+    for attempt in &mut state.review.attempts {
+        if attempt.outcome == AttemptOutcome::Pending {
+            attempt.outcome = AttemptOutcome::Overridden;
+        }
+    }
+
+    state.review.enabled = true;
+    state.review.circuit_breaker_tripped = false;
+    state.review.circuit_breaker_tripped_at = None;
+    state.review.trip_cause = None;
+    state.review.block_count = 0;
+    state.review.issues_blocks = 0;
+    state.review.gate_tripped_at.clear();
+
+    apply_decision(&mut state, decision, DecidedBy::Human, now);
     store.put_session(&state)?;
 
-    println!("Decision recorded: {decision_upper} for session {session_id}");
     Ok(())
 }
 
+/// Replace the current decision, moving the previous one into history.
+fn apply_decision(
+    state: &mut SessionState,
+    decision: Decision,
+    decided_by: DecidedBy,
+    now: DateTime<Utc>,
+) {
+    // Preserve history (capped to prevent unbounded growth)
+    let record = DecisionRecord {
+        decision: state.review.decision.clone(),
+        timestamp: now,
+        decided_by: state.review.decided_by,
+        rejected: false,
+    };
+    state.review.push_decision_record(record);
+    state.review.before_complete = None;
+
+    // The reviewer (or a human) showed up, so stops and gate denials are no
+    // longer "not spawned"
+    state.review.not_spawned_blocks = 0;
+    state.review.gate_denials.clear();
+
+    // Track when gate was approved (for approval scope tracking). Approval
+    // also ends the issues loop and the breaker's backoff, and starts the
+    // risk threshold counts over.
+    if matches!(decision, Decision::Complete { .. }) {
+        state.review.gate_approved_at = Some(now);
+        state.review.issues_blocks = 0;
+        state.review.trip_count = 0;
+        state.review.gate_tripped_at.clear();
+        state.review.activity = SessionActivity::default();
+    }
+
+    state.review.decision = decision;
+    state.review.decided_by = Some(decided_by);
+    state.updated_at = now;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,6 +339,8 @@ mod tests {
         state.review.decision_history.push(DecisionRecord {
             decision: state.review.decision.clone(),
             timestamp: now,
+            decided_by: None,
+            rejected: false,
         });
         state.review.decision = Decision::Complete {
             summary: "All good".to_string(),
@@ -162,6 +364,8 @@ mod tests {
         state.review.decision_history.push(DecisionRecord {
             decision: state.review.decision.clone(),
             timestamp: now,
+            decided_by: None,
+            rejected: false,
         });
         state.review.decision = Decision::Complete {
             summary: "Verified correct".to_string(),
@@ -197,6 +401,8 @@ mod tests {
         state.review.decision_history.push(DecisionRecord {
             decision: state.review.decision.clone(),
             timestamp: now,
+            decided_by: None,
+            rejected: false,
         });
         state.review.decision = Decision::Issues {
             summary: "Found bugs".to_string(),
@@ -230,6 +436,8 @@ mod tests {
         state.review.decision_history.push(DecisionRecord {
             decision: state.review.decision.clone(),
             timestamp: now,
+            decided_by: None,
+            rejected: false,
         });
         state.review.decision = Decision::Complete {
             summary: "First review".to_string(),
@@ -262,6 +470,8 @@ mod tests {
         state.review.decision_history.push(DecisionRecord {
             decision: state.review.decision.clone(),
             timestamp: now,
+            decided_by: None,
+            rejected: false,
         });
         state.review.gate_approved_at = Some(now);
 
@@ -314,6 +524,8 @@ mod tests {
         state.review.decision_history.push(DecisionRecord {
             decision: state.review.decision.clone(),
             timestamp: now,
+            decided_by: None,
+            rejected: false,
         });
 
         // Update the most recent pending attempt's outcome
@@ -350,4 +562,185 @@ mod tests {
             other => panic!("Expected Success outcome, got {other:?}"),
         }
     }
+
+    #[test]
+    fn record_decision_resolves_latest_attempt() {
+        let store = MemoryBackend::new();
+        create_test_session_with_attempt(&store, "test-record", 2);
+
+        let decision =
+            record_decision(&store, "test-record", "complete", "All good", None, None).unwrap();
+        assert_eq!(decision, "COMPLETE");
+
+        let updated = store.get_session("test-record").unwrap().unwrap();
+        assert!(matches!(updated.review.decision, Decision::Complete { .. }));
+        assert!(updated.review.gate_approved_at.is_some());
+        assert_eq!(
+            updated.review.attempts[0].outcome,
+            AttemptOutcome::Success {
+                decision_type: "complete".to_string(),
+                blocks_needed: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn record_decision_upgrades_no_decision_attempt() {
+        let store = MemoryBackend::new();
+        create_test_session_with_attempt(&store, "test-recover", 1);
+        let mut state = store.get_session("test-recover").unwrap().unwrap();
+        state.review.attempts[0].outcome = AttemptOutcome::NoDecision;
+        store.put_session(&state).unwrap();
+
+        record_decision(&store, "test-recover", "ISSUES", "Bugs", Some("Fix"), None).unwrap();
+
+        let updated = store.get_session("test-recover").unwrap().unwrap();
+        assert!(matches!(
+            updated.review.attempts[0].outcome,
+            AttemptOutcome::Success { .. }
+        ));
+    }
+
+    #[test]
+    fn record_decision_unknown_session_is_remembered_with_its_cwd() {
+        let store = MemoryBackend::new();
+        let before = Utc::now();
+
+        let result = record_decision(&store, "no-such-session", "COMPLETE", "ok", None, None);
+        assert!(matches!(result, Err(Error::SessionNotFound(_))));
+        // The library leaves the directory to the caller
+        assert!(store.unknown_decisions_since(before).unwrap().is_empty());
+
+        let cwd = Path::new("/work/project");
+        record_unknown_session(&store, "no-such-session", Some(cwd), &Context::system());
+        let unknown = store.unknown_decisions_since(before).unwrap();
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0].session_id, "no-such-session");
+        assert_eq!(unknown[0].cwd.as_deref(), Some(cwd));
+    }
+
+    #[test]
+    fn record_decision_invalid_type() {
+        let store = MemoryBackend::new();
+        create_test_session(&store, "test-invalid");
+
+        let result = record_decision(&store, "test-invalid", "MAYBE", "hmm", None, None);
+        assert!(matches!(result, Err(Error::InvalidDecision(_))));
+    }
+
+    #[test]
+    fn record_decision_marks_reviewer() {
+        let store = MemoryBackend::new();
+        create_test_session(&store, "test-reviewer");
+
+        record_decision(&store, "test-reviewer", "COMPLETE", "ok", None, None).unwrap();
+
+        let updated = store.get_session("test-reviewer").unwrap().unwrap();
+        assert_eq!(updated.review.decided_by, Some(DecidedBy::Reviewer));
+    }
+
+    #[test]
+    fn reviewer_decision_clears_gate_denials() {
+        let store = MemoryBackend::new();
+        let mut state = SessionState::new("test-gates");
+        state.review.enabled = true;
+        state
+            .review
+            .gate_denials
+            .insert("Bash:git push*".to_string(), 2);
+        state
+            .review
+            .gate_tripped_at
+            .insert("Bash:gh pr merge*".to_string(), Utc::now());
+        store.put_session(&state).unwrap();
+
+        record_decision(&store, "test-gates", "ISSUES", "fix it", None, None).unwrap();
+        let updated = store.get_session("test-gates").unwrap().unwrap();
+        assert!(updated.review.gate_denials.is_empty());
+        assert_eq!(updated.review.gate_tripped_at.len(), 1);
+
+        record_decision(&store, "test-gates", "COMPLETE", "ok", None, None).unwrap();
+        let updated = store.get_session("test-gates").unwrap().unwrap();
+        assert!(updated.review.gate_tripped_at.is_empty());
+    }
+
+    #[test]
+    fn human_decision_marked_and_audited() {
+        let store = MemoryBackend::new();
+        create_test_session_with_attempt(&store, "test-human", 3);
+        let mut state = store.get_session("test-human").unwrap().unwrap();
+        state.review.circuit_breaker_tripped = true;
+        state.review.circuit_breaker_tripped_at = Some(Utc::now());
+        state.review.enabled = false;
+        store.put_session(&state).unwrap();
+
+        record_human_decision(
+            &store,
+            "test-human",
+            Decision::Issues {
+                summary: "Tests are missing".to_string(),
+                message_to_agent: Some("Tests are missing".to_string()),
+            },
+        )
+        .unwrap();
+
+        let updated = store.get_session("test-human").unwrap().unwrap();
+        assert_eq!(updated.review.decided_by, Some(DecidedBy::Human));
+        assert!(updated.review.enabled);
+        assert!(!updated.review.circuit_breaker_tripped);
+        assert_eq!(updated.review.block_count, 0);
+        assert_eq!(
+            updated.review.attempts[0].outcome,
+            AttemptOutcome::Overridden
+        );
+        assert_eq!(updated.review.decision_history.len(), 1);
+
+        let event = updated.trace.last().unwrap();
+        assert_eq!(event.event_type, EventType::HumanDecision);
+        assert_eq!(event.payload["decided_by"], "human");
+        assert_eq!(event.payload["message"], "Tests are missing");
+    }
+
+    #[test]
+    fn human_decision_history_keeps_previous_decider() {
+        let store = MemoryBackend::new();
+        create_test_session(&store, "test-history-decider");
+        record_decision(&store, "test-history-decider", "ISSUES", "Bugs", None, None).unwrap();
+
+        record_human_decision(
+            &store,
+            "test-history-decider",
+            Decision::Complete {
+                summary: "Reviewed by hand".to_string(),
+                second_opinions: None,
+            },
+        )
+        .unwrap();
+
+        let updated = store.get_session("test-history-decider").unwrap().unwrap();
+        let last = updated.review.decision_history.last().unwrap();
+        assert!(matches!(last.decision, Decision::Issues { .. }));
+        assert_eq!(last.decided_by, Some(DecidedBy::Reviewer));
+        assert!(updated.review.gate_approved_at.is_some());
+    }
+
+    #[test]
+    fn human_decision_unknown_session_errors() {
+        let store = MemoryBackend::new();
+        let result = record_human_decision(
+            &store,
+            "missing",
+            Decision::Complete {
+                summary: "ok".to_string(),
+                second_opinions: None,
+            },
+        );
+        assert!(matches!(result, Err(Error::SessionNotFound(_))));
+        assert!(
+            store
+                .unknown_decisions_since(Utc::now() - chrono::Duration::hours(1))
+                .unwrap()
+                .is_empty()
+        );
+    }
 }
@@ -0,0 +1,749 @@
+//! `roz doctor` command implementation.
+//!
+//! Hooks fail open, so a broken install looks like a working one. Doctor
+//! checks the pieces a working install needs and runs a synthetic session
+//! through `dispatch_hook` to prove the stop hook actually blocks.
+
+use crate::cli::decide::record_decision;
+use crate::cli::format::{CsvRecord, OutputFormat, print_machine};
+use crate::cli::install::{
+    InstallScope, gate_matchers, installed_events, installed_matchers, plugin_matchers,
+    read_settings, required_events,
+};
+use crate::config::{Config, EDIT_TOOLS, ReviewMode, load_config};
+use crate::error::{Error, Result};
+use crate::hooks::{HookDecision, HookInput, dispatch_hook};
+use crate::storage::MemoryBackend;
+use crate::storage::file::get_roz_home;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+/// Maximum directory depth searched for an installed plugin.
+const PLUGIN_SEARCH_DEPTH: usize = 6;
+
+/// Age after which a `.tmp` file is treated as left over rather than a write
+/// still in progress.
+const STALE_TMP_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Result of a single check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    /// Working as expected.
+    Ok,
+
+    /// Works, but something looks off.
+    Warn,
+
+    /// Broken: roz will not gate correctly.
+    Fail,
+}
+
+/// A single doctor check.
+#[derive(Debug, Serialize)]
+pub struct Check {
+    /// Check name.
+    pub name: &'static str,
+
+    /// Outcome.
+    pub status: CheckStatus,
+
+    /// What was found.
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+impl CsvRecord for Check {
+    const HEADERS: &'static [&'static str] = &["name", "status", "detail"];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.name.to_string(),
+            status_label(self.status).to_lowercase(),
+            self.detail.clone(),
+        ]
+    }
+}
+
+/// Results of all doctor checks.
+#[derive(Debug, Serialize)]
+pub struct DoctorReport {
+    /// Checks, in the order they ran.
+    pub checks: Vec<Check>,
+}
+
+impl DoctorReport {
+    /// Number of failed checks.
+    #[must_use]
+    pub fn failures(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Fail)
+            .count()
+    }
+}
+
+/// Run the doctor command.
+///
+/// # Errors
+///
+/// Returns an error if any check fails, so scripts can rely on the exit code.
+pub fn run(format: OutputFormat) -> Result<()> {
+    let report = collect();
+
+    if !print_machine(format, &report, &report.checks)? {
+        for check in &report.checks {
+            println!(
+                "[{:<4}] {:<16} {}",
+                status_label(check.status),
+                check.name,
+                check.detail
+            );
+        }
+    }
+
+    match report.failures() {
+        0 => Ok(()),
+        n => Err(Error::InvalidState(format!(
+            "roz doctor: {n} check(s) failed"
+        ))),
+    }
+}
+
+/// Run every check.
+#[must_use]
+pub fn collect() -> DoctorReport {
+    let roz_home = get_roz_home();
+    let config = load_config();
+    let plugin = find_plugin();
+
+    let mut checks = vec![
+        check_binary(),
+        check_plugin_version(plugin.as_ref()),
+        check_config(&config),
+    ];
+
+    let config = config.unwrap_or_default();
+    checks.push(check_hooks(plugin.is_some(), &config));
+    checks.push(check_storage(&roz_home));
+    checks.push(check_templates(&config, &roz_home));
+    checks.push(check_stale_tmp(&roz_home));
+    checks.push(check_second_opinions(&config));
+    checks.push(check_synthetic_session(&config));
+
+    DoctorReport { checks }
+}
+
+/// Check that `roz` on PATH is this version.
+fn check_binary() -> Check {
+    const NAME: &str = "binary";
+    let version = env!("CARGO_PKG_VERSION");
+
+    let Some(path) = find_in_path("roz") else {
+        return Check::new(
+            NAME,
+            CheckStatus::Fail,
+            "roz is not on PATH; hooks run `roz hook ...` and will not find it",
+        );
+    };
+
+    let output = Command::new(&path).arg("--version").output();
+    match output {
+        Ok(o) if String::from_utf8_lossy(&o.stdout).contains(version) => Check::new(
+            NAME,
+            CheckStatus::Ok,
+            format!("{} ({version})", path.display()),
+        ),
+        Ok(o) => Check::new(
+            NAME,
+            CheckStatus::Warn,
+            format!(
+                "{} reports `{}`, this binary is {version}",
+                path.display(),
+                String::from_utf8_lossy(&o.stdout).trim()
+            ),
+        ),
+        Err(e) => Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} cannot be run: {e}", path.display()),
+        ),
+    }
+}
+
+/// Check that the installed plugin matches this binary's version.
+fn check_plugin_version(plugin: Option<&(PathBuf, String)>) -> Check {
+    const NAME: &str = "plugin";
+    let version = env!("CARGO_PKG_VERSION");
+
+    match plugin {
+        None => Check::new(
+            NAME,
+            CheckStatus::Ok,
+            "not installed (using settings hooks)",
+        ),
+        Some((path, v)) if v == version => {
+            Check::new(NAME, CheckStatus::Ok, format!("{v} at {}", path.display()))
+        }
+        Some((path, v)) => Check::new(
+            NAME,
+            CheckStatus::Warn,
+            format!(
+                "plugin {v} at {} does not match binary {version}",
+                path.display()
+            ),
+        ),
+    }
+}
+
+/// Check that the config file parses.
+fn check_config(config: &Result<Config>) -> Check {
+    const NAME: &str = "config";
+    match config {
+        Ok(c) => Check::new(
+            NAME,
+            CheckStatus::Ok,
+            format!(
+                "review.mode = {:?}, {} gate pattern(s)",
+                c.review.mode,
+                c.review.gates.tools.len()
+            )
+            .to_lowercase(),
+        ),
+        Err(e) => Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{e} (hooks fall back to defaults)"),
+        ),
+    }
+}
+
+/// Check that hooks are registered via the plugin or settings.json.
+fn check_hooks(plugin_installed: bool, config: &Config) -> Check {
+    const NAME: &str = "hooks";
+
+    let mut sources = Vec::new();
+    let mut events: Vec<String> = Vec::new();
+    let mut matchers: Vec<String> = Vec::new();
+
+    if plugin_installed {
+        sources.push("plugin".to_string());
+        events.extend(required_events().unwrap_or_default());
+        events.push("PreToolUse".to_string());
+        matchers.extend(plugin_matchers());
+    }
+
+    let mut paths: Vec<PathBuf> = [InstallScope::User, InstallScope::Project]
+        .into_iter()
+        .filter_map(|scope| scope.settings_path().ok())
+        .collect();
+    paths.dedup();
+
+    for path in paths {
+        match read_settings(&path) {
+            Ok(Some(settings)) => {
+                let installed = installed_events(&settings);
+                if !installed.is_empty() {
+                    sources.push(path.display().to_string());
+                    events.extend(installed);
+                    matchers.extend(installed_matchers(&settings));
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                return Check::new(NAME, CheckStatus::Fail, e.to_string());
+            }
+        }
+    }
+
+    if sources.is_empty() {
+        return Check::new(
+            NAME,
+            CheckStatus::Fail,
+            "no roz hooks registered; install the plugin or run `roz install`",
+        );
+    }
+
+    let missing: Vec<String> = required_events()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|e| !events.contains(e))
+        .collect();
+    if !missing.is_empty() {
+        return Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("missing {} in {}", missing.join(", "), sources.join(", ")),
+        );
+    }
+
+    let uncovered = uncovered_tools(config, &matchers);
+    if !uncovered.is_empty() {
+        // Running `roz install` on top of the plugin would register every hook twice
+        let fix = if plugin_installed {
+            "update the plugin, or uninstall it and use `roz install`"
+        } else {
+            "re-run `roz install`"
+        };
+        let edits: Vec<&str> = uncovered
+            .iter()
+            .map(String::as_str)
+            .filter(|m| EDIT_TOOLS.contains(m))
+            .collect();
+        let detail = if edits.is_empty() {
+            format!(
+                "no PreToolUse matcher for gated or risk-watched tool(s) {}; {fix}",
+                uncovered.join(", ")
+            )
+        } else {
+            format!(
+                "risk thresholds watch file edits but {} are not hooked, so edits are never counted; {fix}",
+                edits.join(", ")
+            )
+        };
+        return Check::new(NAME, CheckStatus::Warn, detail);
+    }
+
+    if sources.len() > 1 {
+        return Check::new(
+            NAME,
+            CheckStatus::Warn,
+            format!(
+                "registered more than once ({}); hooks will run twice",
+                sources.join(", ")
+            ),
+        );
+    }
+
+    Check::new(
+        NAME,
+        CheckStatus::Ok,
+        format!("registered via {}", sources[0]),
+    )
+}
+
+/// `PreToolUse` matchers the config needs (gates and risk-watched tools) that
+/// none of the registered `matchers` covers.
+fn uncovered_tools(config: &Config, matchers: &[String]) -> Vec<String> {
+    gate_matchers(&config.review.hooked_tools())
+        .into_iter()
+        .filter(|needed| !matchers.iter().any(|m| matcher_covers(m, needed)))
+        .collect()
+}
+
+/// Whether the registered matcher `matcher` fires for everything `needed`
+/// matches: the same matcher, or a regex such as `Edit|Write` that matches
+/// `needed` as a tool name.
+fn matcher_covers(matcher: &str, needed: &str) -> bool {
+    matcher == needed || Regex::new(&format!("^(?:{matcher})$")).is_ok_and(|re| re.is_match(needed))
+}
+
+/// Check that the storage directory is writable.
+fn check_storage(roz_home: &Path) -> Check {
+    const NAME: &str = "storage";
+    let sessions = roz_home.join("sessions");
+    let probe = sessions.join(".doctor-probe");
+
+    let result = fs::create_dir_all(&sessions)
+        .and_then(|()| fs::write(&probe, b"ok"))
+        .and_then(|()| fs::remove_file(&probe));
+
+    match result {
+        Ok(()) => Check::new(
+            NAME,
+            CheckStatus::Ok,
+            format!("{} is writable", sessions.display()),
+        ),
+        Err(e) => Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} is not writable: {e}", sessions.display()),
+        ),
+    }
+}
+
+/// Check that templates referenced by the config exist.
+fn check_templates(config: &Config, roz_home: &Path) -> Check {
+    const NAME: &str = "templates";
+    let templates = &config.templates;
+
+    let referenced: Vec<&String> = if templates.active == "random" {
+        templates
+            .weights
+            .iter()
+            .filter(|(_, w)| **w > 0)
+            .map(|(id, _)| id)
+            .collect()
+    } else {
+        vec![&templates.active]
+    };
+
+    let dir = roz_home.join("templates");
+    let missing: Vec<String> = referenced
+        .iter()
+        // "default" is the built-in template; a file only overrides it
+        .filter(|id| id.as_str() != "default")
+        .filter(|id| !dir.join(format!("block-{id}.md")).exists())
+        .map(|id| (*id).clone())
+        .collect();
+
+    if missing.is_empty() {
+        Check::new(
+            NAME,
+            CheckStatus::Ok,
+            format!("{} referenced template(s) available", referenced.len()),
+        )
+    } else {
+        Check::new(
+            NAME,
+            CheckStatus::Warn,
+            format!(
+                "missing block-{{{}}}.md in {}; the built-in default is used instead",
+                missing.join(","),
+                dir.display()
+            ),
+        )
+    }
+}
+
+/// Check for leftover `.tmp` files from interrupted writes.
+///
+/// Sessions are written under `sessions/`, while the incident and unknown
+/// decision logs are rewritten in the roz home itself, so both are scanned.
+fn check_stale_tmp(roz_home: &Path) -> Check {
+    const NAME: &str = "temp files";
+    let stale = stale_tmp_files(roz_home, SystemTime::now());
+
+    if stale.is_empty() {
+        Check::new(NAME, CheckStatus::Ok, "no stale .tmp files")
+    } else {
+        Check::new(
+            NAME,
+            CheckStatus::Warn,
+            format!(
+                "{} stale .tmp file(s) in {} (safe to delete)",
+                stale.len(),
+                roz_home.display()
+            ),
+        )
+    }
+}
+
+/// `.tmp` files in the roz home and its sessions directory that were last
+/// modified more than [`STALE_TMP_AGE`] before `now`.
+fn stale_tmp_files(roz_home: &Path, now: SystemTime) -> Vec<PathBuf> {
+    [roz_home.to_path_buf(), roz_home.join("sessions")]
+        .iter()
+        .flat_map(|dir| fs::read_dir(dir).into_iter().flatten())
+        .filter_map(std::result::Result::ok)
+        .filter(|entry| entry.path().extension().is_some_and(|e| e == "tmp"))
+        .filter(|entry| {
+            entry
+                .metadata()
+                .and_then(|meta| meta.modified())
+                .is_ok_and(|modified| {
+                    now.duration_since(modified)
+                        .is_ok_and(|age| age > STALE_TMP_AGE)
+                })
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// Report which second-opinion CLIs are available.
+fn check_second_opinions(config: &Config) -> Check {
+    const NAME: &str = "second opinion";
+    let models = &config.external_models;
+
+    let available: Vec<&str> = [models.codex.as_str(), models.gemini.as_str()]
+        .into_iter()
+        .filter(|cmd| !cmd.is_empty() && find_in_path(cmd).is_some())
+        .collect();
+
+    let detail = if available.is_empty() {
+        "none on PATH (reviewer falls back to Claude)".to_string()
+    } else {
+        format!("available: {}", available.join(", "))
+    };
+    Check::new(NAME, CheckStatus::Ok, detail)
+}
+
+/// Run a synthetic session against an in-memory store.
+fn check_synthetic_session(config: &Config) -> Check {
+    const NAME: &str = "synthetic run";
+
+    if config.review.mode == ReviewMode::Never {
+        return Check::new(
+            NAME,
+            CheckStatus::Warn,
+            "review.mode = never: the stop hook never blocks",
+        );
+    }
+
+    match run_synthetic_session(config) {
+        Ok(()) => Check::new(
+            NAME,
+            CheckStatus::Ok,
+            "stop blocked until a decision was posted",
+        ),
+        Err(e) => Check::new(NAME, CheckStatus::Fail, e),
+    }
+}
+
+/// Drive session-start → prompt → stop → decide → stop.
+///
+/// Review is forced on (`mode = always`, no opt-outs) so the run tests the
+/// blocking path whatever triggers the user configured.
+fn run_synthetic_session(config: &Config) -> std::result::Result<(), String> {
+    let mut config = config.clone();
+    config.review.mode = ReviewMode::Always;
+    config.review.opt_out.clear();
+    let config = &config;
+
+    let store = MemoryBackend::new();
+    let session_id = "roz-doctor";
+    let input = |extra: Value| -> std::result::Result<HookInput, String> {
+        let mut value = json!({ "session_id": session_id, "cwd": "/" });
+        if let (Some(base), Some(extra)) = (value.as_object_mut(), extra.as_object()) {
+            base.extend(extra.clone());
+        }
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    };
+
+    dispatch_hook("session-start", &input(json!({}))?, &store, config);
+    dispatch_hook(
+        "user-prompt",
+        &input(json!({ "prompt": "roz doctor check" }))?,
+        &store,
+        config,
+    );
+
+    let output = dispatch_hook("stop", &input(json!({}))?, &store, config);
+    if output.decision != Some(HookDecision::Block) {
+        return Err("stop hook did not block a pending review".to_string());
+    }
+
+    record_decision(&store, session_id, "COMPLETE", "doctor", None, None)
+        .map_err(|e| format!("could not record decision: {e}"))?;
+
+    let output = dispatch_hook("stop", &input(json!({}))?, &store, config);
+    if output.decision.is_some() {
+        return Err("stop hook still blocked after COMPLETE".to_string());
+    }
+    Ok(())
+}
+
+/// Find the installed roz plugin's `plugin.json` under `~/.claude/plugins`.
+fn find_plugin() -> Option<(PathBuf, String)> {
+    let root = dirs::home_dir()?.join(".claude").join("plugins");
+    let mut found = Vec::new();
+    search_plugin_manifests(&root, PLUGIN_SEARCH_DEPTH, &mut found);
+    // Prefer a manifest matching this binary if several versions are cached
+    let version = env!("CARGO_PKG_VERSION");
+    found
+        .iter()
+        .find(|(_, v)| v == version)
+        .or_else(|| found.first())
+        .cloned()
+}
+
+/// Recursively collect roz `plugin.json` manifests.
+fn search_plugin_manifests(dir: &Path, depth: usize, found: &mut Vec<(PathBuf, String)>) {
+    let manifest = dir.join(".claude-plugin").join("plugin.json");
+    if let Ok(contents) = fs::read_to_string(&manifest) {
+        if let Ok(value) = serde_json::from_str::<Value>(&contents) {
+            if value.get("name").and_then(Value::as_str) == Some("roz") {
+                let version = value
+                    .get("version")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown")
+                    .to_string();
+                found.push((dir.to_path_buf(), version));
+                return;
+            }
+        }
+    }
+
+    if depth == 0 {
+        return;
+    }
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            search_plugin_manifests(&path, depth - 1, found);
+        }
+    }
+}
+
+/// Find an executable on PATH.
+fn find_in_path(cmd: &str) -> Option<PathBuf> {
+    let cmd_path = Path::new(cmd);
+    if cmd_path.components().count() > 1 {
+        return cmd_path.is_file().then(|| cmd_path.to_path_buf());
+    }
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(cmd))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Display label for a status.
+fn status_label(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Ok => "OK",
+        CheckStatus::Warn => "WARN",
+        CheckStatus::Fail => "FAIL",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn synthetic_session_blocks_with_default_config() {
+        assert_eq!(run_synthetic_session(&Config::default()), Ok(()));
+    }
+
+    #[test]
+    fn synthetic_session_ignores_custom_triggers_and_opt_outs() {
+        let config: Config = toml::from_str(
+            r#"
+            [review]
+            triggers = [{ keyword = "deploy" }]
+            opt_out = ["doctor"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(run_synthetic_session(&config), Ok(()));
+    }
+
+    #[test]
+    fn risk_thresholds_need_the_edit_tools_hooked() {
+        let config: Config = toml::from_str(
+            r#"
+            [review.gates]
+            tools = ["mcp__tissue__close*", "Bash:gh pr merge*"]
+
+            [review.risk]
+            files_changed = 10
+            "#,
+        )
+        .unwrap();
+
+        // The plugin's single Edit|MultiEdit|Write|NotebookEdit matcher covers them
+        assert!(uncovered_tools(&config, &plugin_matchers()).is_empty());
+
+        let gates_only = ["mcp__tissue__.*".to_string(), "Bash".to_string()];
+        assert_eq!(
+            uncovered_tools(&config, &gates_only),
+            ["Edit", "MultiEdit", "Write", "NotebookEdit"]
+        );
+        assert!(uncovered_tools(&Config::default(), &gates_only).is_empty());
+    }
+
+    #[test]
+    fn synthetic_session_detects_disabled_blocking() {
+        let mut config = Config::default();
+        config.circuit_breaker.max_blocks = 0;
+        assert!(run_synthetic_session(&config).is_err());
+    }
+
+    #[test]
+    fn synthetic_check_warns_when_review_never() {
+        let mut config = Config::default();
+        config.review.mode = ReviewMode::Never;
+        assert_eq!(check_synthetic_session(&config).status, CheckStatus::Warn);
+    }
+
+    #[test]
+    fn storage_check_writable_dir() {
+        let dir = TempDir::new().unwrap();
+        let check = check_storage(dir.path());
+        assert_eq!(check.status, CheckStatus::Ok);
+        assert!(!dir.path().join("sessions").join(".doctor-probe").exists());
+    }
+
+    #[test]
+    fn stale_tmp_files_reported() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(check_stale_tmp(dir.path()).status, CheckStatus::Ok);
+
+        let sessions = dir.path().join("sessions");
+        fs::create_dir_all(&sessions).unwrap();
+        fs::write(sessions.join("abc.tmp"), "{}").unwrap();
+        fs::write(dir.path().join("incidents.tmp"), "").unwrap();
+        fs::write(dir.path().join("unknown_decisions.tmp"), "").unwrap();
+
+        // Fresh files may still be mid-write
+        assert_eq!(check_stale_tmp(dir.path()).status, CheckStatus::Ok);
+
+        let later = SystemTime::now() + STALE_TMP_AGE + Duration::from_secs(1);
+        assert_eq!(stale_tmp_files(dir.path(), later).len(), 3);
+
+        let old = SystemTime::now() - STALE_TMP_AGE - Duration::from_secs(1);
+        fs::File::options()
+            .write(true)
+            .open(dir.path().join("incidents.tmp"))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        let check = check_stale_tmp(dir.path());
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(check.detail.contains("1 stale"));
+    }
+
+    #[test]
+    fn templates_check_reports_missing_weights() {
+        let dir = TempDir::new().unwrap();
+        let templates = dir.path().join("templates");
+        fs::create_dir_all(&templates).unwrap();
+        fs::write(templates.join("block-v1.md"), "x").unwrap();
+
+        let mut config = Config::default();
+        config.templates.active = "random".to_string();
+        config.templates.weights = [("v1".to_string(), 50), ("v2".to_string(), 50)]
+            .into_iter()
+            .collect();
+
+        let check = check_templates(&config, dir.path());
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(check.detail.contains("v2"));
+        assert!(!check.detail.contains("v1"));
+    }
+
+    #[test]
+    fn plugin_manifest_found_by_name() {
+        let dir = TempDir::new().unwrap();
+        let plugin = dir.path().join("cache").join("roz").join(".claude-plugin");
+        fs::create_dir_all(&plugin).unwrap();
+        fs::write(
+            plugin.join("plugin.json"),
+            r#"{"name": "roz", "version": "9.9.9"}"#,
+        )
+        .unwrap();
+
+        let mut found = Vec::new();
+        search_plugin_manifests(dir.path(), PLUGIN_SEARCH_DEPTH, &mut found);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].1, "9.9.9");
+
+        let check = check_plugin_version(found.first());
+        assert_eq!(check.status, CheckStatus::Warn);
+    }
+}
@@ -0,0 +1,198 @@
+//! Output formats for user-facing commands.
+//!
+//! Every reporting command renders one of four formats:
+//!
+//! - `table` - human-readable text (default)
+//! - `json` - a single pretty-printed object with a `schema_version` field
+//! - `jsonl` - one compact JSON object per record, no envelope
+//! - `csv` - a header row followed by one row per record (RFC 4180 quoting)
+//!
+//! The JSON shapes are documented in the README and are versioned by
+//! [`SCHEMA_VERSION`]. Fields may be added without a version bump; renaming or
+//! removing a field bumps the version.
+
+use crate::error::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Version of the JSON output schemas.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Output format for reporting commands.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable table.
+    #[default]
+    Table,
+
+    /// Single JSON document.
+    Json,
+
+    /// One JSON object per line.
+    Jsonl,
+
+    /// Comma-separated values with a header row.
+    Csv,
+}
+
+/// A record that can be written as a CSV row.
+pub trait CsvRecord {
+    /// Column names, in output order.
+    const HEADERS: &'static [&'static str];
+
+    /// Field values, in the same order as `HEADERS`.
+    fn csv_fields(&self) -> Vec<String>;
+}
+
+/// JSON envelope adding the schema version to a report.
+#[derive(Serialize)]
+struct Envelope<'a, T: Serialize> {
+    schema_version: u32,
+    #[serde(flatten)]
+    body: &'a T,
+}
+
+/// Serialize a report as pretty JSON with a `schema_version` field.
+///
+/// # Errors
+///
+/// Returns an error if serialization fails.
+pub fn to_json<T: Serialize>(body: &T) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&Envelope {
+        schema_version: SCHEMA_VERSION,
+        body,
+    })?)
+}
+
+/// Serialize records as JSON Lines (one object per line).
+///
+/// # Errors
+///
+/// Returns an error if serialization fails.
+pub fn to_jsonl<T: Serialize>(rows: &[T]) -> Result<String> {
+    let mut out = String::new();
+    for row in rows {
+        out.push_str(&serde_json::to_string(row)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Serialize records as CSV with a header row.
+#[must_use]
+pub fn to_csv<T: CsvRecord>(rows: &[T]) -> String {
+    let mut out = csv_line(T::HEADERS.iter().map(|h| (*h).to_string()));
+    for row in rows {
+        out.push_str(&csv_line(row.csv_fields()));
+    }
+    out
+}
+
+/// Format a single CSV line, quoting fields as needed.
+fn csv_line(fields: impl IntoIterator<Item = String>) -> String {
+    let mut line = fields
+        .into_iter()
+        .map(|f| csv_escape(&f))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push('\n');
+    line
+}
+
+/// Quote a CSV field if it contains a delimiter, quote, or line break.
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Print a report in a machine-readable format.
+///
+/// `body` is used for `json`, `rows` for `jsonl` and `csv`. Returns `false`
+/// for `table`, leaving human-readable rendering to the caller.
+///
+/// # Errors
+///
+/// Returns an error if serialization fails.
+pub fn print_machine<B: Serialize, R: Serialize + CsvRecord>(
+    format: OutputFormat,
+    body: &B,
+    rows: &[R],
+) -> Result<bool> {
+    match format {
+        OutputFormat::Table => return Ok(false),
+        OutputFormat::Json => println!("{}", to_json(body)?),
+        OutputFormat::Jsonl => print!("{}", to_jsonl(rows)?),
+        OutputFormat::Csv => print!("{}", to_csv(rows)),
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Row {
+        name: String,
+        count: u32,
+    }
+
+    impl CsvRecord for Row {
+        const HEADERS: &'static [&'static str] = &["name", "count"];
+
+        fn csv_fields(&self) -> Vec<String> {
+            vec![self.name.clone(), self.count.to_string()]
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Report {
+        rows: Vec<Row>,
+    }
+
+    fn rows() -> Vec<Row> {
+        vec![
+            Row {
+                name: "plain".to_string(),
+                count: 1,
+            },
+            Row {
+                name: "has, comma \"and\" quotes".to_string(),
+                count: 2,
+            },
+        ]
+    }
+
+    #[test]
+    fn json_includes_schema_version() {
+        let json = to_json(&Report { rows: rows() }).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["schema_version"], SCHEMA_VERSION);
+        assert_eq!(parsed["rows"][0]["name"], "plain");
+    }
+
+    #[test]
+    fn jsonl_one_object_per_line() {
+        let out = to_jsonl(&rows()).unwrap();
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let second: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(second["count"], 2);
+    }
+
+    #[test]
+    fn csv_header_and_quoting() {
+        let out = to_csv(&rows());
+        let expected = "name,count\nplain,1\n\"has, comma \"\"and\"\" quotes\",2\n";
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn csv_escape_line_breaks() {
+        assert_eq!(csv_escape("a\nb"), "\"a\nb\"");
+        assert_eq!(csv_escape("simple"), "simple");
+    }
+}
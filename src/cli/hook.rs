@@ -1,17 +1,25 @@
 //! `roz hook` command implementation.
 
-use crate::config::load_config;
-use crate::core::handle_pre_tool_use;
+use crate::cli::record::record_hook;
+use crate::config::{Config, apply_env_overrides, load_config};
+use crate::core::safety::{handle_error, handle_init_error};
+use crate::core::{Context, handle_pre_tool_use_with_context};
 use crate::error::Result;
-use crate::hooks::{HookInput, HookOutput, PreToolUseOutput, dispatch_hook};
+use crate::hooks::{
+    HookEvent, HookInput, HookOutput, PreToolUseEvent, PreToolUseOutput, StopEvent,
+    SubagentStopEvent, dispatch_event_with_context, dispatch_hook_with_context,
+};
 use crate::storage::file::{FileBackend, get_roz_home};
+use crate::storage::{IncidentKind, MessageStore};
 use serde::Serialize;
+use serde_json::Value;
 use std::io::{self, Read, Write};
 
 /// Run a hook command.
 ///
 /// Reads JSON from stdin, dispatches to the appropriate hook handler,
-/// and writes JSON to stdout.
+/// and writes JSON to stdout. With `[record] enabled` the stdin/stdout pair
+/// is also appended to the session's recording for `roz replay`.
 ///
 /// # Errors
 ///
@@ -21,17 +29,15 @@ pub fn run(hook_name: &str) -> Result<()> {
     let mut input_str = String::new();
     io::stdin().read_to_string(&mut input_str)?;
 
-    // Parse input
-    let input = match serde_json::from_str::<HookInput>(&input_str) {
-        Ok(i) => i,
+    // Load config first: the safety mode decides how every later error is
+    // handled. Env overrides (including ROZ_SAFETY_MODE) apply even when the
+    // config file is broken.
+    let (config, config_error) = match load_config() {
+        Ok(c) => (c, None),
         Err(e) => {
-            eprintln!("roz: warning: failed to parse input: {e}");
-            // Fail open - return approve (different for pre-tool-use)
-            return if hook_name == "pre-tool-use" {
-                write_json(&PreToolUseOutput::allow())
-            } else {
-                write_json(&HookOutput::approve())
-            };
+            let mut config = Config::default();
+            apply_env_overrides(&mut config);
+            (config, Some(format!("config error: {e}")))
         }
     };
 
@@ -39,29 +45,154 @@ pub fn run(hook_name: &str) -> Result<()> {
     let store = match FileBackend::new(get_roz_home()) {
         Ok(s) => s,
         Err(e) => {
-            eprintln!("roz: warning: storage init failed: {e}");
-            // Fail open
-            return if hook_name == "pre-tool-use" {
-                write_json(&PreToolUseOutput::allow())
-            } else {
-                write_json(&HookOutput::approve())
-            };
+            let parsed = serde_json::from_str::<HookInput>(&input_str);
+            let diagnostic = handle_init_error(
+                &get_roz_home(),
+                &config,
+                hook_name,
+                parsed.as_ref().ok().map(Into::into),
+                &format!("storage init failed: {e}"),
+                &Context::system(),
+            );
+            return write_json(&failure_output(hook_name, diagnostic)?);
+        }
+    };
+
+    let output = respond(
+        hook_name,
+        &input_str,
+        &config,
+        config_error.as_deref(),
+        &store,
+        &Context::system(),
+    )?;
+    if config.record.enabled {
+        record_hook(&get_roz_home(), hook_name, &input_str, &output);
+    }
+    write_json(&output)
+}
+
+/// Compute a hook's JSON output for raw stdin against a ready store.
+///
+/// Input naming a known `hook_event_name` is validated as a [`HookEvent`]
+/// and dispatched on the event; input without one, or naming an event this
+/// version does not know, is dispatched by `hook_name`. A Stop or
+/// `SubagentStop` event missing a required field still runs its handler, on
+/// the defaults input without an event name gets, since failing open there
+/// would skip review.
+/// `config_error` is a config load failure to report (and possibly block
+/// on) before dispatching. Shared by `roz hook` and `roz replay`, which
+/// passes a fixed clock set to each recorded step's time.
+///
+/// # Errors
+///
+/// Returns an error if the output cannot be serialized.
+pub fn respond(
+    hook_name: &str,
+    input_str: &str,
+    config: &Config,
+    config_error: Option<&str>,
+    store: &dyn MessageStore,
+    ctx: &Context,
+) -> Result<Value> {
+    // Parse input
+    let input = match serde_json::from_str::<HookInput>(input_str) {
+        Ok(i) => i,
+        Err(e) => {
+            let diagnostic = handle_error(
+                store,
+                config,
+                hook_name,
+                None,
+                IncidentKind::InputParse,
+                &format!("failed to parse input: {e}"),
+                ctx,
+            );
+            return failure_output(hook_name, diagnostic);
         }
     };
 
-    // Load config for all hooks
-    let config = load_config().unwrap_or_default();
+    // Input naming a known event is validated and dispatched on the event.
+    // Older input without one, or an event newer than this version, falls
+    // back to the hook name, so only missing required fields fail here
+    let event = match input.hook_event_name.as_deref() {
+        Some(name) if HookEvent::is_known(name) => match HookEvent::try_from(input.clone()) {
+            Ok(event) => Some(event),
+            Err(e) if name == "Stop" => {
+                eprintln!("roz: warning: invalid hook input: {e}");
+                Some(HookEvent::Stop(StopEvent::from_input(&input)))
+            }
+            Err(e) if name == "SubagentStop" => {
+                eprintln!("roz: warning: invalid hook input: {e}");
+                Some(HookEvent::SubagentStop(SubagentStopEvent::from_input(
+                    &input,
+                )))
+            }
+            Err(e) => {
+                let diagnostic = handle_error(
+                    store,
+                    config,
+                    hook_name,
+                    Some((&input).into()),
+                    IncidentKind::InputParse,
+                    &format!("invalid hook input: {e}"),
+                    ctx,
+                );
+                return failure_output(hook_name, diagnostic);
+            }
+        },
+        _ => None,
+    };
+
+    // Running with default config could skip configured gates
+    if let Some(message) = config_error {
+        let diagnostic = handle_error(
+            store,
+            config,
+            hook_name,
+            Some((&input).into()),
+            IncidentKind::Config,
+            message,
+            ctx,
+        );
+        if diagnostic.is_some() {
+            return failure_output(hook_name, diagnostic);
+        }
+    }
 
     // Dispatch hook - pre-tool-use has different output type
-    if hook_name == "pre-tool-use" {
-        let output = handle_pre_tool_use(&input, &config, &store);
-        write_json(&output)
+    if let Some(event) = event {
+        Ok(serde_json::to_value(dispatch_event_with_context(
+            &event, store, config, ctx,
+        ))?)
+    } else if hook_name == "pre-tool-use" {
+        Ok(serde_json::to_value(handle_pre_tool_use_with_context(
+            &PreToolUseEvent::from_input(&input),
+            config,
+            store,
+            ctx,
+        ))?)
     } else {
-        let output = dispatch_hook(hook_name, &input, &store, &config);
-        write_json(&output)
+        Ok(serde_json::to_value(dispatch_hook_with_context(
+            hook_name, &input, store, config, ctx,
+        ))?)
     }
 }
 
+/// Output for a hook that could not run.
+///
+/// Blocks (denies for pre-tool-use) with `diagnostic` when set, otherwise
+/// fails open.
+fn failure_output(hook_name: &str, diagnostic: Option<String>) -> Result<Value> {
+    let output = match (hook_name, diagnostic) {
+        ("pre-tool-use", Some(d)) => serde_json::to_value(PreToolUseOutput::deny(&d))?,
+        ("pre-tool-use", None) => serde_json::to_value(PreToolUseOutput::allow())?,
+        (_, Some(d)) => serde_json::to_value(HookOutput::block(&d))?,
+        (_, None) => serde_json::to_value(HookOutput::approve())?,
+    };
+    Ok(output)
+}
+
 /// Write a serializable value as JSON to stdout.
 fn write_json<T: Serialize>(output: &T) -> Result<()> {
     let json = serde_json::to_string(output)?;
@@ -72,10 +203,13 @@ fn write_json<T: Serialize>(output: &T) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use crate::config::Config;
+    use super::respond;
+    use crate::config::{Config, SafetyMode};
+    use crate::core::Context;
     use crate::hooks::dispatch_hook;
     use crate::hooks::{HookDecision, HookInput, HookOutput, PreToolUseOutput};
-    use crate::storage::{MemoryBackend, MessageStore};
+    use crate::storage::{IncidentKind, MemoryBackend, MessageStore};
+    use chrono::DateTime;
     use std::path::PathBuf;
 
     fn make_input(session_id: &str) -> HookInput {
@@ -97,6 +231,10 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         }
     }
 
@@ -181,6 +319,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn respond_dispatches_unknown_events_by_hook_name() {
+        let store = MemoryBackend::new();
+        let config = Config::default();
+        let ctx = Context::system();
+        let mut input = make_input("test-new-event");
+        input.prompt = Some("#roz test".to_string());
+        dispatch_hook("user-prompt", &input, &store, &config);
+
+        // An event from a newer Claude Code still runs the named hook
+        let stdin =
+            r#"{"session_id":"test-new-event","cwd":"/tmp","hook_event_name":"StopFailure"}"#;
+        let output = respond("stop", stdin, &config, None, &store, &ctx).unwrap();
+        assert_eq!(output["decision"], "block");
+        assert!(
+            store
+                .incidents_since(DateTime::UNIX_EPOCH)
+                .unwrap()
+                .is_empty()
+        );
+
+        // A known event missing its own required field is still an input error
+        let stdin = r#"{"session_id":"test-new-event","cwd":"/tmp","hook_event_name":"PreToolUse","tool_name":"Bash"}"#;
+        let mut fail_closed = Config::default();
+        fail_closed.safety.mode = SafetyMode::FailClosed;
+        let output = respond("pre-tool-use", stdin, &fail_closed, None, &store, &ctx).unwrap();
+        assert_eq!(output["hookSpecificOutput"]["permissionDecision"], "deny");
+        let incidents = store.incidents_since(DateTime::UNIX_EPOCH).unwrap();
+        assert_eq!(incidents[0].kind, IncidentKind::InputParse);
+    }
+
     #[test]
     fn hook_output_approve_serialization() {
         let output = HookOutput::approve();
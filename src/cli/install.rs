@@ -0,0 +1,581 @@
+//! `roz install` and `roz uninstall` command implementations.
+//!
+//! Manages roz's entries in a Claude Code `settings.json` as an alternative to
+//! the plugin. The hook set comes from the plugin's `hooks/hooks.json`, except
+//! the `PreToolUse` matchers, which are derived from `review.gates.tools` and
+//! the tools `[review.risk]` watches.
+//!
+//! roz entries are recognized by their `roz hook ` command prefix, so installing
+//! twice is a no-op and uninstalling leaves every other hook untouched.
+
+use crate::config::load_config;
+use crate::error::{Error, Result};
+use serde_json::{Map, Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Plugin hook definitions (the source of truth for installed hooks).
+const PLUGIN_HOOKS: &str = include_str!("../../hooks/hooks.json");
+
+/// Command prefix identifying roz hook entries.
+const ROZ_COMMAND_PREFIX: &str = "roz hook ";
+
+/// Which settings file to manage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallScope {
+    /// `.claude/settings.json` in the current directory.
+    Project,
+
+    /// `~/.claude/settings.json`.
+    User,
+}
+
+impl InstallScope {
+    /// Path to the settings file for this scope.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the current or home directory cannot be determined.
+    pub fn settings_path(self) -> Result<PathBuf> {
+        let base = match self {
+            Self::Project => std::env::current_dir()?,
+            Self::User => dirs::home_dir()
+                .ok_or_else(|| Error::Config("cannot determine home directory".to_string()))?,
+        };
+        Ok(base.join(".claude").join("settings.json"))
+    }
+}
+
+/// Result of an install or uninstall.
+#[derive(Debug)]
+pub struct InstallReport {
+    /// Settings file that was managed.
+    pub settings_path: PathBuf,
+
+    /// Copy of the settings as they were before roz first changed them, if
+    /// the file existed and was changed (`None` for uninstall, which deletes
+    /// the backup).
+    pub backup_path: Option<PathBuf>,
+
+    /// Whether the settings file was modified.
+    pub changed: bool,
+
+    /// `PreToolUse` matchers installed (empty for uninstall).
+    pub matchers: Vec<String>,
+}
+
+/// Run the install command.
+///
+/// # Errors
+///
+/// Returns an error if the config or settings cannot be read or written.
+pub fn install(scope: InstallScope) -> Result<()> {
+    let config = load_config()?;
+    let report = install_at(&scope.settings_path()?, &config.review.hooked_tools())?;
+
+    if !report.changed {
+        println!(
+            "roz hooks already installed in {}",
+            report.settings_path.display()
+        );
+        return Ok(());
+    }
+
+    println!("Installed roz hooks in {}", report.settings_path.display());
+    if report.matchers.is_empty() {
+        println!("PreToolUse: none (no review gates or risk thresholds configured)");
+    } else {
+        println!("PreToolUse matchers: {}", report.matchers.join(", "));
+    }
+    if let Some(backup) = &report.backup_path {
+        println!("Backup: {}", backup.display());
+    }
+    println!("\nIf the roz plugin is also installed, hooks will run twice; keep one.");
+    Ok(())
+}
+
+/// Run the uninstall command.
+///
+/// # Errors
+///
+/// Returns an error if the settings cannot be read or written.
+pub fn uninstall(scope: InstallScope) -> Result<()> {
+    let report = uninstall_at(&scope.settings_path()?)?;
+
+    if report.changed {
+        println!("Removed roz hooks from {}", report.settings_path.display());
+    } else {
+        println!("No roz hooks found in {}", report.settings_path.display());
+    }
+    Ok(())
+}
+
+/// Install roz hooks into the settings file at `path`.
+///
+/// # Errors
+///
+/// Returns an error if the settings file is not a JSON object or I/O fails.
+pub fn install_at(path: &Path, gate_patterns: &[String]) -> Result<InstallReport> {
+    let original = read_settings(path)?;
+    let matchers = gate_matchers(gate_patterns);
+
+    let mut settings = original.clone().unwrap_or_else(|| json!({}));
+    strip_roz_hooks(&mut settings, path)?;
+    merge_hooks(&mut settings, &roz_hooks(&matchers)?, path)?;
+
+    let mut report = write_if_changed(path, original.as_ref(), &settings)?;
+    report.matchers = matchers;
+    Ok(report)
+}
+
+/// Remove roz hooks from the settings file at `path`.
+///
+/// # Errors
+///
+/// Returns an error if the settings file is not a JSON object or I/O fails.
+pub fn uninstall_at(path: &Path) -> Result<InstallReport> {
+    let original = read_settings(path)?;
+    let Some(mut settings) = original.clone() else {
+        return Ok(InstallReport {
+            settings_path: path.to_path_buf(),
+            backup_path: None,
+            changed: false,
+            matchers: Vec::new(),
+        });
+    };
+
+    strip_roz_hooks(&mut settings, path)?;
+    let mut report = write_if_changed(path, original.as_ref(), &settings)?;
+
+    // The settings are back to roz-free; a later install backs them up afresh
+    if let Some(backup) = report.backup_path.take() {
+        fs::remove_file(backup)?;
+    }
+    Ok(report)
+}
+
+/// Hook events roz always needs registered.
+///
+/// `PreToolUse` is excluded: it is only needed when gates are configured.
+///
+/// # Errors
+///
+/// Returns an error if the embedded plugin hooks cannot be parsed.
+pub fn required_events() -> Result<Vec<String>> {
+    Ok(roz_hooks(&[])?.keys().cloned().collect())
+}
+
+/// `PreToolUse` matchers registered by the plugin's `hooks/hooks.json`.
+#[must_use]
+pub fn plugin_matchers() -> Vec<String> {
+    serde_json::from_str::<Value>(PLUGIN_HOOKS)
+        .ok()
+        .and_then(|v| v.pointer("/hooks/PreToolUse").cloned())
+        .map(|groups| matchers_of(&groups))
+        .unwrap_or_default()
+}
+
+/// Hook events with at least one roz entry in `settings`.
+#[must_use]
+pub fn installed_events(settings: &Value) -> Vec<String> {
+    let Some(Value::Object(hooks)) = settings.get("hooks") else {
+        return Vec::new();
+    };
+    hooks
+        .iter()
+        .filter(|(_, groups)| roz_groups(groups).next().is_some())
+        .map(|(event, _)| event.clone())
+        .collect()
+}
+
+/// `PreToolUse` matchers of roz entries in `settings`.
+#[must_use]
+pub fn installed_matchers(settings: &Value) -> Vec<String> {
+    settings
+        .pointer("/hooks/PreToolUse")
+        .map(matchers_of)
+        .unwrap_or_default()
+}
+
+/// Matchers of the roz groups in a hook event array.
+fn matchers_of(groups: &Value) -> Vec<String> {
+    roz_groups(groups)
+        .filter_map(|group| group.get("matcher").and_then(Value::as_str))
+        .map(String::from)
+        .collect()
+}
+
+/// Groups in a hook event array that contain a roz entry.
+fn roz_groups(groups: &Value) -> impl Iterator<Item = &Value> {
+    groups.as_array().into_iter().flatten().filter(|group| {
+        group
+            .get("hooks")
+            .and_then(Value::as_array)
+            .is_some_and(|entries| entries.iter().any(is_roz_entry))
+    })
+}
+
+/// Derive `PreToolUse` matchers (regexes on tool name) from gate patterns.
+///
+/// Gate patterns are globs over the tool key, which is `Bash:<command>` for
+/// Bash and the tool name otherwise; matchers only see the tool name.
+#[must_use]
+pub fn gate_matchers(patterns: &[String]) -> Vec<String> {
+    let mut matchers: Vec<String> = Vec::new();
+    for pattern in patterns {
+        let tool = pattern.split_once(':').map_or(pattern.as_str(), |(t, _)| t);
+        let matcher = glob_to_regex(tool);
+        if !matchers.contains(&matcher) {
+            matchers.push(matcher);
+        }
+    }
+    matchers
+}
+
+/// Convert a glob to an equivalent regex (`*` → `.*`, `?` → `.`).
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::with_capacity(glob.len());
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            _ => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    regex
+}
+
+/// Build roz's hooks object: the plugin hooks with derived `PreToolUse` matchers.
+fn roz_hooks(matchers: &[String]) -> Result<Map<String, Value>> {
+    let plugin: Value = serde_json::from_str(PLUGIN_HOOKS)?;
+    let Some(Value::Object(mut hooks)) = plugin.get("hooks").cloned() else {
+        return Err(Error::Config("plugin hooks.json has no hooks".to_string()));
+    };
+
+    // Reuse the plugin's pre-tool-use command entry for each derived matcher
+    let pre_tool_use_hooks = hooks
+        .get("PreToolUse")
+        .and_then(|groups| groups.get(0))
+        .and_then(|group| group.get("hooks"))
+        .cloned()
+        .unwrap_or_else(
+            || json!([{ "type": "command", "command": "roz hook pre-tool-use", "timeout": 5 }]),
+        );
+
+    if matchers.is_empty() {
+        hooks.shift_remove("PreToolUse");
+    } else {
+        let groups = matchers
+            .iter()
+            .map(|m| json!({ "matcher": m, "hooks": pre_tool_use_hooks }))
+            .collect();
+        hooks.insert("PreToolUse".to_string(), Value::Array(groups));
+    }
+
+    Ok(hooks)
+}
+
+/// Append roz's hook groups to the settings' `hooks` object.
+fn merge_hooks(settings: &mut Value, roz: &Map<String, Value>, path: &Path) -> Result<()> {
+    let root = as_object(settings, path)?;
+    let hooks = root
+        .entry("hooks")
+        .or_insert_with(|| Value::Object(Map::new()));
+    let Value::Object(hooks) = hooks else {
+        return Err(invalid(path, "`hooks` is not an object"));
+    };
+
+    for (event, groups) in roz {
+        let existing = hooks
+            .entry(event.clone())
+            .or_insert_with(|| Value::Array(Vec::new()));
+        let (Value::Array(existing), Value::Array(groups)) = (existing, groups) else {
+            return Err(invalid(path, &format!("`hooks.{event}` is not an array")));
+        };
+        existing.extend(groups.iter().cloned());
+    }
+    Ok(())
+}
+
+/// Remove every roz hook entry, dropping groups and events left empty.
+fn strip_roz_hooks(settings: &mut Value, path: &Path) -> Result<()> {
+    let root = as_object(settings, path)?;
+    let Some(Value::Object(hooks)) = root.get_mut("hooks") else {
+        return Ok(());
+    };
+
+    for groups in hooks.values_mut() {
+        let Value::Array(groups) = groups else {
+            continue;
+        };
+        groups.retain_mut(|group| {
+            let Some(Value::Array(entries)) = group.get_mut("hooks") else {
+                return true;
+            };
+            let before = entries.len();
+            entries.retain(|entry| !is_roz_entry(entry));
+            // Keep groups that were empty to begin with; drop ones we emptied
+            !(entries.is_empty() && before > 0)
+        });
+    }
+
+    hooks.retain(|_, groups| !matches!(groups, Value::Array(g) if g.is_empty()));
+    if hooks.is_empty() {
+        root.shift_remove("hooks");
+    }
+    Ok(())
+}
+
+/// Whether a hook entry runs a roz hook command.
+fn is_roz_entry(entry: &Value) -> bool {
+    entry
+        .get("command")
+        .and_then(Value::as_str)
+        .is_some_and(|cmd| cmd.trim_start().starts_with(ROZ_COMMAND_PREFIX))
+}
+
+/// Read settings, returning `None` if the file does not exist.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not valid JSON.
+pub fn read_settings(path: &Path) -> Result<Option<Value>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(path)?;
+    if contents.trim().is_empty() {
+        return Ok(Some(json!({})));
+    }
+    serde_json::from_str(&contents)
+        .map(Some)
+        .map_err(|e| invalid(path, &e.to_string()))
+}
+
+/// Write settings unless nothing changed.
+///
+/// The first write backs up the existing file; later writes keep that backup
+/// so it always holds the settings from before roz touched them.
+fn write_if_changed(
+    path: &Path,
+    original: Option<&Value>,
+    settings: &Value,
+) -> Result<InstallReport> {
+    let mut report = InstallReport {
+        settings_path: path.to_path_buf(),
+        backup_path: None,
+        changed: false,
+        matchers: Vec::new(),
+    };
+
+    if original == Some(settings) {
+        return Ok(report);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if original.is_some() {
+        let backup = path.with_extension("json.roz-backup");
+        if !backup.exists() {
+            fs::copy(path, &backup)?;
+        }
+        report.backup_path = Some(backup);
+    }
+
+    // Atomic write, as for session files
+    let temp = path.with_extension("json.tmp");
+    let mut contents = serde_json::to_string_pretty(settings)?;
+    contents.push('\n');
+    fs::write(&temp, contents)?;
+    fs::rename(&temp, path)?;
+
+    report.changed = true;
+    Ok(report)
+}
+
+/// Borrow the settings root as an object.
+fn as_object<'a>(settings: &'a mut Value, path: &Path) -> Result<&'a mut Map<String, Value>> {
+    settings
+        .as_object_mut()
+        .ok_or_else(|| invalid(path, "expected a JSON object"))
+}
+
+/// Error for a malformed settings file.
+fn invalid(path: &Path, detail: &str) -> Error {
+    Error::Config(format!("{}: {detail}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn patterns(p: &[&str]) -> Vec<String> {
+        p.iter().map(|s| (*s).to_string()).collect()
+    }
+
+    fn settings_path(dir: &TempDir) -> PathBuf {
+        dir.path().join(".claude").join("settings.json")
+    }
+
+    fn read(path: &Path) -> Value {
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn gate_matchers_from_patterns() {
+        let matchers = gate_matchers(&patterns(&[
+            "mcp__tissue__*",
+            "mcp__beads__close",
+            "Bash:git push*",
+            "Bash:git commit*",
+        ]));
+        assert_eq!(
+            matchers,
+            vec!["mcp__tissue__.*", "mcp__beads__close", "Bash"]
+        );
+    }
+
+    #[test]
+    fn glob_to_regex_escapes_metacharacters() {
+        assert_eq!(glob_to_regex("a.b?c*"), "a\\.b.c.*");
+        let matcher = glob_to_regex("mcp__x__get[ab]*");
+        assert_eq!(matcher, "mcp__x__get\\[ab\\].*");
+        let regex = regex::Regex::new(&format!("^{matcher}$")).unwrap();
+        assert!(regex.is_match("mcp__x__get[ab]_item"));
+        assert!(!regex.is_match("mcp__x__geta"));
+    }
+
+    #[test]
+    fn install_creates_settings() {
+        let dir = TempDir::new().unwrap();
+        let path = settings_path(&dir);
+
+        let report = install_at(&path, &patterns(&["mcp__tissue__*"])).unwrap();
+        assert!(report.changed);
+        assert!(report.backup_path.is_none());
+
+        let settings = read(&path);
+        assert_eq!(
+            settings["hooks"]["PreToolUse"][0]["matcher"],
+            "mcp__tissue__.*"
+        );
+        assert_eq!(
+            settings["hooks"]["Stop"][0]["hooks"][0]["command"],
+            "roz hook stop"
+        );
+        assert_eq!(settings["hooks"]["SubagentStop"][0]["matcher"], "roz:roz");
+    }
+
+    #[test]
+    fn install_without_gates_skips_pre_tool_use() {
+        let dir = TempDir::new().unwrap();
+        let path = settings_path(&dir);
+
+        install_at(&path, &[]).unwrap();
+        assert!(read(&path)["hooks"].get("PreToolUse").is_none());
+    }
+
+    #[test]
+    fn install_is_idempotent_and_preserves_other_hooks() {
+        let dir = TempDir::new().unwrap();
+        let path = settings_path(&dir);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let original = json!({
+            "model": "opus",
+            "hooks": {
+                "Stop": [{ "hooks": [{ "type": "command", "command": "notify-send done" }] }]
+            }
+        });
+        fs::write(&path, serde_json::to_string(&original).unwrap()).unwrap();
+
+        let first = install_at(&path, &patterns(&["Bash:git push*"])).unwrap();
+        assert!(first.changed);
+        let backup = first.backup_path.unwrap();
+        assert_eq!(read(&backup), original);
+        let installed = read(&path);
+
+        let second = install_at(&path, &patterns(&["Bash:git push*"])).unwrap();
+        assert!(!second.changed);
+        assert_eq!(read(&path), installed);
+
+        // Changing gates rewrites the settings but keeps the original backup
+        let third = install_at(&path, &patterns(&["mcp__tissue__close*"])).unwrap();
+        assert!(third.changed);
+        assert_eq!(third.backup_path.unwrap(), backup);
+        assert_eq!(read(&backup), original);
+
+        let stop = installed["hooks"]["Stop"].as_array().unwrap();
+        assert_eq!(stop.len(), 2);
+        assert_eq!(stop[0]["hooks"][0]["command"], "notify-send done");
+        assert_eq!(installed["model"], "opus");
+    }
+
+    #[test]
+    fn install_updates_matchers_when_gates_change() {
+        let dir = TempDir::new().unwrap();
+        let path = settings_path(&dir);
+
+        install_at(&path, &patterns(&["mcp__tissue__*"])).unwrap();
+        install_at(&path, &patterns(&["mcp__beads__*"])).unwrap();
+
+        let groups = read(&path)["hooks"]["PreToolUse"].clone();
+        assert_eq!(groups.as_array().unwrap().len(), 1);
+        assert_eq!(groups[0]["matcher"], "mcp__beads__.*");
+    }
+
+    #[test]
+    fn uninstall_restores_original() {
+        let dir = TempDir::new().unwrap();
+        let path = settings_path(&dir);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let original = json!({
+            "permissions": { "allow": ["Bash(ls:*)"] },
+            "hooks": {
+                "Stop": [{ "hooks": [{ "type": "command", "command": "notify-send done" }] }]
+            }
+        });
+        fs::write(&path, serde_json::to_string(&original).unwrap()).unwrap();
+
+        let backup = install_at(&path, &patterns(&["mcp__tissue__*"]))
+            .unwrap()
+            .backup_path
+            .unwrap();
+        let report = uninstall_at(&path).unwrap();
+        assert!(report.changed);
+        assert_eq!(read(&path), original);
+        assert!(report.backup_path.is_none());
+        assert!(!backup.exists());
+
+        // The next install backs up the settings as they are now
+        let edited = json!({ "model": "sonnet" });
+        fs::write(&path, serde_json::to_string(&edited).unwrap()).unwrap();
+        install_at(&path, &[]).unwrap();
+        assert_eq!(read(&backup), edited);
+        uninstall_at(&path).unwrap();
+        assert_eq!(read(&path), edited);
+
+        let again = uninstall_at(&path).unwrap();
+        assert!(!again.changed);
+    }
+
+    #[test]
+    fn uninstall_missing_settings_is_noop() {
+        let dir = TempDir::new().unwrap();
+        let report = uninstall_at(&settings_path(&dir)).unwrap();
+        assert!(!report.changed);
+    }
+
+    #[test]
+    fn install_rejects_non_object_settings() {
+        let dir = TempDir::new().unwrap();
+        let path = settings_path(&dir);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "[1, 2]").unwrap();
+
+        let result = install_at(&path, &[]);
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+}
@@ -1,9 +1,11 @@
 //! `roz list` command implementation.
 
+use crate::cli::format::{CsvRecord, OutputFormat, print_machine};
 use crate::error::Result;
-use crate::storage::MessageStore;
 use crate::storage::file::{FileBackend, get_roz_home};
-use chrono::{DateTime, Local, Utc};
+use crate::storage::{Incident, MessageStore, SessionSummary};
+use chrono::{DateTime, Duration, Local, SecondsFormat, Utc};
+use serde::Serialize;
 
 /// Default number of sessions to show.
 const DEFAULT_LIMIT: usize = 20;
@@ -11,6 +13,45 @@ const DEFAULT_LIMIT: usize = 20;
 /// Maximum length for prompt preview.
 const PROMPT_PREVIEW_LEN: usize = 50;
 
+/// How far back `roz list` looks for incidents.
+const INCIDENT_WINDOW_DAYS: i64 = 7;
+
+/// Maximum number of incidents shown in the table footer.
+const INCIDENT_PREVIEW: usize = 5;
+
+/// JSON report for `roz list --format json`.
+#[derive(Debug, Serialize)]
+pub struct ListReport {
+    /// Sessions, most recent first.
+    pub sessions: Vec<SessionSummary>,
+
+    /// Incidents from the last 7 days, oldest first.
+    pub incidents: Vec<Incident>,
+}
+
+impl CsvRecord for SessionSummary {
+    const HEADERS: &'static [&'static str] = &[
+        "session_id",
+        "created_at",
+        "first_prompt",
+        "event_count",
+        "cwd",
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.session_id.clone(),
+            self.created_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            self.first_prompt.clone().unwrap_or_default(),
+            self.event_count.to_string(),
+            self.cwd
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default(),
+        ]
+    }
+}
+
 /// Run the list command.
 ///
 /// Shows recent sessions with their IDs, creation time, and first prompt.
@@ -18,15 +59,19 @@ const PROMPT_PREVIEW_LEN: usize = 50;
 /// # Errors
 ///
 /// Returns an error if the storage backend fails.
-pub fn run(limit: Option<usize>) -> Result<()> {
+pub fn run(limit: Option<usize>, format: OutputFormat) -> Result<()> {
     let store = FileBackend::new(get_roz_home())?;
-    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    let report = collect(&store, limit)?;
 
-    let sessions = store.list_sessions(limit)?;
+    if print_machine(format, &report, &report.sessions)? {
+        return Ok(());
+    }
 
+    let sessions = report.sessions;
     if sessions.is_empty() {
         println!("No sessions found.");
         println!("\nSessions are stored in: {}", get_roz_home().display());
+        render_incidents(&report.incidents);
         return Ok(());
     }
 
@@ -42,10 +87,54 @@ pub fn run(limit: Option<usize>) -> Result<()> {
 
     println!("{}", "─".repeat(90));
     println!("Showing {} session(s)", sessions.len());
+    render_incidents(&report.incidents);
 
     Ok(())
 }
 
+/// Print recent incidents, most recent first.
+fn render_incidents(incidents: &[Incident]) {
+    if incidents.is_empty() {
+        return;
+    }
+
+    println!(
+        "\n{} incident(s) in the last {INCIDENT_WINDOW_DAYS} days (hooks could not check review state):",
+        incidents.len()
+    );
+    for incident in incidents.iter().rev().take(INCIDENT_PREVIEW) {
+        println!(
+            "  {}  {:<14} {:<18} {:<8} {}",
+            format_local_time(incident.timestamp),
+            incident.hook,
+            incident.kind.as_str(),
+            if incident.blocked {
+                "blocked"
+            } else {
+                "bypassed"
+            },
+            incident.session_id.as_deref().unwrap_or("-")
+        );
+    }
+    if incidents.len() > INCIDENT_PREVIEW {
+        println!("  ... use `roz list --format json` for all of them");
+    }
+}
+
+/// Collect recent sessions for the list command.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails.
+pub fn collect(store: &dyn MessageStore, limit: Option<usize>) -> Result<ListReport> {
+    let sessions = store.list_sessions(limit.unwrap_or(DEFAULT_LIMIT))?;
+    let incidents = store.incidents_since(Utc::now() - Duration::days(INCIDENT_WINDOW_DAYS))?;
+    Ok(ListReport {
+        sessions,
+        incidents,
+    })
+}
+
 /// Format UTC time as local time for display.
 fn format_local_time(utc: DateTime<Utc>) -> String {
     let local: DateTime<Local> = utc.into();
@@ -118,6 +207,56 @@ mod tests {
         assert_eq!(sessions.len(), 3);
     }
 
+    #[test]
+    fn collect_applies_default_limit() {
+        let store = MemoryBackend::new();
+        for i in 0..(DEFAULT_LIMIT + 5) {
+            store
+                .put_session(&SessionState::new(&format!("session-{i}")))
+                .unwrap();
+        }
+
+        let report = collect(&store, None).unwrap();
+        assert_eq!(report.sessions.len(), DEFAULT_LIMIT);
+    }
+
+    #[test]
+    fn collect_includes_recent_incidents() {
+        use crate::storage::{Incident, IncidentKind};
+
+        let store = MemoryBackend::new();
+        for days_ago in [1, INCIDENT_WINDOW_DAYS + 1] {
+            store
+                .record_incident(&Incident {
+                    timestamp: Utc::now() - Duration::days(days_ago),
+                    hook: "stop".to_string(),
+                    kind: IncidentKind::StorageRead,
+                    session_id: None,
+                    message: "storage error".to_string(),
+                    blocked: false,
+                })
+                .unwrap();
+        }
+
+        let report = collect(&store, None).unwrap();
+        assert_eq!(report.incidents.len(), 1);
+    }
+
+    #[test]
+    fn session_summary_csv_fields() {
+        let store = MemoryBackend::new();
+        let mut state = SessionState::new("session-csv");
+        state.review.user_prompts.push("#roz a, b".to_string());
+        store.put_session(&state).unwrap();
+
+        let report = collect(&store, None).unwrap();
+        let fields = report.sessions[0].csv_fields();
+        assert_eq!(fields.len(), SessionSummary::HEADERS.len());
+        assert_eq!(fields[0], "session-csv");
+        assert_eq!(fields[2], "#roz a, b");
+        assert!(fields[1].ends_with('Z'));
+    }
+
     #[test]
     fn format_prompt_preview_truncates_long_prompts() {
         let long_prompt = "x".repeat(100);
@@ -1,10 +1,21 @@
 //! CLI command implementations.
 
+pub mod approve;
+pub mod breaker;
 pub mod clean;
 pub mod context;
 pub mod debug;
 pub mod decide;
+pub mod doctor;
+pub mod format;
 pub mod hook;
+pub mod install;
 pub mod list;
+pub mod record;
+pub mod replay;
+pub mod search;
 pub mod stats;
+pub mod status;
+pub mod tail;
 pub mod trace;
+pub mod watch;
@@ -0,0 +1,190 @@
+//! Hook recording and `roz record` command implementation.
+//!
+//! With `[record] enabled = true` (or `ROZ_RECORD=1`) every hook run appends
+//! its raw stdin and stdout to `~/.roz/recordings/<session>.jsonl`, and
+//! `roz decide`/`approve`/`reject` append their arguments, so a session can be
+//! replayed exactly with `roz replay`. Recording never fails a hook.
+
+use crate::config::load_config;
+use crate::error::{Error, Result};
+use crate::storage::file::{FileBackend, get_roz_home};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Directory under the roz home that holds recordings.
+pub const RECORDINGS_DIR: &str = "recordings";
+
+/// One recorded step: a hook run or a decision command.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedStep {
+    /// When the step ran.
+    pub timestamp: DateTime<Utc>,
+
+    /// Hook name (`stop`, `pre-tool-use`, ...) or command (`decide`,
+    /// `approve`, `reject`).
+    pub hook: String,
+
+    /// Hook stdin (parsed JSON, or the raw string if it was not JSON), or the
+    /// command's arguments.
+    pub input: Value,
+
+    /// Hook stdout, or `{"ok": true}` / `{"error": "..."}` for commands.
+    pub output: Value,
+}
+
+/// Path of a session's recording.
+#[must_use]
+pub fn recording_path(base_dir: &Path, session_id: &str) -> PathBuf {
+    base_dir
+        .join(RECORDINGS_DIR)
+        .join(format!("{session_id}.jsonl"))
+}
+
+/// Append a step to a session's recording.
+///
+/// # Errors
+///
+/// Returns an error if the session ID is invalid or the file cannot be written.
+pub fn append_step(base_dir: &Path, session_id: &str, step: &RecordedStep) -> Result<()> {
+    if !FileBackend::is_valid_session_id(session_id) {
+        return Err(Error::InvalidState(format!(
+            "Invalid session ID: '{session_id}'"
+        )));
+    }
+
+    let path = recording_path(base_dir, session_id);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut line = serde_json::to_string(step)?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?
+        .write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Record a hook run. Failures are only warned about.
+pub fn record_hook(base_dir: &Path, hook: &str, input_str: &str, output: &Value) {
+    let input = serde_json::from_str::<Value>(input_str)
+        .unwrap_or_else(|_| Value::String(input_str.to_string()));
+    let Some(session_id) = input
+        .get("session_id")
+        .and_then(Value::as_str)
+        .map(String::from)
+    else {
+        return; // Nothing to file it under
+    };
+
+    let step = RecordedStep {
+        timestamp: Utc::now(),
+        hook: hook.to_string(),
+        input,
+        output: output.clone(),
+    };
+    if let Err(e) = append_step(base_dir, &session_id, &step) {
+        eprintln!("roz: warning: failed to record hook: {e}");
+    }
+}
+
+/// Record a decision command if recording is enabled. Failures are only
+/// warned about.
+pub fn record_command<T>(command: &str, session_id: &str, input: Value, result: &Result<T>) {
+    if !load_config().is_ok_and(|c| c.record.enabled) {
+        return;
+    }
+
+    let output = match result {
+        Ok(_) => serde_json::json!({ "ok": true }),
+        Err(e) => serde_json::json!({ "error": e.to_string() }),
+    };
+    let step = RecordedStep {
+        timestamp: Utc::now(),
+        hook: command.to_string(),
+        input,
+        output,
+    };
+    if let Err(e) = append_step(&get_roz_home(), session_id, &step) {
+        eprintln!("roz: warning: failed to record {command}: {e}");
+    }
+}
+
+/// Run the record command: export a session's recording as a fixture.
+///
+/// Writes to `output`, or prints to stdout without one.
+///
+/// # Errors
+///
+/// Returns an error if the session has no recording or writing fails.
+pub fn run(session_id: &str, output: Option<&Path>) -> Result<()> {
+    let path = recording_path(&get_roz_home(), session_id);
+    if !FileBackend::is_valid_session_id(session_id) || !path.exists() {
+        return Err(Error::SessionNotFound(format!(
+            "no recording for {session_id} (enable [record] or set ROZ_RECORD=1)"
+        )));
+    }
+
+    let contents = fs::read_to_string(&path)?;
+    match output {
+        Some(out) => {
+            fs::write(out, &contents)?;
+            println!(
+                "Wrote {} step(s) to {}",
+                contents.lines().count(),
+                out.display()
+            );
+        }
+        None => print!("{contents}"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn record_hook_appends_steps_per_session() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let input = r#"{"session_id":"rec-1","cwd":"/tmp"}"#;
+
+        record_hook(temp.path(), "session-start", input, &json!({}));
+        record_hook(temp.path(), "stop", input, &json!({"decision": "block"}));
+
+        let contents = fs::read_to_string(recording_path(temp.path(), "rec-1")).unwrap();
+        let steps: Vec<RecordedStep> = contents
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[1].hook, "stop");
+        assert_eq!(steps[1].input["cwd"], "/tmp");
+        assert_eq!(steps[1].output["decision"], "block");
+    }
+
+    #[test]
+    fn record_hook_skips_input_without_session() {
+        let temp = tempfile::TempDir::new().unwrap();
+        record_hook(temp.path(), "stop", "not json", &json!({}));
+        assert!(!temp.path().join(RECORDINGS_DIR).exists());
+    }
+
+    #[test]
+    fn append_step_rejects_path_traversal() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let step = RecordedStep {
+            timestamp: Utc::now(),
+            hook: "stop".to_string(),
+            input: json!({}),
+            output: json!({}),
+        };
+        assert!(append_step(temp.path(), "../escape", &step).is_err());
+    }
+}
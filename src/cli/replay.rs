@@ -0,0 +1,285 @@
+//! `roz replay` command implementation.
+//!
+//! Feeds a recorded fixture (see `roz record`) back through the hook handlers
+//! and decision commands against an in-memory store, and reports every step
+//! whose output differs from the recording. Each step runs on a fixed clock
+//! set to its recorded time, so TTLs and cooldowns behave as they did.
+
+use crate::cli::decide::{
+    record_decision_with_context, record_human_decision_with_context, record_unknown_session,
+};
+use crate::cli::format::{CsvRecord, OutputFormat, print_machine};
+use crate::cli::hook::respond;
+use crate::cli::record::RecordedStep;
+use crate::config::{Config, load_config, load_config_from};
+use crate::core::state::Decision;
+use crate::core::{Context, FixedClock, SeededRng};
+use crate::error::{Error, Result};
+use crate::storage::{MemoryBackend, MessageStore};
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::Path;
+
+/// A step whose replayed output differs from the recording.
+#[derive(Debug, Serialize)]
+pub struct Divergence {
+    /// Zero-based index of the step in the fixture.
+    pub step: usize,
+
+    /// Hook or command name.
+    pub hook: String,
+
+    /// Output in the recording.
+    pub expected: Value,
+
+    /// Output produced by the replay.
+    pub actual: Value,
+}
+
+impl CsvRecord for Divergence {
+    const HEADERS: &'static [&'static str] = &["step", "hook", "expected", "actual"];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.step.to_string(),
+            self.hook.clone(),
+            self.expected.to_string(),
+            self.actual.to_string(),
+        ]
+    }
+}
+
+/// JSON report for `roz replay --format json`.
+#[derive(Debug, Serialize)]
+pub struct ReplayReport {
+    /// Number of steps replayed.
+    pub steps: usize,
+
+    /// Steps whose output diverged.
+    pub divergences: Vec<Divergence>,
+}
+
+/// Run the replay command.
+///
+/// Uses the config at `config_path` if given, otherwise the normal config.
+///
+/// # Errors
+///
+/// Returns an error if the fixture or config cannot be read, or if any step
+/// diverged.
+pub fn run(fixture: &Path, config_path: Option<&Path>, format: OutputFormat) -> Result<()> {
+    let config = match config_path {
+        Some(path) => load_config_from(path)?,
+        None => load_config()?,
+    };
+    let steps = load_fixture(fixture)?;
+    let report = replay(&steps, &config)?;
+
+    if !print_machine(format, &report, &report.divergences)? {
+        for (i, step) in steps.iter().enumerate() {
+            match report.divergences.iter().find(|d| d.step == i) {
+                Some(d) => {
+                    println!("{i:>4}  {:<16} DIVERGED", step.hook);
+                    println!("      expected: {}", d.expected);
+                    println!("      actual:   {}", d.actual);
+                }
+                None => println!("{i:>4}  {:<16} ok", step.hook),
+            }
+        }
+        println!(
+            "\n{} step(s), {} divergence(s)",
+            report.steps,
+            report.divergences.len()
+        );
+    }
+
+    if report.divergences.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::InvalidState(format!(
+            "{} divergence(s) in {}",
+            report.divergences.len(),
+            fixture.display()
+        )))
+    }
+}
+
+/// Read a fixture: one recorded step per line.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or a line is not a step.
+pub fn load_fixture(path: &Path) -> Result<Vec<RecordedStep>> {
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(Error::from))
+        .collect()
+}
+
+/// Replay steps against a fresh in-memory store, with the clock set to each
+/// step's recorded time.
+///
+/// # Errors
+///
+/// Returns an error if a step names an unknown hook input or output cannot
+/// be serialized.
+pub fn replay(steps: &[RecordedStep], config: &Config) -> Result<ReplayReport> {
+    let store = MemoryBackend::new();
+    let clock = FixedClock::new(steps.first().map_or_else(Utc::now, |s| s.timestamp));
+    let rng = SeededRng::new(0);
+    let ctx = Context::new(&clock, &rng);
+    let mut divergences = Vec::new();
+
+    for (i, step) in steps.iter().enumerate() {
+        clock.set(step.timestamp);
+        let actual = replay_step(&store, config, step, &ctx)?;
+        if actual != step.output {
+            divergences.push(Divergence {
+                step: i,
+                hook: step.hook.clone(),
+                expected: step.output.clone(),
+                actual,
+            });
+        }
+    }
+
+    Ok(ReplayReport {
+        steps: steps.len(),
+        divergences,
+    })
+}
+
+/// Run one step and return its output in recorded form.
+fn replay_step(
+    store: &dyn MessageStore,
+    config: &Config,
+    step: &RecordedStep,
+    ctx: &Context,
+) -> Result<Value> {
+    let arg = |key: &str| step.input.get(key).and_then(Value::as_str);
+    let session_id = arg("session_id").unwrap_or_default();
+
+    let result = match step.hook.as_str() {
+        "decide" => {
+            let result = record_decision_with_context(
+                store,
+                session_id,
+                arg("decision").unwrap_or_default(),
+                arg("summary").unwrap_or_default(),
+                arg("message"),
+                arg("opinions"),
+                ctx,
+            );
+            if matches!(result, Err(Error::SessionNotFound(_))) {
+                record_unknown_session(store, session_id, arg("cwd").map(Path::new), ctx);
+            }
+            result.map(|_| ())
+        }
+        "approve" => record_human_decision_with_context(
+            store,
+            session_id,
+            Decision::Complete {
+                summary: arg("reason").unwrap_or_default().to_string(),
+                second_opinions: None,
+            },
+            ctx,
+        ),
+        "reject" => {
+            let message = arg("message").unwrap_or_default().to_string();
+            record_human_decision_with_context(
+                store,
+                session_id,
+                Decision::Issues {
+                    summary: message.clone(),
+                    message_to_agent: Some(message),
+                },
+                ctx,
+            )
+        }
+        hook => {
+            let input_str = match &step.input {
+                Value::String(raw) => raw.clone(),
+                other => other.to_string(),
+            };
+            return respond(hook, &input_str, config, None, store, ctx);
+        }
+    };
+
+    Ok(match result {
+        Ok(()) => json!({ "ok": true }),
+        Err(e) => json!({ "error": e.to_string() }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(hook: &str, input: Value, output: Value) -> RecordedStep {
+        RecordedStep {
+            timestamp: Utc::now(),
+            hook: hook.to_string(),
+            input,
+            output,
+        }
+    }
+
+    #[test]
+    fn replay_matches_and_diverges() {
+        let config = Config::default();
+        let start = json!({"session_id": "replay-1", "cwd": "/tmp"});
+        let output = respond(
+            "session-start",
+            &start.to_string(),
+            &config,
+            None,
+            &MemoryBackend::new(),
+            &Context::system(),
+        )
+        .unwrap();
+
+        let steps = [
+            step("session-start", start, output),
+            step(
+                "decide",
+                json!({"session_id": "missing", "decision": "COMPLETE", "summary": "ok"}),
+                json!({"ok": true}),
+            ),
+        ];
+        let report = replay(&steps, &config).unwrap();
+        assert_eq!(report.steps, 2);
+        assert_eq!(report.divergences.len(), 1);
+        assert_eq!(report.divergences[0].step, 1);
+        assert!(
+            report.divergences[0].actual["error"]
+                .as_str()
+                .unwrap()
+                .contains("missing")
+        );
+    }
+
+    #[test]
+    fn load_fixture_skips_blank_lines() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("fixture.jsonl");
+        let line = serde_json::to_string(&step("stop", json!({}), json!({}))).unwrap();
+        fs::write(&path, format!("{line}\n\n{line}\n")).unwrap();
+
+        assert_eq!(load_fixture(&path).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn divergence_csv_fields() {
+        let d = Divergence {
+            step: 3,
+            hook: "stop".to_string(),
+            expected: json!({"decision": "block"}),
+            actual: json!({}),
+        };
+        assert_eq!(d.csv_fields().len(), Divergence::HEADERS.len());
+        assert_eq!(d.csv_fields()[0], "3");
+    }
+}
@@ -0,0 +1,246 @@
+//! `roz search` command implementation.
+//!
+//! Scans stored sessions for trace events matching the same filters as
+//! `roz trace` (`--type`, `--since`/`--until`, `--grep`), optionally limited
+//! to sessions recorded under a directory.
+
+use crate::cli::format::{CsvRecord, OutputFormat, print_machine};
+use crate::cli::tail::payload_preview;
+use crate::cli::trace::{TraceFilter, event_type_name};
+use crate::core::state::TraceEvent;
+use crate::error::Result;
+use crate::storage::MessageStore;
+use crate::storage::file::{FileBackend, get_roz_home};
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// A matching event and the session it belongs to.
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    /// Session identifier.
+    pub session_id: String,
+
+    /// Working directory the session last ran in.
+    pub cwd: Option<PathBuf>,
+
+    /// The matching event.
+    #[serde(flatten)]
+    pub event: TraceEvent,
+}
+
+impl CsvRecord for SearchHit {
+    const HEADERS: &'static [&'static str] = &[
+        "session_id",
+        "cwd",
+        "id",
+        "timestamp",
+        "event_type",
+        "payload",
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        let mut fields = vec![
+            self.session_id.clone(),
+            self.cwd
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default(),
+        ];
+        fields.extend(self.event.csv_fields());
+        fields
+    }
+}
+
+/// JSON report for `roz search --format json`.
+#[derive(Debug, Serialize)]
+pub struct SearchReport {
+    /// Number of sessions scanned.
+    pub sessions_scanned: usize,
+
+    /// Matching events, oldest first.
+    pub matches: Vec<SearchHit>,
+}
+
+/// Run the search command.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails.
+pub fn run(
+    filter: &TraceFilter,
+    cwd: Option<&Path>,
+    limit: Option<usize>,
+    format: OutputFormat,
+) -> Result<()> {
+    let store = FileBackend::new(get_roz_home())?;
+    let report = collect(&store, filter, cwd, limit)?;
+
+    if print_machine(format, &report, &report.matches)? {
+        return Ok(());
+    }
+
+    if report.matches.is_empty() {
+        println!(
+            "No matching events in {} session(s).",
+            report.sessions_scanned
+        );
+        return Ok(());
+    }
+
+    for hit in &report.matches {
+        let time: DateTime<Local> = hit.event.timestamp.into();
+        println!(
+            "{}  {:<36}  {:<24} {}",
+            time.format("%Y-%m-%d %H:%M:%S"),
+            hit.session_id,
+            event_type_name(&hit.event.event_type),
+            payload_preview(&hit.event.payload)
+        );
+    }
+    println!(
+        "\n{} event(s) in {} session(s) scanned",
+        report.matches.len(),
+        report.sessions_scanned
+    );
+    Ok(())
+}
+
+/// Collect matching events across sessions.
+///
+/// Sessions last updated before `filter.since` are skipped without being
+/// loaded. With `cwd`, only sessions whose recorded directory is `cwd` or
+/// below it are scanned. With `limit`, only the most recent `limit` matches
+/// are kept.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails.
+pub fn collect(
+    store: &dyn MessageStore,
+    filter: &TraceFilter,
+    cwd: Option<&Path>,
+    limit: Option<usize>,
+) -> Result<SearchReport> {
+    let mut sessions_scanned = 0;
+    let mut matches = Vec::new();
+
+    let since = filter.since.unwrap_or(DateTime::<Utc>::MIN_UTC);
+    for session_id in store.sessions_updated_since(since)? {
+        // The store may list files it cannot parse
+        let Ok(Some(state)) = store.get_session(&session_id) else {
+            continue;
+        };
+        if state.updated_at < since {
+            continue;
+        }
+        if let Some(dir) = cwd {
+            if !state.cwd.as_deref().is_some_and(|c| c.starts_with(dir)) {
+                continue;
+            }
+        }
+        sessions_scanned += 1;
+
+        matches.extend(
+            state
+                .trace
+                .into_iter()
+                .filter(|e| filter.matches(e))
+                .map(|event| SearchHit {
+                    session_id: state.session_id.clone(),
+                    cwd: state.cwd.clone(),
+                    event,
+                }),
+        );
+    }
+
+    matches.sort_by_key(|hit| hit.event.timestamp);
+    if let Some(limit) = limit {
+        let excess = matches.len().saturating_sub(limit);
+        matches.drain(..excess);
+    }
+
+    Ok(SearchReport {
+        sessions_scanned,
+        matches,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::state::{EventType, SessionState};
+    use crate::storage::MemoryBackend;
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+
+    fn session_with_gate(store: &MemoryBackend, id: &str, cwd: &str, days_ago: i64, tool: &str) {
+        let mut state = SessionState::new(id);
+        state.cwd = Some(PathBuf::from(cwd));
+        state.trace.push(TraceEvent {
+            id: format!("{id}-gate"),
+            timestamp: Utc::now() - Duration::days(days_ago),
+            event_type: EventType::GateBlocked,
+            payload: json!({"tool": tool, "pattern": "Bash:git push*"}),
+        });
+        state.trace.push(TraceEvent {
+            id: format!("{id}-stop"),
+            timestamp: Utc::now(),
+            event_type: EventType::StopHookCalled,
+            payload: json!({}),
+        });
+        store.put_session(&state).unwrap();
+    }
+
+    #[test]
+    fn search_across_sessions() {
+        let store = MemoryBackend::new();
+        session_with_gate(&store, "a", "/work/one", 2, "Bash:git push");
+        session_with_gate(&store, "b", "/work/two", 3, "Bash:git push origin");
+        session_with_gate(&store, "c", "/work/two", 30, "Bash:git push");
+        // Untouched since before `--since`, so never loaded
+        let mut idle = SessionState::new("idle");
+        idle.updated_at = Utc::now() - Duration::days(10);
+        store.put_session(&idle).unwrap();
+
+        let filter = TraceFilter::parse(
+            &["gate_blocked".to_string()],
+            Some("7d"),
+            None,
+            Some("Bash:git push*"),
+        )
+        .unwrap();
+        let report = collect(&store, &filter, None, None).unwrap();
+        assert_eq!(report.sessions_scanned, 3);
+        let sessions: Vec<&str> = report
+            .matches
+            .iter()
+            .map(|h| h.session_id.as_str())
+            .collect();
+        assert_eq!(sessions, ["b", "a"]); // oldest first
+
+        let report = collect(&store, &filter, Some(Path::new("/work/two")), None).unwrap();
+        assert_eq!(report.sessions_scanned, 2);
+        assert_eq!(report.matches.len(), 1);
+
+        let report = collect(&store, &filter, None, Some(1)).unwrap();
+        assert_eq!(report.matches[0].session_id, "a");
+    }
+
+    #[test]
+    fn search_hit_csv_and_json() {
+        let store = MemoryBackend::new();
+        session_with_gate(&store, "a", "/work/one", 0, "Bash:git push");
+        let filter = TraceFilter::parse(&["gate_blocked".to_string()], None, None, None).unwrap();
+        let report = collect(&store, &filter, None, None).unwrap();
+
+        let hit = &report.matches[0];
+        let fields = hit.csv_fields();
+        assert_eq!(fields.len(), SearchHit::HEADERS.len());
+        assert_eq!(fields[4], "gate_blocked");
+
+        let value = serde_json::to_value(hit).unwrap();
+        assert_eq!(value["session_id"], "a");
+        assert_eq!(value["event_type"], "gate_blocked");
+    }
+}
@@ -1,11 +1,13 @@
 //! Stats command for template A/B test performance.
 
+use crate::cli::format::{CsvRecord, OutputFormat, print_machine};
 use crate::core::state::AttemptOutcome;
 use crate::error::Result;
-use crate::storage::MessageStore;
 use crate::storage::file::{FileBackend, get_roz_home};
-use chrono::{Duration, Utc};
-use std::collections::HashMap;
+use crate::storage::{IncidentKind, MessageStore};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 
 /// Template statistics for A/B testing analysis.
 #[derive(Debug, Default)]
@@ -42,6 +44,8 @@ impl TemplateStats {
             AttemptOutcome::BadSessionId => {
                 self.bad_session_id += 1;
             }
+            // A human decided; says nothing about the template
+            AttemptOutcome::Overridden => {}
         }
     }
 
@@ -68,6 +72,247 @@ impl TemplateStats {
             f64::from(self.total_blocks) / f64::from(self.success_count)
         }
     }
+
+    /// Number of resolved attempts (successes plus failures).
+    fn resolved(&self) -> u32 {
+        self.success_count + self.failure_count()
+    }
+
+    /// 95% Wilson score interval for the success rate, as percentages.
+    fn confidence_interval(&self) -> (f64, f64) {
+        let (low, high) = wilson_interval(self.success_count, self.resolved(), Z_95);
+        (low * 100.0, high * 100.0)
+    }
+}
+
+/// z-score for a two-sided 95% confidence level.
+const Z_95: f64 = 1.959_963_984_540_054;
+
+/// p-value threshold for calling a difference significant.
+const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+/// Wilson score interval for a binomial proportion.
+///
+/// Better behaved than the normal approximation for small samples and
+/// rates near 0% or 100%. Returns `(0.0, 0.0)` when there are no trials.
+fn wilson_interval(successes: u32, trials: u32, z: f64) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 0.0);
+    }
+
+    let n = f64::from(trials);
+    let p = f64::from(successes) / n;
+    let z2 = z * z;
+
+    let denom = 1.0 + z2 / n;
+    let center = (p + z2 / (2.0 * n)) / denom;
+    let margin = (z / denom) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+
+    ((center - margin).max(0.0), (center + margin).min(1.0))
+}
+
+/// Two-sided two-proportion z-test.
+///
+/// Returns the p-value for the null hypothesis that both templates have the
+/// same success rate, or `None` if either sample is empty or the pooled rate
+/// is 0% or 100% (no variance to test against).
+fn two_proportion_p_value(a: &TemplateStats, b: &TemplateStats) -> Option<f64> {
+    let (n1, n2) = (a.resolved(), b.resolved());
+    if n1 == 0 || n2 == 0 {
+        return None;
+    }
+
+    let (n1, n2) = (f64::from(n1), f64::from(n2));
+    let (s1, s2) = (f64::from(a.success_count), f64::from(b.success_count));
+    let pooled = (s1 + s2) / (n1 + n2);
+    let se = (pooled * (1.0 - pooled) * (1.0 / n1 + 1.0 / n2)).sqrt();
+    if se == 0.0 {
+        return None;
+    }
+
+    let z = (s1 / n1 - s2 / n2) / se;
+    Some(2.0 * (1.0 - normal_cdf(z.abs())))
+}
+
+/// Standard normal cumulative distribution function.
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// Error function (Abramowitz & Stegun 7.1.26, max error 1.5e-7).
+fn erf(x: f64) -> f64 {
+    let sign = if x < 0.0 { -1.0 } else { 1.0 };
+    let x = x.abs();
+
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+
+    sign * (1.0 - poly * (-x * x).exp())
+}
+
+/// Outcome of comparing templates against each other.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Verdict {
+    /// Fewer than two templates have enough resolved attempts.
+    InsufficientData,
+    /// The leader is not significantly better than every other template.
+    NoSignificantDifference {
+        /// Template with the highest success rate.
+        leader: String,
+    },
+    /// The leader beats every other eligible template at `SIGNIFICANCE_LEVEL`.
+    Winner {
+        /// Winning template.
+        template_id: String,
+        /// Largest p-value across the pairwise comparisons.
+        p_value: f64,
+    },
+}
+
+/// Decide whether one template is significantly better than the rest.
+///
+/// Only templates with at least `min_samples` resolved attempts take part.
+/// The leader (highest success rate, ties broken by name) must beat every
+/// other eligible template with `p < SIGNIFICANCE_LEVEL`.
+fn compare_templates(stats: &HashMap<String, TemplateStats>, min_samples: u32) -> Verdict {
+    let mut eligible: Vec<(&String, &TemplateStats)> = stats
+        .iter()
+        .filter(|(_, s)| s.resolved() >= min_samples.max(1))
+        .collect();
+
+    if eligible.len() < 2 {
+        return Verdict::InsufficientData;
+    }
+
+    eligible.sort_by(|(a_id, a), (b_id, b)| {
+        b.success_rate()
+            .total_cmp(&a.success_rate())
+            .then_with(|| a_id.cmp(b_id))
+    });
+
+    let (leader_id, leader) = eligible[0];
+    let mut worst_p: f64 = 0.0;
+    for (_, other) in &eligible[1..] {
+        match two_proportion_p_value(leader, other) {
+            Some(p) if p < SIGNIFICANCE_LEVEL && leader.success_rate() > other.success_rate() => {
+                worst_p = worst_p.max(p);
+            }
+            _ => {
+                return Verdict::NoSignificantDifference {
+                    leader: leader_id.clone(),
+                };
+            }
+        }
+    }
+
+    Verdict::Winner {
+        template_id: leader_id.clone(),
+        p_value: worst_p,
+    }
+}
+
+/// Per-template row in `roz stats` machine-readable output.
+#[derive(Debug, Serialize)]
+pub struct TemplateRow {
+    /// Template ID.
+    pub template_id: String,
+    /// Attempts that ended with a posted decision.
+    pub success: u32,
+    /// Attempts that failed (sum of the failure columns).
+    pub failure: u32,
+    /// Attempts not yet resolved.
+    pub pending: u32,
+    /// Failures where the reviewer was never spawned.
+    pub not_spawned: u32,
+    /// Failures where the reviewer exited without a decision.
+    pub no_decision: u32,
+    /// Failures where the decision used a wrong session ID.
+    pub bad_session_id: u32,
+    /// Average blocks needed for successful reviews.
+    pub avg_blocks: f64,
+    /// Success rate as a percentage of resolved attempts.
+    pub success_rate: f64,
+    /// Lower bound of the 95% Wilson interval (percent).
+    pub ci_low: f64,
+    /// Upper bound of the 95% Wilson interval (percent).
+    pub ci_high: f64,
+}
+
+impl TemplateRow {
+    fn new(template_id: &str, stats: &TemplateStats) -> Self {
+        let (ci_low, ci_high) = stats.confidence_interval();
+        Self {
+            template_id: template_id.to_string(),
+            success: stats.success_count,
+            failure: stats.failure_count(),
+            pending: stats.pending,
+            not_spawned: stats.not_spawned,
+            no_decision: stats.no_decision,
+            bad_session_id: stats.bad_session_id,
+            avg_blocks: stats.avg_blocks(),
+            success_rate: stats.success_rate(),
+            ci_low,
+            ci_high,
+        }
+    }
+}
+
+impl CsvRecord for TemplateRow {
+    const HEADERS: &'static [&'static str] = &[
+        "template_id",
+        "success",
+        "failure",
+        "pending",
+        "not_spawned",
+        "no_decision",
+        "bad_session_id",
+        "avg_blocks",
+        "success_rate",
+        "ci_low",
+        "ci_high",
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.template_id.clone(),
+            self.success.to_string(),
+            self.failure.to_string(),
+            self.pending.to_string(),
+            self.not_spawned.to_string(),
+            self.no_decision.to_string(),
+            self.bad_session_id.to_string(),
+            format!("{:.3}", self.avg_blocks),
+            format!("{:.3}", self.success_rate),
+            format!("{:.3}", self.ci_low),
+            format!("{:.3}", self.ci_high),
+        ]
+    }
+}
+
+/// Template statistics report for `roz stats`.
+#[derive(Debug, Serialize)]
+pub struct StatsReport {
+    /// Number of days looked back.
+    pub days: u32,
+    /// Resolved attempts each template needs before a winner is named.
+    pub min_samples: u32,
+    /// Sessions created within the window.
+    pub sessions_analyzed: u32,
+    /// Sessions that recorded at least one review attempt.
+    pub sessions_with_attempts: u32,
+    /// Per-template results, sorted by template ID.
+    pub templates: Vec<TemplateRow>,
+    /// Significance verdict across templates.
+    pub verdict: Verdict,
+    /// Incidents (hooks that could not check review state) within the window, by kind.
+    pub incidents: BTreeMap<IncidentKind, u32>,
+    /// Raw per-template counters (for table rendering).
+    #[serde(skip)]
+    by_template: HashMap<String, TemplateStats>,
 }
 
 /// Run the stats command.
@@ -75,19 +320,72 @@ impl TemplateStats {
 /// # Arguments
 ///
 /// * `days` - Number of days to look back (default 30).
+/// * `min_samples` - Resolved attempts each template needs before a winner
+///   can be named.
+/// * `format` - Output format.
 ///
 /// # Errors
 ///
 /// Returns an error if storage operations fail.
-pub fn run(days: u32) -> Result<()> {
+pub fn run(days: u32, min_samples: u32, format: OutputFormat) -> Result<()> {
+    let store = FileBackend::new(get_roz_home())?;
+    let report = collect(&store, days, min_samples)?;
+
+    if print_machine(format, &report, &report.templates)? {
+        return Ok(());
+    }
+
+    let stats = &report.by_template;
+    if stats.is_empty() {
+        println!("No template statistics available for the last {days} days.");
+    } else {
+        render_stats_table(stats, days);
+        render_failure_breakdown(stats);
+        render_significance(stats.len(), &report.verdict, min_samples);
+    }
+
+    println!("\nSessions analyzed: {}", report.sessions_analyzed);
+    println!(
+        "Sessions with review attempts: {}",
+        report.sessions_with_attempts
+    );
+    render_incidents(&report.incidents);
+
+    Ok(())
+}
+
+/// Collect template statistics from sessions created in the last `days` days.
+///
+/// # Errors
+///
+/// Returns an error if storage operations fail.
+pub fn collect(store: &dyn MessageStore, days: u32, min_samples: u32) -> Result<StatsReport> {
+    collect_at(store, days, min_samples, Utc::now())
+}
+
+/// Collect template statistics for the `days` days before `now`.
+///
+/// # Errors
+///
+/// Returns an error if storage operations fail.
+pub fn collect_at(
+    store: &dyn MessageStore,
+    days: u32,
+    min_samples: u32,
+    now: DateTime<Utc>,
+) -> Result<StatsReport> {
     /// Maximum number of sessions to analyze.
     /// Set high enough to effectively handle any realistic number of sessions.
     const MAX_SESSIONS_TO_ANALYZE: usize = 1_000_000;
 
-    let store = FileBackend::new(get_roz_home())?;
-    let cutoff = Utc::now() - Duration::days(i64::from(days));
+    let cutoff = now - Duration::days(i64::from(days));
     let sessions = store.list_sessions(MAX_SESSIONS_TO_ANALYZE)?;
 
+    let mut incidents = BTreeMap::new();
+    for incident in store.incidents_since(cutoff)? {
+        *incidents.entry(incident.kind).or_insert(0) += 1;
+    }
+
     let mut stats: HashMap<String, TemplateStats> = HashMap::new();
     let mut total_sessions = 0;
     let mut sessions_with_attempts = 0;
@@ -110,31 +408,42 @@ pub fn run(days: u32) -> Result<()> {
         }
     }
 
-    if stats.is_empty() {
-        println!("No template statistics available for the last {days} days.");
-        println!("\nSessions analyzed: {total_sessions}");
-        println!("Sessions with review attempts: {sessions_with_attempts}");
-        return Ok(());
-    }
-
-    render_stats_table(&stats, days);
-    render_failure_breakdown(&stats);
-
-    println!("\nSessions analyzed: {total_sessions}");
-    println!("Sessions with review attempts: {sessions_with_attempts}");
+    let mut templates: Vec<TemplateRow> = stats
+        .iter()
+        .map(|(id, stat)| TemplateRow::new(id, stat))
+        .collect();
+    templates.sort_by(|a, b| a.template_id.cmp(&b.template_id));
+
+    Ok(StatsReport {
+        days,
+        min_samples,
+        sessions_analyzed: total_sessions,
+        sessions_with_attempts,
+        templates,
+        verdict: compare_templates(&stats, min_samples),
+        incidents,
+        by_template: stats,
+    })
+}
 
-    Ok(())
+/// Render incident counts by kind.
+fn render_incidents(incidents: &BTreeMap<IncidentKind, u32>) {
+    let total: u32 = incidents.values().sum();
+    println!("Incidents (hooks could not check review state): {total}");
+    for (kind, count) in incidents {
+        println!("  {:<18} {count}", kind.as_str());
+    }
 }
 
 /// Render the stats table.
 fn render_stats_table(stats: &HashMap<String, TemplateStats>, days: u32) {
     println!("Template Performance (last {days} days):");
-    println!("{}", "─".repeat(70));
+    println!("{}", "─".repeat(84));
     println!(
-        "{:<12} {:>10} {:>10} {:>12} {:>14}",
-        "Template", "Success", "Failure", "Avg Blocks", "Success Rate"
+        "{:<12} {:>10} {:>10} {:>12} {:>14} {:>18}",
+        "Template", "Success", "Failure", "Avg Blocks", "Success Rate", "95% CI"
     );
-    println!("{}", "─".repeat(70));
+    println!("{}", "─".repeat(84));
 
     // Sort by template name for consistent output
     let mut template_ids: Vec<_> = stats.keys().collect();
@@ -142,16 +451,49 @@ fn render_stats_table(stats: &HashMap<String, TemplateStats>, days: u32) {
 
     for template_id in template_ids {
         let stat = &stats[template_id];
+        let ci = if stat.resolved() == 0 {
+            "-".to_string()
+        } else {
+            let (low, high) = stat.confidence_interval();
+            format!("{low:.1}%–{high:.1}%")
+        };
         println!(
-            "{:<12} {:>10} {:>10} {:>12.1} {:>13.1}%",
+            "{:<12} {:>10} {:>10} {:>12.1} {:>13.1}% {:>18}",
             template_id,
             stat.success_count,
             stat.failure_count(),
             stat.avg_blocks(),
-            stat.success_rate()
+            stat.success_rate(),
+            ci
         );
     }
-    println!("{}", "─".repeat(70));
+    println!("{}", "─".repeat(84));
+}
+
+/// Render the significance test between templates.
+fn render_significance(template_count: usize, verdict: &Verdict, min_samples: u32) {
+    // Nothing to compare with a single template
+    if template_count < 2 {
+        return;
+    }
+
+    println!("\nSignificance (two-proportion z-test, α = {SIGNIFICANCE_LEVEL}):");
+    match verdict {
+        Verdict::InsufficientData => {
+            println!(
+                "  Not enough data: need at least two templates with {min_samples}+ resolved attempts."
+            );
+        }
+        Verdict::NoSignificantDifference { leader } => {
+            println!("  Leader: {leader} (not significantly better than all others)");
+        }
+        Verdict::Winner {
+            template_id,
+            p_value,
+        } => {
+            println!("  Winner: {template_id} (p = {p_value:.4})");
+        }
+    }
 }
 
 /// Render failure breakdown.
@@ -282,4 +624,184 @@ mod tests {
         assert_eq!(stats.success_count, 1);
         assert_eq!(stats.success_rate(), 100.0); // Only counts resolved attempts
     }
+
+    fn make_stats(successes: u32, failures: u32) -> TemplateStats {
+        TemplateStats {
+            success_count: successes,
+            total_blocks: successes,
+            not_spawned: failures,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn wilson_interval_empty() {
+        assert_eq!(wilson_interval(0, 0, Z_95), (0.0, 0.0));
+    }
+
+    #[test]
+    fn wilson_interval_contains_rate() {
+        let (low, high) = wilson_interval(75, 100, Z_95);
+        assert!(low < 0.75 && 0.75 < high);
+        // Known value: Wilson 95% CI for 75/100 is roughly [0.657, 0.825]
+        assert!((low - 0.657).abs() < 0.005, "low: {low}");
+        assert!((high - 0.825).abs() < 0.005, "high: {high}");
+    }
+
+    #[test]
+    fn wilson_interval_stays_in_bounds() {
+        let (low, high) = wilson_interval(10, 10, Z_95);
+        assert!(low > 0.0);
+        assert!(high <= 1.0);
+
+        let (low, high) = wilson_interval(0, 10, Z_95);
+        assert_eq!(low, 0.0);
+        assert!(high < 1.0);
+    }
+
+    #[test]
+    fn normal_cdf_known_values() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-6);
+        assert!((normal_cdf(Z_95) - 0.975).abs() < 1e-4);
+        assert!((normal_cdf(-Z_95) - 0.025).abs() < 1e-4);
+    }
+
+    #[test]
+    fn p_value_identical_rates_is_one() {
+        let a = make_stats(50, 50);
+        let b = make_stats(50, 50);
+        let p = two_proportion_p_value(&a, &b).unwrap();
+        assert!((p - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn p_value_large_difference_is_small() {
+        let a = make_stats(90, 10);
+        let b = make_stats(50, 50);
+        let p = two_proportion_p_value(&a, &b).unwrap();
+        assert!(p < 0.001, "p: {p}");
+    }
+
+    #[test]
+    fn p_value_undefined_without_variance() {
+        let a = make_stats(10, 0);
+        let b = make_stats(10, 0);
+        assert!(two_proportion_p_value(&a, &b).is_none());
+        assert!(two_proportion_p_value(&a, &TemplateStats::default()).is_none());
+    }
+
+    #[test]
+    fn compare_templates_requires_min_samples() {
+        let mut stats = HashMap::new();
+        stats.insert("v1".to_string(), make_stats(9, 1));
+        stats.insert("v2".to_string(), make_stats(2, 8));
+
+        assert_eq!(compare_templates(&stats, 30), Verdict::InsufficientData);
+    }
+
+    #[test]
+    fn compare_templates_names_significant_winner() {
+        let mut stats = HashMap::new();
+        stats.insert("v1".to_string(), make_stats(90, 10));
+        stats.insert("v2".to_string(), make_stats(50, 50));
+
+        let verdict = compare_templates(&stats, 30);
+        assert!(
+            matches!(verdict, Verdict::Winner { ref template_id, .. } if template_id == "v1"),
+            "unexpected verdict: {verdict:?}"
+        );
+    }
+
+    #[test]
+    fn compare_templates_no_winner_for_close_rates() {
+        let mut stats = HashMap::new();
+        stats.insert("v1".to_string(), make_stats(52, 48));
+        stats.insert("v2".to_string(), make_stats(50, 50));
+
+        assert_eq!(
+            compare_templates(&stats, 30),
+            Verdict::NoSignificantDifference {
+                leader: "v1".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn compare_templates_ignores_small_templates() {
+        let mut stats = HashMap::new();
+        stats.insert("v1".to_string(), make_stats(90, 10));
+        stats.insert("v2".to_string(), make_stats(50, 50));
+        // Perfect but tiny sample must not be crowned
+        stats.insert("v3".to_string(), make_stats(3, 0));
+
+        let verdict = compare_templates(&stats, 30);
+        assert!(
+            matches!(verdict, Verdict::Winner { ref template_id, .. } if template_id == "v1"),
+            "unexpected verdict: {verdict:?}"
+        );
+    }
+
+    #[test]
+    fn collect_report_serializes_rows_and_verdict() {
+        use crate::core::SessionState;
+        use crate::core::state::ReviewAttempt;
+        use crate::storage::MemoryBackend;
+
+        let store = MemoryBackend::new();
+        let mut state = SessionState::new("stats-session");
+        state.review.attempts.push(ReviewAttempt {
+            template_id: "v1".to_string(),
+            timestamp: Utc::now(),
+            outcome: AttemptOutcome::Success {
+                decision_type: "complete".to_string(),
+                blocks_needed: 1,
+            },
+        });
+        store.put_session(&state).unwrap();
+
+        let report = collect(&store, 30, 30).unwrap();
+        assert_eq!(report.sessions_analyzed, 1);
+        assert_eq!(report.sessions_with_attempts, 1);
+        assert_eq!(report.templates.len(), 1);
+        assert_eq!(report.templates[0].success, 1);
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["verdict"]["type"], "insufficient_data");
+        assert_eq!(json["templates"][0]["template_id"], "v1");
+        assert!(json.get("by_template").is_none());
+        assert_eq!(
+            report.templates[0].csv_fields().len(),
+            TemplateRow::HEADERS.len()
+        );
+    }
+
+    #[test]
+    fn collect_counts_incidents_by_kind() {
+        use crate::storage::{Incident, MemoryBackend};
+
+        let store = MemoryBackend::new();
+        for kind in [
+            IncidentKind::StorageRead,
+            IncidentKind::StorageRead,
+            IncidentKind::InputParse,
+        ] {
+            store
+                .record_incident(&Incident {
+                    timestamp: Utc::now(),
+                    hook: "stop".to_string(),
+                    kind,
+                    session_id: None,
+                    message: "boom".to_string(),
+                    blocked: false,
+                })
+                .unwrap();
+        }
+
+        let report = collect(&store, 30, 30).unwrap();
+        assert_eq!(report.incidents[&IncidentKind::StorageRead], 2);
+        assert_eq!(report.incidents[&IncidentKind::InputParse], 1);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["incidents"]["storage_read"], 2);
+    }
 }
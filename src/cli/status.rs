@@ -0,0 +1,620 @@
+//! `roz status` command implementation.
+
+use crate::cli::format::{CsvRecord, OutputFormat, print_machine};
+use crate::config::{ApprovalScope, Config, load_config};
+use crate::core::circuit_breaker;
+use crate::core::hooks::is_gate_approved;
+use crate::core::state::{DecidedBy, Decision, GateTrigger, SessionState, TripCause};
+use crate::error::{Error, Result};
+use crate::storage::MessageStore;
+use crate::storage::file::{FileBackend, get_roz_home};
+use chrono::{DateTime, Duration, Local, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Maximum number of sessions to scan when resolving by working directory.
+const MAX_SESSIONS_TO_SCAN: usize = 1_000_000;
+
+/// Circuit breaker state for a session.
+#[derive(Debug, Serialize)]
+pub struct BreakerStatus {
+    /// Whether the breaker is currently tripped.
+    pub tripped: bool,
+
+    /// When the breaker tripped.
+    pub tripped_at: Option<DateTime<Utc>>,
+
+    /// Blocks allowed before the breaker trips.
+    pub max_blocks: u32,
+
+    /// Blocks since the reviewer last posted a decision.
+    pub not_spawned_blocks: u32,
+
+    /// Limit for `not_spawned_blocks`.
+    pub max_not_spawned_blocks: u32,
+
+    /// Blocks for unresolved review issues.
+    pub issues_blocks: u32,
+
+    /// Limit for `issues_blocks`.
+    pub max_issues_blocks: u32,
+
+    /// Why the breaker last tripped.
+    pub trip_cause: Option<TripCause>,
+
+    /// Trips since the last COMPLETE decision.
+    pub trip_count: u32,
+
+    /// Seconds until the breaker resets (only while tripped).
+    pub cooldown_remaining_seconds: Option<i64>,
+
+    /// `PreToolUse` denials per gate pattern.
+    pub gate_denials: BTreeMap<String, u32>,
+
+    /// Denials allowed per gate pattern before it trips.
+    pub max_gate_denials: u32,
+
+    /// Gate patterns whose breaker is tripped and still cooling down.
+    pub tripped_gates: Vec<String>,
+}
+
+/// Gate approval state for a session.
+#[derive(Debug, Serialize)]
+pub struct ApprovalStatus {
+    /// Configured approval scope.
+    pub scope: ApprovalScope,
+
+    /// When the gate was last approved.
+    pub approved_at: Option<DateTime<Utc>>,
+
+    /// Whether gated tools are currently allowed without a new review.
+    pub active: bool,
+
+    /// Seconds until the approval expires (only with a TTL configured).
+    pub ttl_remaining_seconds: Option<i64>,
+}
+
+/// Review status for a session (`roz status --format json` schema).
+#[derive(Debug, Serialize)]
+pub struct StatusReport {
+    /// Session identifier.
+    pub session_id: String,
+
+    /// Working directory the session last ran in.
+    pub cwd: Option<PathBuf>,
+
+    /// When the session was last updated.
+    pub updated_at: DateTime<Utc>,
+
+    /// Whether review is enabled for this session.
+    pub review_enabled: bool,
+
+    /// Current decision (tagged by `type`: `pending`, `complete`, `issues`).
+    pub decision: Decision,
+
+    /// Who made the current decision (`reviewer` or `human`).
+    pub decided_by: Option<DecidedBy>,
+
+    /// Number of times the stop hook has blocked.
+    pub block_count: u32,
+
+    /// Circuit breaker state.
+    pub circuit_breaker: BreakerStatus,
+
+    /// Tool call that triggered a gate, if any.
+    pub gate_trigger: Option<GateTrigger>,
+
+    /// Gate approval state.
+    pub approval: ApprovalStatus,
+
+    /// What has to happen next for the session to proceed.
+    pub next_action: String,
+}
+
+impl StatusReport {
+    /// Build a status report from session state.
+    #[must_use]
+    pub fn from_state(state: &SessionState, config: &Config) -> Self {
+        let now = Utc::now();
+        let circuit_breaker = breaker_status(state, config, now);
+        let approval = approval_status(state, config, now);
+        let next_action = next_action(state, &circuit_breaker, &approval);
+
+        Self {
+            session_id: state.session_id.clone(),
+            cwd: state.cwd.clone(),
+            updated_at: state.updated_at,
+            review_enabled: state.review.enabled,
+            decision: state.review.decision.clone(),
+            decided_by: state.review.decided_by,
+            block_count: state.review.block_count,
+            circuit_breaker,
+            gate_trigger: state.review.gate_trigger.clone(),
+            approval,
+            next_action,
+        }
+    }
+}
+
+impl CsvRecord for StatusReport {
+    const HEADERS: &'static [&'static str] = &[
+        "session_id",
+        "cwd",
+        "review_enabled",
+        "decision",
+        "block_count",
+        "breaker_tripped",
+        "cooldown_remaining_seconds",
+        "gate_tool",
+        "approval_scope",
+        "approval_active",
+        "ttl_remaining_seconds",
+        "next_action",
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        let optional = |v: Option<i64>| v.map(|n| n.to_string()).unwrap_or_default();
+        vec![
+            self.session_id.clone(),
+            self.cwd
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default(),
+            self.review_enabled.to_string(),
+            decision_name(&self.decision).to_string(),
+            self.block_count.to_string(),
+            self.circuit_breaker.tripped.to_string(),
+            optional(self.circuit_breaker.cooldown_remaining_seconds),
+            self.gate_trigger
+                .as_ref()
+                .map(|g| g.tool_name.clone())
+                .unwrap_or_default(),
+            scope_name(&self.approval.scope).to_string(),
+            self.approval.active.to_string(),
+            optional(self.approval.ttl_remaining_seconds),
+            self.next_action.clone(),
+        ]
+    }
+}
+
+/// Run the status command.
+///
+/// Shows the review state for `session_id`, or for the most recent session
+/// recorded in the current directory.
+///
+/// # Errors
+///
+/// Returns an error if no matching session exists or storage fails.
+pub fn run(session_id: Option<&str>, format: OutputFormat) -> Result<()> {
+    let store = FileBackend::new(get_roz_home())?;
+    let config = load_config()?;
+    let cwd = std::env::current_dir()?;
+    let report = collect(&store, &config, session_id, &cwd)?;
+
+    if print_machine(format, &report, std::slice::from_ref(&report))? {
+        return Ok(());
+    }
+
+    render(&report);
+    Ok(())
+}
+
+/// Collect the status report for a session.
+///
+/// With `session_id`, that session is used. Otherwise the session whose
+/// recorded `cwd` is `cwd` itself, or failing that its closest ancestor; the
+/// most recently updated session wins a tie.
+///
+/// # Errors
+///
+/// Returns `SessionNotFound` if no session matches, or a storage error.
+pub fn collect(
+    store: &dyn MessageStore,
+    config: &Config,
+    session_id: Option<&str>,
+    cwd: &Path,
+) -> Result<StatusReport> {
+    let state = resolve_session(store, session_id, cwd)?;
+    Ok(StatusReport::from_state(&state, config))
+}
+
+/// Find the session to report on.
+pub(crate) fn resolve_session(
+    store: &dyn MessageStore,
+    session_id: Option<&str>,
+    cwd: &Path,
+) -> Result<SessionState> {
+    if let Some(id) = session_id {
+        return store
+            .get_session(id)?
+            .ok_or_else(|| Error::SessionNotFound(id.to_string()));
+    }
+
+    // A session at `$HOME` or `/` must not shadow the project's own session,
+    // so the deepest matching directory wins before recency
+    let summary = store
+        .list_sessions(MAX_SESSIONS_TO_SCAN)?
+        .into_iter()
+        .filter_map(|s| Some((cwd_match_depth(s.cwd.as_deref(), cwd)?, s)))
+        .max_by_key(|(depth, s)| (*depth, s.updated_at))
+        .map(|(_, s)| s)
+        .ok_or_else(|| {
+            Error::SessionNotFound(format!("no session recorded for {}", cwd.display()))
+        })?;
+
+    store
+        .get_session(&summary.session_id)?
+        .ok_or(Error::SessionNotFound(summary.session_id))
+}
+
+/// Depth of `session_cwd` if it is `cwd` or one of its ancestors (`None`
+/// otherwise); deeper directories match a session more closely.
+pub(crate) fn cwd_match_depth(session_cwd: Option<&Path>, cwd: &Path) -> Option<usize> {
+    session_cwd
+        .filter(|dir| cwd.starts_with(dir))
+        .map(|dir| dir.components().count())
+}
+
+/// Compute circuit breaker status.
+pub(crate) fn breaker_status(
+    state: &SessionState,
+    config: &Config,
+    now: DateTime<Utc>,
+) -> BreakerStatus {
+    let tripped = state.review.circuit_breaker_tripped;
+    let tripped_at = state.review.circuit_breaker_tripped_at;
+    let cb = &config.circuit_breaker;
+    let cooldown_remaining_seconds = match (tripped, tripped_at) {
+        (true, Some(at)) => {
+            let resets_at = at + circuit_breaker::cooldown(state, cb);
+            Some((resets_at - now).num_seconds().max(0))
+        }
+        _ => None,
+    };
+    let gate_cooldown = Duration::seconds(i64::try_from(cb.cooldown_seconds).unwrap_or(i64::MAX));
+
+    BreakerStatus {
+        tripped,
+        tripped_at,
+        max_blocks: cb.max_blocks,
+        not_spawned_blocks: state.review.not_spawned_blocks,
+        max_not_spawned_blocks: cb.not_spawned_limit(),
+        issues_blocks: state.review.issues_blocks,
+        max_issues_blocks: cb.issues_limit(),
+        trip_cause: state.review.trip_cause,
+        trip_count: state.review.trip_count,
+        cooldown_remaining_seconds,
+        gate_denials: state.review.gate_denials.clone(),
+        max_gate_denials: cb.gate_denial_limit(),
+        tripped_gates: state
+            .review
+            .gate_tripped_at
+            .iter()
+            .filter(|(_, at)| now < **at + gate_cooldown)
+            .map(|(pattern, _)| pattern.clone())
+            .collect(),
+    }
+}
+
+/// Compute gate approval status.
+fn approval_status(state: &SessionState, config: &Config, now: DateTime<Utc>) -> ApprovalStatus {
+    let gates = &config.review.gates;
+    let approved_at = state.review.gate_approved_at;
+    let active = is_gate_approved(state, gates, now);
+    let ttl_remaining_seconds = match (active, approved_at, gates.approval_ttl_seconds) {
+        (true, Some(at), Some(ttl)) => {
+            let ttl = i64::try_from(ttl).unwrap_or(i64::MAX);
+            Some((at + Duration::seconds(ttl) - now).num_seconds().max(0))
+        }
+        _ => None,
+    };
+
+    ApprovalStatus {
+        scope: gates.approval_scope.clone(),
+        approved_at,
+        active,
+        ttl_remaining_seconds,
+    }
+}
+
+/// Describe what has to happen next for the session to proceed.
+fn next_action(state: &SessionState, breaker: &BreakerStatus, approval: &ApprovalStatus) -> String {
+    if breaker.tripped {
+        return match breaker.cooldown_remaining_seconds {
+            Some(secs) if secs > 0 => format!(
+                "None: circuit breaker tripped, review resumes in {}",
+                format_seconds(secs)
+            ),
+            _ => "None: circuit breaker cooldown elapsed, review resumes on next stop".to_string(),
+        };
+    }
+
+    if !state.review.enabled {
+        return "None: review is not enabled for this session".to_string();
+    }
+
+    match &state.review.decision {
+        Decision::Pending => format!(
+            "Spawn roz:roz with SESSION_ID={} to review the work",
+            state.session_id
+        ),
+        Decision::Issues { .. } => {
+            "Fix the reported issues, then spawn roz:roz again to re-review".to_string()
+        }
+        Decision::Complete { .. } if state.review.gate_trigger.is_some() && !approval.active => {
+            "Gate approval no longer valid: the next gated tool call needs a fresh review"
+                .to_string()
+        }
+        Decision::Complete { .. } => "None: review approved".to_string(),
+    }
+}
+
+/// Short name for a decision.
+pub(crate) fn decision_name(decision: &Decision) -> &'static str {
+    match decision {
+        Decision::Pending => "pending",
+        Decision::Complete { .. } => "complete",
+        Decision::Issues { .. } => "issues",
+    }
+}
+
+/// Short name for an approval scope.
+fn scope_name(scope: &ApprovalScope) -> &'static str {
+    match scope {
+        ApprovalScope::Session => "session",
+        ApprovalScope::Prompt => "prompt",
+        ApprovalScope::Tool => "tool",
+    }
+}
+
+/// Print the breaker and per-gate denial lines of a status table.
+pub(crate) fn render_breaker(breaker: &BreakerStatus) {
+    let cause = breaker.trip_cause.map_or("", TripCause::as_str);
+    match (breaker.tripped, breaker.cooldown_remaining_seconds) {
+        (false, _) => println!("Breaker:   ok"),
+        (true, Some(secs)) => println!(
+            "Breaker:   tripped {cause} (trip #{}, resets in {})",
+            breaker.trip_count,
+            format_seconds(secs)
+        ),
+        (true, None) => println!("Breaker:   tripped {cause}"),
+    }
+    if !breaker.gate_denials.is_empty() || !breaker.tripped_gates.is_empty() {
+        let tripped = breaker
+            .tripped_gates
+            .iter()
+            .map(|pattern| format!("`{pattern}` tripped"));
+        let counting = breaker
+            .gate_denials
+            .iter()
+            .filter(|(pattern, _)| !breaker.tripped_gates.contains(pattern))
+            .map(|(pattern, count)| format!("`{pattern}` {count}/{}", breaker.max_gate_denials));
+        let gates: Vec<String> = tripped.chain(counting).collect();
+        println!("Denials:   {}", gates.join(", "));
+    }
+}
+
+/// Format a number of seconds as e.g. "4m 10s".
+fn format_seconds(secs: i64) -> String {
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{h}h {m}m")
+    } else if m > 0 {
+        format!("{m}m {s}s")
+    } else {
+        format!("{s}s")
+    }
+}
+
+/// Render the status report as text.
+fn render(report: &StatusReport) {
+    let updated: DateTime<Local> = report.updated_at.into();
+
+    println!("Session:   {}", report.session_id);
+    if let Some(cwd) = &report.cwd {
+        println!("Directory: {}", cwd.display());
+    }
+    println!("Updated:   {}", updated.format("%Y-%m-%d %H:%M:%S"));
+    println!();
+
+    println!(
+        "Review:    {}",
+        if report.review_enabled {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+    let by = match report.decided_by {
+        Some(DecidedBy::Human) => " (by human)",
+        Some(DecidedBy::Reviewer) | None => "",
+    };
+    match &report.decision {
+        Decision::Pending => println!("Decision:  pending"),
+        Decision::Complete { summary, .. } => println!("Decision:  complete{by} - {summary}"),
+        Decision::Issues { summary, .. } => println!("Decision:  issues{by} - {summary}"),
+    }
+    let breaker = &report.circuit_breaker;
+    println!(
+        "Blocks:    {} (not spawned {}/{}, issues {}/{})",
+        report.block_count,
+        breaker.not_spawned_blocks,
+        breaker.max_not_spawned_blocks,
+        breaker.issues_blocks,
+        breaker.max_issues_blocks
+    );
+
+    render_breaker(breaker);
+
+    match &report.gate_trigger {
+        Some(gate) => println!(
+            "Gate:      {} (pattern: {})",
+            gate.tool_name, gate.pattern_matched
+        ),
+        None => println!("Gate:      none"),
+    }
+
+    let approval = &report.approval;
+    let scope = scope_name(&approval.scope);
+    match (approval.active, approval.ttl_remaining_seconds) {
+        (true, Some(secs)) => println!(
+            "Approval:  active, {scope} scope (expires in {})",
+            format_seconds(secs)
+        ),
+        (true, None) => println!("Approval:  active, {scope} scope"),
+        (false, _) if approval.approved_at.is_some() => {
+            println!("Approval:  expired ({scope} scope)");
+        }
+        (false, _) => println!("Approval:  none ({scope} scope)"),
+    }
+
+    println!("\nNext: {}", report.next_action);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryBackend;
+
+    fn session_in(store: &MemoryBackend, id: &str, cwd: &str, age_secs: i64) -> SessionState {
+        let mut state = SessionState::new(id);
+        state.created_at = Utc::now() - Duration::seconds(age_secs);
+        state.updated_at = state.created_at;
+        state.cwd = Some(PathBuf::from(cwd));
+        store.put_session(&state).unwrap();
+        state
+    }
+
+    #[test]
+    fn resolves_most_recent_session_for_cwd() {
+        let store = MemoryBackend::new();
+        session_in(&store, "old", "/work/project", 600);
+        session_in(&store, "new", "/work/project", 10);
+        session_in(&store, "other", "/work/other", 0);
+
+        let report = collect(
+            &store,
+            &Config::default(),
+            None,
+            Path::new("/work/project/src"),
+        )
+        .unwrap();
+        assert_eq!(report.session_id, "new");
+    }
+
+    #[test]
+    fn project_session_beats_newer_session_at_parent_dir() {
+        let store = MemoryBackend::new();
+        session_in(&store, "project", "/work/project", 600);
+        session_in(&store, "home", "/work", 0);
+        session_in(&store, "root", "/", 0);
+
+        let report = collect(
+            &store,
+            &Config::default(),
+            None,
+            Path::new("/work/project/src"),
+        )
+        .unwrap();
+        assert_eq!(report.session_id, "project");
+
+        // Outside the project the closest ancestor still applies
+        let report = collect(&store, &Config::default(), None, Path::new("/work/other")).unwrap();
+        assert_eq!(report.session_id, "home");
+    }
+
+    #[test]
+    fn explicit_session_overrides_cwd() {
+        let store = MemoryBackend::new();
+        session_in(&store, "a", "/work/a", 0);
+        session_in(&store, "b", "/work/b", 0);
+
+        let report = collect(&store, &Config::default(), Some("b"), Path::new("/work/a")).unwrap();
+        assert_eq!(report.session_id, "b");
+    }
+
+    #[test]
+    fn no_session_for_cwd_is_not_found() {
+        let store = MemoryBackend::new();
+        session_in(&store, "a", "/work/a", 0);
+
+        let result = collect(&store, &Config::default(), None, Path::new("/elsewhere"));
+        assert!(matches!(result, Err(Error::SessionNotFound(_))));
+    }
+
+    #[test]
+    fn pending_review_asks_for_reviewer() {
+        let mut state = SessionState::new("pending");
+        state.review.enabled = true;
+        state.review.block_count = 2;
+
+        let report = StatusReport::from_state(&state, &Config::default());
+        assert_eq!(report.block_count, 2);
+        assert!(!report.circuit_breaker.tripped);
+        assert!(report.next_action.contains("SESSION_ID=pending"));
+    }
+
+    #[test]
+    fn tripped_breaker_reports_cooldown() {
+        let mut state = SessionState::new("tripped");
+        state.review.circuit_breaker_tripped = true;
+        state.review.circuit_breaker_tripped_at = Some(Utc::now() - Duration::seconds(100));
+
+        let report = StatusReport::from_state(&state, &Config::default());
+        let remaining = report.circuit_breaker.cooldown_remaining_seconds.unwrap();
+        assert!((195..=200).contains(&remaining), "remaining: {remaining}");
+        assert!(report.next_action.contains("circuit breaker"));
+    }
+
+    #[test]
+    fn gate_breaker_reports_expired_trips_as_counting() {
+        let mut state = SessionState::new("gates");
+        state
+            .review
+            .gate_denials
+            .insert("Bash:git push*".to_string(), 2);
+        state
+            .review
+            .gate_tripped_at
+            .insert("Bash:gh pr merge*".to_string(), Utc::now());
+        state.review.gate_tripped_at.insert(
+            "Bash:git push*".to_string(),
+            Utc::now() - Duration::hours(1),
+        );
+
+        let report = StatusReport::from_state(&state, &Config::default());
+        let breaker = &report.circuit_breaker;
+        assert_eq!(breaker.max_gate_denials, 3);
+        assert_eq!(breaker.gate_denials["Bash:git push*"], 2);
+        assert_eq!(breaker.tripped_gates, vec!["Bash:gh pr merge*".to_string()]);
+    }
+
+    #[test]
+    fn approval_ttl_remaining() {
+        let mut config = Config::default();
+        config.review.gates.approval_scope = ApprovalScope::Session;
+        config.review.gates.approval_ttl_seconds = Some(600);
+
+        let mut state = SessionState::new("approved");
+        state.review.enabled = true;
+        state.review.decision = Decision::Complete {
+            summary: "ok".to_string(),
+            second_opinions: None,
+        };
+        state.review.gate_approved_at = Some(Utc::now() - Duration::seconds(60));
+
+        let report = StatusReport::from_state(&state, &config);
+        assert!(report.approval.active);
+        let remaining = report.approval.ttl_remaining_seconds.unwrap();
+        assert!((535..=540).contains(&remaining), "remaining: {remaining}");
+        assert_eq!(report.next_action, "None: review approved");
+    }
+
+    #[test]
+    fn format_seconds_units() {
+        assert_eq!(format_seconds(42), "42s");
+        assert_eq!(format_seconds(250), "4m 10s");
+        assert_eq!(format_seconds(7260), "2h 1m");
+    }
+}
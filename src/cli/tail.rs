@@ -0,0 +1,427 @@
+//! `roz tail` command implementation.
+//!
+//! Follows a session's trace as the hooks write it. The session is polled
+//! through the storage backend, so no file watcher is needed and a session
+//! rewritten by an atomic rename is picked up like any other update. When
+//! following a directory, all sessions are scanned once; later polls only
+//! load sessions the store reports as written since the previous poll.
+
+use crate::cli::format::{OutputFormat, to_csv};
+use crate::cli::status::{cwd_match_depth, resolve_session};
+use crate::cli::trace::{TraceFilter, event_type_name};
+use crate::core::state::{SessionState, TraceEvent};
+use crate::error::{Error, Result};
+use crate::storage::MessageStore;
+use crate::storage::file::{FileBackend, get_roz_home};
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+/// Maximum payload length shown per event in the table.
+const PAYLOAD_PREVIEW_LEN: usize = 120;
+
+/// Which session `roz tail` follows.
+#[derive(Debug, Clone)]
+pub enum TailTarget {
+    /// A fixed session.
+    Session(String),
+
+    /// The latest session recorded in a directory, switching when a newer
+    /// one starts.
+    Cwd(PathBuf),
+}
+
+/// Follower state: which session is being tailed and which events were shown.
+#[derive(Debug)]
+pub struct Tail {
+    target: TailTarget,
+    filter: TraceFilter,
+    backlog: usize,
+    session_id: Option<String>,
+
+    /// How closely the followed session matches a `Cwd` target, as
+    /// (directory depth, `updated_at`).
+    rank: Option<(usize, DateTime<Utc>)>,
+
+    /// When a `Cwd` target was last resolved.
+    checked_at: Option<DateTime<Utc>>,
+
+    /// Last event returned (or skipped by the filter), as (id, timestamp).
+    last_seen: Option<(String, DateTime<Utc>)>,
+}
+
+impl Tail {
+    /// Create a follower. Only events passing `filter` are returned; `backlog`
+    /// is how many existing events to show when a session is first seen.
+    #[must_use]
+    pub fn new(target: TailTarget, filter: TraceFilter, backlog: usize) -> Self {
+        Self {
+            target,
+            filter,
+            backlog,
+            session_id: None,
+            rank: None,
+            checked_at: None,
+            last_seen: None,
+        }
+    }
+
+    /// Session currently being followed.
+    #[must_use]
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+
+    /// Poll once, returning matching events not returned before, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionNotFound` if a fixed session does not exist, or a
+    /// storage error.
+    pub fn poll(&mut self, store: &dyn MessageStore) -> Result<Vec<TraceEvent>> {
+        let Some(state) = self.resolve(store)? else {
+            return Ok(Vec::new());
+        };
+
+        let fresh = self.session_id.as_deref() != Some(state.session_id.as_str());
+        if fresh {
+            self.last_seen = None;
+            self.session_id = Some(state.session_id.clone());
+        }
+
+        let mut trace = state.trace;
+        let unseen = self.unseen_from(&trace);
+        let new = trace.split_off(unseen);
+        if let Some(last) = new.last() {
+            self.last_seen = Some((last.id.clone(), last.timestamp));
+        }
+
+        let mut events: Vec<TraceEvent> =
+            new.into_iter().filter(|e| self.filter.matches(e)).collect();
+        if fresh && events.len() > self.backlog {
+            events.drain(..events.len() - self.backlog);
+        }
+        Ok(events)
+    }
+
+    /// Index of the first event in `trace` not returned before.
+    ///
+    /// Events are appended, so everything after the last seen event is new.
+    /// If that event was compacted away, anything newer than it is.
+    fn unseen_from(&self, trace: &[TraceEvent]) -> usize {
+        let Some((id, timestamp)) = &self.last_seen else {
+            return 0;
+        };
+        trace.iter().rposition(|e| &e.id == id).map_or_else(
+            || {
+                trace
+                    .iter()
+                    .position(|e| e.timestamp > *timestamp)
+                    .unwrap_or(trace.len())
+            },
+            |i| i + 1,
+        )
+    }
+
+    /// Load the target session (`None` while no session exists in the
+    /// directory, or a directory's session has not changed since the last poll).
+    fn resolve(&mut self, store: &dyn MessageStore) -> Result<Option<SessionState>> {
+        let dir = match &self.target {
+            TailTarget::Session(id) => {
+                return store
+                    .get_session(id)?
+                    .map(Some)
+                    .ok_or_else(|| Error::SessionNotFound(id.clone()));
+            }
+            TailTarget::Cwd(dir) => dir.clone(),
+        };
+
+        let now = Utc::now();
+        let state = match self.checked_at {
+            Some(since) => self.updated_match(store, &dir, since)?,
+            None => match resolve_session(store, None, &dir) {
+                Ok(state) => Some(state),
+                Err(Error::SessionNotFound(_)) => None,
+                Err(e) => return Err(e),
+            },
+        };
+        self.checked_at = Some(now);
+
+        if let Some(state) = &state {
+            self.rank =
+                cwd_match_depth(state.cwd.as_deref(), &dir).map(|depth| (depth, state.updated_at));
+        }
+        Ok(state)
+    }
+
+    /// Among sessions written since `since`, the one matching `dir` best
+    /// (`resolve_session`'s ranking), if it is the followed session or ranks
+    /// above it.
+    fn updated_match(
+        &self,
+        store: &dyn MessageStore,
+        dir: &Path,
+        since: DateTime<Utc>,
+    ) -> Result<Option<SessionState>> {
+        let mut best: Option<((usize, DateTime<Utc>), SessionState)> = None;
+        for id in store.sessions_updated_since(since)? {
+            // The store may list files it cannot parse
+            let Ok(Some(state)) = store.get_session(&id) else {
+                continue;
+            };
+            let Some(depth) = cwd_match_depth(state.cwd.as_deref(), dir) else {
+                continue;
+            };
+            let rank = (depth, state.updated_at);
+            if best.as_ref().is_none_or(|(best_rank, _)| rank > *best_rank) {
+                best = Some((rank, state));
+            }
+        }
+        Ok(best
+            .filter(|(rank, state)| {
+                self.session_id.as_deref() == Some(state.session_id.as_str())
+                    || self.rank.is_none_or(|current| *rank > current)
+            })
+            .map(|(_, state)| state))
+    }
+}
+
+/// Run the tail command until interrupted.
+///
+/// # Errors
+///
+/// Returns an error if the session is not found or storage fails.
+pub fn run(
+    target: TailTarget,
+    filter: TraceFilter,
+    backlog: usize,
+    interval_ms: u64,
+    format: OutputFormat,
+) -> Result<()> {
+    let store = FileBackend::new(get_roz_home())?;
+    let mut tail = Tail::new(target, filter, backlog);
+    let mut current: Option<String> = None;
+    let mut header_printed = false;
+
+    loop {
+        let events = tail.poll(&store)?;
+
+        if format == OutputFormat::Table && tail.session_id() != current.as_deref() {
+            if let Some(id) = tail.session_id() {
+                println!("── session {id} ──");
+            }
+        }
+        current = tail.session_id().map(String::from);
+
+        print_events(format, &events, &mut header_printed)?;
+        thread::sleep(Duration::from_millis(interval_ms));
+    }
+}
+
+/// Print a batch of events in `format` (`json` prints one event per line).
+fn print_events(
+    format: OutputFormat,
+    events: &[TraceEvent],
+    header_printed: &mut bool,
+) -> Result<()> {
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Table => {
+            for event in events {
+                println!(
+                    "{}  {:<24} {}",
+                    event.timestamp.format("%H:%M:%S"),
+                    event_type_name(&event.event_type),
+                    payload_preview(&event.payload)
+                );
+            }
+        }
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            for event in events {
+                println!("{}", serde_json::to_string(event)?);
+            }
+        }
+        OutputFormat::Csv => {
+            let csv = to_csv(events);
+            // The header row only goes out once
+            let rows = if *header_printed {
+                csv.split_once('\n').map_or("", |(_, rows)| rows)
+            } else {
+                &csv
+            };
+            print!("{rows}");
+            *header_printed = true;
+        }
+    }
+    Ok(())
+}
+
+/// Compact one-line payload, truncated for the table.
+pub(crate) fn payload_preview(payload: &serde_json::Value) -> String {
+    let text = payload.to_string();
+    if text.chars().count() > PAYLOAD_PREVIEW_LEN {
+        let cut: String = text.chars().take(PAYLOAD_PREVIEW_LEN).collect();
+        format!("{cut}...")
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::state::EventType;
+    use crate::storage::MemoryBackend;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn push_event(state: &mut SessionState, id: &str, event_type: EventType) {
+        state.trace.push(TraceEvent {
+            id: id.to_string(),
+            timestamp: Utc::now(),
+            event_type,
+            payload: json!({}),
+        });
+    }
+
+    #[test]
+    fn poll_returns_only_new_matching_events() {
+        let store = MemoryBackend::new();
+        let mut state = SessionState::new("tail-1");
+        push_event(&mut state, "e1", EventType::SessionStart);
+        push_event(&mut state, "e2", EventType::GateBlocked);
+        store.put_session(&state).unwrap();
+
+        let mut tail = Tail::new(
+            TailTarget::Session("tail-1".to_string()),
+            TraceFilter {
+                types: vec![EventType::GateBlocked, EventType::RozDecision],
+                ..TraceFilter::default()
+            },
+            10,
+        );
+        let first = tail.poll(&store).unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].id, "e2");
+        assert!(tail.poll(&store).unwrap().is_empty());
+
+        push_event(&mut state, "e3", EventType::StopHookCalled);
+        push_event(&mut state, "e4", EventType::RozDecision);
+        store.put_session(&state).unwrap();
+
+        let next = tail.poll(&store).unwrap();
+        assert_eq!(next.len(), 1);
+        assert_eq!(next[0].id, "e4");
+    }
+
+    #[test]
+    fn poll_limits_backlog_on_first_sight() {
+        let store = MemoryBackend::new();
+        let mut state = SessionState::new("tail-backlog");
+        for i in 0..5 {
+            push_event(&mut state, &format!("e{i}"), EventType::PromptReceived);
+        }
+        store.put_session(&state).unwrap();
+
+        let mut tail = Tail::new(
+            TailTarget::Session("tail-backlog".to_string()),
+            TraceFilter::default(),
+            2,
+        );
+        let events = tail.poll(&store).unwrap();
+        let ids: Vec<&str> = events.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["e3", "e4"]);
+    }
+
+    #[test]
+    fn cwd_target_waits_for_and_switches_sessions() {
+        let store = MemoryBackend::new();
+        let mut tail = Tail::new(
+            TailTarget::Cwd(PathBuf::from("/work/project")),
+            TraceFilter::default(),
+            10,
+        );
+        assert!(tail.poll(&store).unwrap().is_empty());
+        assert_eq!(tail.session_id(), None);
+
+        let mut state = SessionState::new("tail-cwd");
+        state.cwd = Some(PathBuf::from("/work/project"));
+        push_event(&mut state, "e1", EventType::SessionStart);
+        store.put_session(&state).unwrap();
+
+        assert_eq!(tail.poll(&store).unwrap().len(), 1);
+        assert_eq!(tail.session_id(), Some("tail-cwd"));
+        assert!(tail.poll(&store).unwrap().is_empty());
+
+        // A newer session at a parent directory does not take over
+        let mut parent = SessionState::new("tail-parent");
+        parent.cwd = Some(PathBuf::from("/work"));
+        push_event(&mut parent, "p1", EventType::SessionStart);
+        store.put_session(&parent).unwrap();
+        assert!(tail.poll(&store).unwrap().is_empty());
+        assert_eq!(tail.session_id(), Some("tail-cwd"));
+
+        // Updates to the followed session are picked up
+        push_event(&mut state, "e2", EventType::PromptReceived);
+        state.updated_at = Utc::now();
+        store.put_session(&state).unwrap();
+        let events = tail.poll(&store).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, "e2");
+
+        // A newer session in the same directory does
+        let mut next = SessionState::new("tail-next");
+        next.cwd = Some(PathBuf::from("/work/project"));
+        push_event(&mut next, "n1", EventType::SessionStart);
+        store.put_session(&next).unwrap();
+        assert_eq!(tail.poll(&store).unwrap()[0].id, "n1");
+        assert_eq!(tail.session_id(), Some("tail-next"));
+    }
+
+    #[test]
+    fn poll_resumes_after_compaction_dropped_last_seen_event() {
+        let store = MemoryBackend::new();
+        let mut state = SessionState::new("tail-compact");
+        push_event(&mut state, "e1", EventType::SessionStart);
+        push_event(&mut state, "e2", EventType::PromptReceived);
+        store.put_session(&state).unwrap();
+
+        let mut tail = Tail::new(
+            TailTarget::Session("tail-compact".to_string()),
+            TraceFilter::default(),
+            10,
+        );
+        assert_eq!(tail.poll(&store).unwrap().len(), 2);
+
+        // e2 is compacted away; only events newer than it are returned
+        state.trace.remove(1);
+        push_event(&mut state, "e3", EventType::StopHookCalled);
+        store.put_session(&state).unwrap();
+        let events = tail.poll(&store).unwrap();
+        let ids: Vec<&str> = events.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["e3"]);
+    }
+
+    #[test]
+    fn missing_session_errors() {
+        let store = MemoryBackend::new();
+        let mut tail = Tail::new(
+            TailTarget::Session("missing".to_string()),
+            TraceFilter::default(),
+            10,
+        );
+        assert!(matches!(tail.poll(&store), Err(Error::SessionNotFound(_))));
+    }
+
+    #[test]
+    fn payload_preview_truncates() {
+        let payload = json!({ "text": "x".repeat(500) });
+        let preview = payload_preview(&payload);
+        assert!(preview.ends_with("..."));
+        assert_eq!(preview.chars().count(), PAYLOAD_PREVIEW_LEN + 3);
+    }
+}
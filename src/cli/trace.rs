@@ -1,8 +1,144 @@
 //! `roz trace` command implementation.
 
+use crate::cli::clean::parse_duration;
+use crate::cli::format::{CsvRecord, OutputFormat, print_machine};
+use crate::core::state::{EventType, TraceEvent};
 use crate::error::{Error, Result};
 use crate::storage::MessageStore;
 use crate::storage::file::{FileBackend, get_roz_home};
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Event filter shared by `roz trace`, `roz tail` and `roz search`.
+#[derive(Debug, Clone, Default)]
+pub struct TraceFilter {
+    /// Only these event types (all if empty).
+    pub types: Vec<EventType>,
+
+    /// Only events at or after this time.
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only events at or before this time.
+    pub until: Option<DateTime<Utc>>,
+
+    /// Only events with a payload key or value containing this text.
+    pub grep: Option<String>,
+}
+
+impl TraceFilter {
+    /// Build a filter from command-line values.
+    ///
+    /// `since` and `until` accept an RFC 3339 timestamp, a date
+    /// (`2026-03-01`, midnight UTC) or a duration ago (`7d`, `24h`, `30m`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for an unknown event type or an invalid time.
+    pub fn parse(
+        types: &[String],
+        since: Option<&str>,
+        until: Option<&str>,
+        grep: Option<&str>,
+    ) -> Result<Self> {
+        let now = Utc::now();
+        Ok(Self {
+            types: parse_event_types(types)?,
+            since: since.map(|s| parse_time(s, now)).transpose()?,
+            until: until.map(|s| parse_time(s, now)).transpose()?,
+            grep: grep.map(String::from),
+        })
+    }
+
+    /// Whether `event` passes every filter.
+    #[must_use]
+    pub fn matches(&self, event: &TraceEvent) -> bool {
+        (self.types.is_empty() || self.types.contains(&event.event_type))
+            && self.since.is_none_or(|since| event.timestamp >= since)
+            && self.until.is_none_or(|until| event.timestamp <= until)
+            && self
+                .grep
+                .as_deref()
+                .is_none_or(|text| payload_contains(&event.payload, text))
+    }
+}
+
+/// Parse an absolute time or a duration before `now`.
+fn parse_time(s: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let s = s.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_time(chrono::NaiveTime::MIN).and_utc());
+    }
+    parse_duration(s)
+        .map(|ago| now - ago)
+        .map_err(|_| Error::InvalidState(format!("Invalid time: {s}")))
+}
+
+/// Whether any key or scalar value in `payload` contains `text`.
+fn payload_contains(payload: &Value, text: &str) -> bool {
+    match payload {
+        Value::String(s) => s.contains(text),
+        Value::Number(n) => n.to_string().contains(text),
+        Value::Bool(b) => b.to_string().contains(text),
+        Value::Array(items) => items.iter().any(|v| payload_contains(v, text)),
+        Value::Object(map) => map
+            .iter()
+            .any(|(k, v)| k.contains(text) || payload_contains(v, text)),
+        Value::Null => false,
+    }
+}
+
+/// JSON report for `roz trace --format json`.
+#[derive(Debug, Serialize)]
+pub struct TraceReport {
+    /// Session identifier.
+    pub session_id: String,
+
+    /// When the session was created.
+    pub created_at: DateTime<Utc>,
+
+    /// Trace events, oldest first.
+    pub events: Vec<TraceEvent>,
+}
+
+impl CsvRecord for TraceEvent {
+    const HEADERS: &'static [&'static str] = &["id", "timestamp", "event_type", "payload"];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+            event_type_name(&self.event_type),
+            self.payload.to_string(),
+        ]
+    }
+}
+
+/// Get the serialized (`snake_case`) name of an event type.
+pub(crate) fn event_type_name(event_type: &EventType) -> String {
+    serde_json::to_value(event_type)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+/// Parse `snake_case` event type names (as printed by `event_type_name`).
+///
+/// # Errors
+///
+/// Returns an error naming the first unknown event type.
+pub(crate) fn parse_event_types(names: &[String]) -> Result<Vec<EventType>> {
+    names
+        .iter()
+        .map(|name| {
+            serde_json::from_value(serde_json::Value::String(name.trim().to_string()))
+                .map_err(|_| Error::InvalidState(format!("Unknown event type: {name}")))
+        })
+        .collect()
+}
 
 /// Run the trace command.
 ///
@@ -11,26 +147,35 @@ use crate::storage::file::{FileBackend, get_roz_home};
 /// # Errors
 ///
 /// Returns an error if the storage backend fails or the session is not found.
-pub fn run(session_id: &str, verbose: bool) -> Result<()> {
+pub fn run(
+    session_id: &str,
+    filter: &TraceFilter,
+    verbose: bool,
+    format: OutputFormat,
+) -> Result<()> {
     let store = FileBackend::new(get_roz_home())?;
+    let report = collect(&store, session_id, filter)?;
 
-    let state = store
-        .get_session(session_id)?
-        .ok_or_else(|| Error::SessionNotFound(session_id.to_string()))?;
+    if print_machine(format, &report, &report.events)? {
+        return Ok(());
+    }
 
     // Print header
-    println!("Session: {}", state.session_id);
-    println!("Created: {}", state.created_at.format("%Y-%m-%dT%H:%M:%SZ"));
-    println!("Events: {}", state.trace.len());
+    println!("Session: {}", report.session_id);
+    println!(
+        "Created: {}",
+        report.created_at.format("%Y-%m-%dT%H:%M:%SZ")
+    );
+    println!("Events: {}", report.events.len());
     println!();
 
-    if state.trace.is_empty() {
+    if report.events.is_empty() {
         println!("(no trace events)");
         return Ok(());
     }
 
     // Print trace events
-    for (i, event) in state.trace.iter().enumerate() {
+    for (i, event) in report.events.iter().enumerate() {
         println!(
             "[{:>3}] {} {:?}",
             i + 1,
@@ -52,8 +197,34 @@ pub fn run(session_id: &str, verbose: bool) -> Result<()> {
     Ok(())
 }
 
+/// Collect trace events for a session that pass `filter`.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails or the session is not found.
+pub fn collect(
+    store: &dyn MessageStore,
+    session_id: &str,
+    filter: &TraceFilter,
+) -> Result<TraceReport> {
+    let state = store
+        .get_session(session_id)?
+        .ok_or_else(|| Error::SessionNotFound(session_id.to_string()))?;
+
+    Ok(TraceReport {
+        session_id: state.session_id,
+        created_at: state.created_at,
+        events: state
+            .trace
+            .into_iter()
+            .filter(|e| filter.matches(e))
+            .collect(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
+    use super::{CsvRecord, TraceFilter, collect, parse_event_types, parse_time};
     use crate::core::state::{EventType, SessionState, TraceEvent};
     use crate::storage::MemoryBackend;
     use crate::storage::MessageStore;
@@ -109,7 +280,16 @@ mod tests {
             EventType::ToolCompleted,
             EventType::StopHookCalled,
             EventType::RozDecision,
+            EventType::HumanDecision,
+            EventType::CircuitBreakerTripped,
+            EventType::CircuitBreakerReset,
+            EventType::GateBreakerTripped,
+            EventType::ManualBreakerAction,
             EventType::TraceCompacted,
+            EventType::RiskThresholdCrossed,
+            EventType::ReviewerEvidence,
+            EventType::CompactionStarted,
+            EventType::NotificationReceived,
             EventType::SessionEnd,
         ];
 
@@ -124,7 +304,7 @@ mod tests {
         store.put_session(&state).unwrap();
 
         let retrieved = store.get_session("test-all-events").unwrap().unwrap();
-        assert_eq!(retrieved.trace.len(), 9);
+        assert_eq!(retrieved.trace.len(), event_types.len());
     }
 
     #[test]
@@ -176,4 +356,109 @@ mod tests {
         assert_eq!(payload["tool"], "Bash");
         assert_eq!(payload["input"]["command"], "rm -rf /");
     }
+
+    #[test]
+    fn parse_event_types_by_name() {
+        let types =
+            parse_event_types(&["gate_blocked".to_string(), " roz_decision".to_string()]).unwrap();
+        assert_eq!(types, vec![EventType::GateBlocked, EventType::RozDecision]);
+        assert!(parse_event_types(&["GateBlocked".to_string()]).is_err());
+    }
+
+    #[test]
+    fn trace_event_csv_fields() {
+        let event = TraceEvent {
+            id: "evt-csv".to_string(),
+            timestamp: Utc::now(),
+            event_type: EventType::GateBlocked,
+            payload: json!({"tool": "Bash:git push"}),
+        };
+
+        let fields = event.csv_fields();
+        assert_eq!(fields.len(), TraceEvent::HEADERS.len());
+        assert_eq!(fields[2], "gate_blocked");
+        assert_eq!(fields[3], r#"{"tool":"Bash:git push"}"#);
+    }
+
+    #[test]
+    fn collect_missing_session_errors() {
+        let store = MemoryBackend::new();
+        assert!(matches!(
+            collect(&store, "missing", &TraceFilter::default()),
+            Err(crate::error::Error::SessionNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn filter_by_type_time_and_grep() {
+        let store = MemoryBackend::new();
+        let mut state = SessionState::new("test-filter");
+        let now = Utc::now();
+        for (i, (event_type, minutes_ago, tool)) in [
+            (EventType::GateBlocked, 90, "Bash:git push"),
+            (EventType::GateBlocked, 10, "Bash:git push"),
+            (EventType::GateBlocked, 5, "mcp__tissue__close_issue"),
+            (EventType::RozDecision, 1, "Bash:git push"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            state.trace.push(TraceEvent {
+                id: format!("evt-{i}"),
+                timestamp: now - chrono::Duration::minutes(minutes_ago),
+                event_type,
+                payload: json!({"tool": tool, "nested": {"pattern": format!("{tool}*")}}),
+            });
+        }
+        store.put_session(&state).unwrap();
+
+        let filter = TraceFilter::parse(
+            &["gate_blocked".to_string()],
+            Some("1h"),
+            None,
+            Some("git push"),
+        )
+        .unwrap();
+        let report = collect(&store, "test-filter", &filter).unwrap();
+        let ids: Vec<&str> = report.events.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["evt-1"]);
+
+        // Nested values and keys are searched too
+        let filter = TraceFilter::parse(&[], None, None, Some("push*")).unwrap();
+        assert_eq!(
+            collect(&store, "test-filter", &filter)
+                .unwrap()
+                .events
+                .len(),
+            3
+        );
+        let filter = TraceFilter::parse(&[], None, Some("30m"), Some("pattern")).unwrap();
+        assert_eq!(
+            collect(&store, "test-filter", &filter)
+                .unwrap()
+                .events
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn parse_time_formats() {
+        let now = Utc::now();
+        assert_eq!(
+            parse_time("2026-03-01T12:00:00Z", now)
+                .unwrap()
+                .to_rfc3339(),
+            "2026-03-01T12:00:00+00:00"
+        );
+        assert_eq!(
+            parse_time("2026-03-01", now).unwrap().to_rfc3339(),
+            "2026-03-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_time("2h", now).unwrap(),
+            now - chrono::Duration::hours(2)
+        );
+        assert!(parse_time("yesterday", now).is_err());
+    }
 }
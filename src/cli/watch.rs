@@ -0,0 +1,265 @@
+//! `roz watch` command implementation.
+//!
+//! A live table of active sessions: every session updated recently that has
+//! not ended, with its decision, block counts and breaker state. The table
+//! is redrawn on each poll; machine formats print one snapshot and exit.
+
+use crate::cli::clean::parse_duration;
+use crate::cli::format::{CsvRecord, OutputFormat, print_machine};
+use crate::cli::status::decision_name;
+use crate::core::state::{EventType, SessionState};
+use crate::error::Result;
+use crate::storage::MessageStore;
+use crate::storage::file::{FileBackend, get_roz_home};
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::thread;
+
+/// One active session (`roz watch --format jsonl` record).
+#[derive(Debug, Serialize)]
+pub struct WatchRow {
+    /// Session identifier.
+    pub session_id: String,
+
+    /// Working directory the session last ran in.
+    pub cwd: Option<PathBuf>,
+
+    /// When the session was last updated.
+    pub updated_at: DateTime<Utc>,
+
+    /// Whether review is enabled for this session.
+    pub review_enabled: bool,
+
+    /// Current decision (`pending`, `complete`, `issues`).
+    pub decision: &'static str,
+
+    /// Number of times the stop hook has blocked.
+    pub block_count: u32,
+
+    /// Blocks since the reviewer last posted a decision.
+    pub not_spawned_blocks: u32,
+
+    /// Blocks for unresolved review issues.
+    pub issues_blocks: u32,
+
+    /// Whether the circuit breaker is tripped.
+    pub breaker_tripped: bool,
+
+    /// Tool call that triggered a gate, if any.
+    pub gate_tool: Option<String>,
+}
+
+impl WatchRow {
+    /// Build a row from session state.
+    #[must_use]
+    pub fn from_state(state: &SessionState) -> Self {
+        Self {
+            session_id: state.session_id.clone(),
+            cwd: state.cwd.clone(),
+            updated_at: state.updated_at,
+            review_enabled: state.review.enabled,
+            decision: decision_name(&state.review.decision),
+            block_count: state.review.block_count,
+            not_spawned_blocks: state.review.not_spawned_blocks,
+            issues_blocks: state.review.issues_blocks,
+            breaker_tripped: state.review.circuit_breaker_tripped,
+            gate_tool: state
+                .review
+                .gate_trigger
+                .as_ref()
+                .map(|g| g.tool_name.clone()),
+        }
+    }
+}
+
+impl CsvRecord for WatchRow {
+    const HEADERS: &'static [&'static str] = &[
+        "session_id",
+        "cwd",
+        "updated_at",
+        "review_enabled",
+        "decision",
+        "block_count",
+        "not_spawned_blocks",
+        "issues_blocks",
+        "breaker_tripped",
+        "gate_tool",
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.session_id.clone(),
+            self.cwd
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default(),
+            self.updated_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            self.review_enabled.to_string(),
+            self.decision.to_string(),
+            self.block_count.to_string(),
+            self.not_spawned_blocks.to_string(),
+            self.issues_blocks.to_string(),
+            self.breaker_tripped.to_string(),
+            self.gate_tool.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+/// JSON report for `roz watch --format json`.
+#[derive(Debug, Serialize)]
+pub struct WatchReport {
+    /// Sessions updated at or after this time count as active.
+    pub active_since: DateTime<Utc>,
+
+    /// Active sessions, most recently updated first.
+    pub sessions: Vec<WatchRow>,
+}
+
+/// Run the watch command.
+///
+/// Redraws the table every `interval_ms` until interrupted. Machine formats
+/// print a single snapshot.
+///
+/// # Errors
+///
+/// Returns an error if `active` is not a valid duration or storage fails.
+pub fn run(active: &str, interval_ms: u64, format: OutputFormat) -> Result<()> {
+    let store = FileBackend::new(get_roz_home())?;
+    let window = parse_duration(active)?;
+
+    loop {
+        let report = collect(&store, Utc::now() - window)?;
+        if print_machine(format, &report, &report.sessions)? {
+            return Ok(());
+        }
+
+        // Clear the screen and home the cursor before redrawing
+        print!("\x1b[2J\x1b[H");
+        render(&report, active);
+        thread::sleep(std::time::Duration::from_millis(interval_ms));
+    }
+}
+
+/// Collect sessions updated since `active_since` that have not ended.
+///
+/// Only sessions the store reports as written since `active_since` are
+/// loaded, so idle sessions cost nothing on each redraw.
+///
+/// # Errors
+///
+/// Returns an error if the storage backend fails.
+pub fn collect(store: &dyn MessageStore, active_since: DateTime<Utc>) -> Result<WatchReport> {
+    let mut sessions = Vec::new();
+    for session_id in store.sessions_updated_since(active_since)? {
+        // The store may list files it cannot parse
+        let Ok(Some(state)) = store.get_session(&session_id) else {
+            continue;
+        };
+        if state.updated_at < active_since || has_ended(&state) {
+            continue;
+        }
+        sessions.push(WatchRow::from_state(&state));
+    }
+    sessions.sort_by_key(|row| std::cmp::Reverse(row.updated_at));
+
+    Ok(WatchReport {
+        active_since,
+        sessions,
+    })
+}
+
+/// Whether the last trace event is a session end.
+fn has_ended(state: &SessionState) -> bool {
+    state
+        .trace
+        .last()
+        .is_some_and(|e| e.event_type == EventType::SessionEnd)
+}
+
+/// Render the live table.
+fn render(report: &WatchReport, active: &str) {
+    println!(
+        "roz watch - {} active session(s) (updated within {active}), Ctrl-C to exit",
+        report.sessions.len()
+    );
+    println!();
+    println!(
+        "{:<38} {:<9} {:<9} {:>6} {:>11} {:<8} {:<8} Gate",
+        "Session ID", "Updated", "Decision", "Blocks", "NS/Issues", "Breaker", "Review"
+    );
+    println!("{}", "─".repeat(110));
+
+    for row in &report.sessions {
+        let updated: DateTime<Local> = row.updated_at.into();
+        println!(
+            "{:<38} {:<9} {:<9} {:>6} {:>11} {:<8} {:<8} {}",
+            row.session_id,
+            updated.format("%H:%M:%S"),
+            row.decision,
+            row.block_count,
+            format!("{}/{}", row.not_spawned_blocks, row.issues_blocks),
+            if row.breaker_tripped { "tripped" } else { "ok" },
+            if row.review_enabled { "on" } else { "off" },
+            row.gate_tool.as_deref().unwrap_or("-")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::state::TraceEvent;
+    use crate::storage::MemoryBackend;
+    use chrono::Duration;
+    use serde_json::json;
+
+    #[test]
+    fn collect_skips_stale_and_ended_sessions() {
+        let store = MemoryBackend::new();
+
+        let mut active = SessionState::new("active");
+        active.review.block_count = 2;
+        store.put_session(&active).unwrap();
+
+        let mut stale = SessionState::new("stale");
+        stale.updated_at = Utc::now() - Duration::hours(3);
+        store.put_session(&stale).unwrap();
+
+        let mut ended = SessionState::new("ended");
+        ended.trace.push(TraceEvent {
+            id: "end".to_string(),
+            timestamp: Utc::now(),
+            event_type: EventType::SessionEnd,
+            payload: json!({}),
+        });
+        store.put_session(&ended).unwrap();
+
+        let report = collect(&store, Utc::now() - Duration::hours(1)).unwrap();
+        assert_eq!(report.sessions.len(), 1);
+        assert_eq!(report.sessions[0].session_id, "active");
+        assert_eq!(report.sessions[0].block_count, 2);
+        assert_eq!(report.sessions[0].decision, "pending");
+    }
+
+    #[test]
+    fn collect_skips_unreadable_session_files() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let store = FileBackend::new(temp.path().to_path_buf()).unwrap();
+        store.put_session(&SessionState::new("active")).unwrap();
+        std::fs::write(temp.path().join("sessions/broken.json"), "{ not json").unwrap();
+
+        let report = collect(&store, Utc::now() - Duration::hours(1)).unwrap();
+        assert_eq!(report.sessions.len(), 1);
+        assert_eq!(report.sessions[0].session_id, "active");
+    }
+
+    #[test]
+    fn watch_row_csv_fields() {
+        let row = WatchRow::from_state(&SessionState::new("csv"));
+        let fields = row.csv_fields();
+        assert_eq!(fields.len(), WatchRow::HEADERS.len());
+        assert_eq!(fields[0], "csv");
+        assert_eq!(fields[8], "false");
+    }
+}
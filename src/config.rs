@@ -6,11 +6,11 @@
 //! 3. Defaults
 
 use crate::error::{Error, Result};
-use serde::Deserialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Main configuration struct.
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -36,6 +36,12 @@ pub struct Config {
 
     /// Trace configuration.
     pub trace: TraceConfig,
+
+    /// Safety configuration (behavior on internal errors).
+    pub safety: SafetyConfig,
+
+    /// Hook recording configuration (fixtures for `roz replay`).
+    pub record: RecordConfig,
 }
 
 /// Storage configuration.
@@ -63,6 +69,145 @@ pub struct ReviewConfig {
 
     /// Gate configuration.
     pub gates: GatesConfig,
+
+    /// Rules that enable review in `prompt` mode (first match wins).
+    pub triggers: Vec<TriggerRule>,
+
+    /// Prompt markers that skip review for that prompt, even in `always` mode.
+    pub opt_out: Vec<String>,
+
+    /// Activity thresholds that enable review without a trigger.
+    pub risk: RiskConfig,
+
+    /// What the reviewer must have done before a COMPLETE decision counts.
+    pub evidence: EvidenceConfig,
+}
+
+impl ReviewConfig {
+    /// Tool patterns the pre-tool-use hook must see: gates plus whatever the
+    /// risk thresholds watch.
+    #[must_use]
+    pub fn hooked_tools(&self) -> Vec<String> {
+        let mut tools = self.gates.tools.clone();
+        for thresholds in self.risk.all() {
+            let edits = EDIT_TOOLS.iter().filter(|_| thresholds.watches_files());
+            for tool in edits
+                .copied()
+                .chain(thresholds.commands.iter().map(String::as_str))
+            {
+                if !tools.iter().any(|t| t == tool) {
+                    tools.push(tool.to_string());
+                }
+            }
+        }
+        tools
+    }
+}
+
+/// Tools whose input names a file they change.
+pub const EDIT_TOOLS: [&str; 4] = ["Edit", "MultiEdit", "Write", "NotebookEdit"];
+
+/// Risk thresholds, with optional per-project replacements.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct RiskConfig {
+    /// Thresholds for sessions outside every listed project.
+    #[serde(flatten)]
+    pub defaults: RiskThresholds,
+
+    /// Thresholds keyed by project directory; the deepest directory
+    /// containing the session's cwd replaces the defaults.
+    pub projects: BTreeMap<String, RiskThresholds>,
+}
+
+impl RiskConfig {
+    /// Thresholds that apply to a session in `cwd`.
+    #[must_use]
+    pub fn for_cwd(&self, cwd: &Path) -> &RiskThresholds {
+        self.projects
+            .iter()
+            .map(|(dir, thresholds)| (expand_home(dir), thresholds))
+            .filter(|(dir, _)| cwd.starts_with(dir))
+            .max_by_key(|(dir, _)| dir.components().count())
+            .map_or(&self.defaults, |(_, thresholds)| thresholds)
+    }
+
+    /// The defaults and every project's thresholds.
+    pub fn all(&self) -> impl Iterator<Item = &RiskThresholds> {
+        std::iter::once(&self.defaults).chain(self.projects.values())
+    }
+}
+
+/// Session activity that turns review on once crossed.
+#[derive(Debug, Clone, Deserialize, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct RiskThresholds {
+    /// Enable review once this many distinct files are changed.
+    pub files_changed: Option<u32>,
+
+    /// Enable review once this many lines are changed (added plus removed).
+    pub lines_changed: Option<u32>,
+
+    /// Enable review when a changed file matches one of these globs
+    /// (relative to the session's cwd).
+    pub sensitive_paths: Vec<String>,
+
+    /// Enable review when a tool call matches one of these gate-syntax
+    /// patterns (e.g. `Bash:terraform apply*`).
+    pub commands: Vec<String>,
+}
+
+impl RiskThresholds {
+    /// Whether any threshold is set.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.watches_files() || !self.commands.is_empty()
+    }
+
+    /// Whether any threshold looks at file edits.
+    #[must_use]
+    pub fn watches_files(&self) -> bool {
+        self.files_changed.is_some()
+            || self.lines_changed.is_some()
+            || !self.sensitive_paths.is_empty()
+    }
+}
+
+/// Minimum evidence the roz:roz reviewer must show, from its own transcript,
+/// before a COMPLETE decision is accepted. Everything is off by default.
+#[derive(Debug, Clone, Deserialize, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct EvidenceConfig {
+    /// Must run `roz context`.
+    pub require_context: bool,
+
+    /// Must run `git diff` or `git show`.
+    pub require_diff: bool,
+
+    /// Must `Read` every file changed in the session's working tree.
+    pub read_changed_files: bool,
+
+    /// Must `Read` at least this many files.
+    pub min_files_read: u32,
+}
+
+impl EvidenceConfig {
+    /// Whether any requirement is set.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.require_context
+            || self.require_diff
+            || self.read_changed_files
+            || self.min_files_read > 0
+    }
+}
+
+/// Expand a leading `~/` to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
 }
 
 impl Default for ReviewConfig {
@@ -70,10 +215,53 @@ impl Default for ReviewConfig {
         Self {
             mode: ReviewMode::Prompt,
             gates: GatesConfig::default(),
+            triggers: vec![TriggerRule::Prefix("#roz".to_string())],
+            opt_out: vec!["#noroz".to_string()],
+            risk: RiskConfig::default(),
+            evidence: EvidenceConfig::default(),
         }
     }
 }
 
+/// A rule that turns review on for a prompt.
+///
+/// Written as a one-key table, e.g. `{ branch = "release/*" }`.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerRule {
+    /// Prompt starts with this text (after leading whitespace).
+    Prefix(String),
+
+    /// Prompt contains this word (case-insensitive).
+    Keyword(String),
+
+    /// Prompt matches this regex.
+    Regex(String),
+
+    /// Current git branch matches this glob.
+    Branch(String),
+
+    /// A file changed in the working tree matches this glob.
+    Files(String),
+
+    /// Claude Code's permission mode equals this value.
+    PermissionMode(String),
+}
+
+impl std::fmt::Display for TriggerRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (kind, value) = match self {
+            Self::Prefix(v) => ("prefix", v),
+            Self::Keyword(v) => ("keyword", v),
+            Self::Regex(v) => ("regex", v),
+            Self::Branch(v) => ("branch", v),
+            Self::Files(v) => ("files", v),
+            Self::PermissionMode(v) => ("permission_mode", v),
+        };
+        write!(f, "{kind}:{value}")
+    }
+}
+
 /// Review mode.
 #[derive(Debug, Clone, Deserialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -112,7 +300,7 @@ impl GatesConfig {
 }
 
 /// Approval scope for gates.
-#[derive(Debug, Clone, Deserialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum ApprovalScope {
     /// Once approved, all gated tools allowed until session ends.
@@ -130,22 +318,101 @@ pub enum ApprovalScope {
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct CircuitBreakerConfig {
-    /// Maximum blocks before tripping.
+    /// Maximum blocks before tripping (default for both per-cause limits).
     pub max_blocks: u32,
 
-    /// Cooldown time in seconds before breaker resets.
+    /// Maximum blocks while the reviewer is never spawned (defaults to `max_blocks`).
+    pub max_not_spawned_blocks: Option<u32>,
+
+    /// Maximum blocks for unresolved review issues (defaults to `max_blocks`).
+    pub max_issues_blocks: Option<u32>,
+
+    /// Cooldown time in seconds before breaker resets (first trip).
     pub cooldown_seconds: u64,
+
+    /// Cooldown growth factor for each repeated trip.
+    pub backoff_multiplier: u32,
+
+    /// Upper bound for the grown cooldown.
+    pub max_cooldown_seconds: u64,
+
+    /// What the hooks do while the breaker is tripped.
+    pub on_trip: TripAction,
+
+    /// Maximum `PreToolUse` denials per gate pattern (defaults to `max_blocks`).
+    pub max_gate_denials: Option<u32>,
+
+    /// What `PreToolUse` does while a gate pattern is tripped.
+    ///
+    /// Defaults to `ask` independently of `on_trip`: an agent retrying a
+    /// gated command must never end up with it silently allowed.
+    pub on_gate_trip: TripAction,
 }
 
 impl Default for CircuitBreakerConfig {
     fn default() -> Self {
         Self {
             max_blocks: 3,
+            max_not_spawned_blocks: None,
+            max_issues_blocks: None,
             cooldown_seconds: 300,
+            backoff_multiplier: 2,
+            max_cooldown_seconds: 3600,
+            on_trip: TripAction::Approve,
+            max_gate_denials: None,
+            on_gate_trip: TripAction::Ask,
         }
     }
 }
 
+impl CircuitBreakerConfig {
+    /// Block limit while the reviewer is never spawned.
+    #[must_use]
+    pub fn not_spawned_limit(&self) -> u32 {
+        self.max_not_spawned_blocks.unwrap_or(self.max_blocks)
+    }
+
+    /// Block limit for unresolved review issues.
+    #[must_use]
+    pub fn issues_limit(&self) -> u32 {
+        self.max_issues_blocks.unwrap_or(self.max_blocks)
+    }
+
+    /// Denial limit for each gate pattern.
+    #[must_use]
+    pub fn gate_denial_limit(&self) -> u32 {
+        self.max_gate_denials.unwrap_or(self.max_blocks)
+    }
+
+    /// Cooldown in seconds after the `trip_count`-th trip (1-based).
+    ///
+    /// Grows by `backoff_multiplier` per repeated trip, capped at
+    /// `max_cooldown_seconds` (but never below `cooldown_seconds`).
+    #[must_use]
+    pub fn cooldown_for_trip(&self, trip_count: u32) -> u64 {
+        let cap = self.max_cooldown_seconds.max(self.cooldown_seconds);
+        let factor =
+            u64::from(self.backoff_multiplier.max(1)).saturating_pow(trip_count.saturating_sub(1));
+        self.cooldown_seconds.saturating_mul(factor).min(cap)
+    }
+}
+
+/// Action taken while the circuit breaker is tripped.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TripAction {
+    /// Let the agent continue and disable review until cooldown (default).
+    #[default]
+    Approve,
+
+    /// Let the agent stop but tell the user review was skipped; gated tools
+    /// ask for permission.
+    Ask,
+
+    /// Keep blocking until a human runs `roz approve` or `roz reject`.
+    Block,
+}
+
 /// Cleanup configuration.
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
@@ -187,17 +454,28 @@ pub struct TemplateConfig {
     /// Which template to use: "v1", "v2", "v3", or "random".
     pub active: String,
 
-    /// Weights for random selection.
-    pub weights: HashMap<String, u32>,
+    /// Weights for random selection (iterated in sorted key order).
+    pub weights: BTreeMap<String, u32>,
+
+    /// Salt mixed into the session hash for random assignment.
+    ///
+    /// Changing the salt reshuffles which sessions land in which template.
+    pub salt: String,
+
+    /// Reminder template injected by the user-prompt hook while review is
+    /// active (`~/.roz/templates/reminder-<id>.md`), or "off".
+    pub reminder: String,
 }
 
 impl Default for TemplateConfig {
     fn default() -> Self {
-        let mut weights = HashMap::new();
+        let mut weights = BTreeMap::new();
         weights.insert("default".to_string(), 100);
         Self {
             active: "default".to_string(),
             weights,
+            salt: String::new(),
+            reminder: "default".to_string(),
         }
     }
 }
@@ -216,6 +494,34 @@ impl Default for TraceConfig {
     }
 }
 
+/// Safety configuration.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct SafetyConfig {
+    /// What hooks do when an error keeps them from checking review state.
+    pub mode: SafetyMode,
+}
+
+/// Hook recording configuration.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct RecordConfig {
+    /// Append every hook's stdin/stdout to `~/.roz/recordings/<session>.jsonl`.
+    pub enabled: bool,
+}
+
+/// Behavior on internal errors.
+#[derive(Debug, Clone, Copy, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SafetyMode {
+    /// Approve and let the agent continue (default).
+    #[default]
+    FailOpen,
+
+    /// Block (Stop) or deny (`PreToolUse`) with a diagnostic.
+    FailClosed,
+}
+
 /// Get the default roz home directory.
 fn default_roz_home() -> PathBuf {
     dirs::home_dir().map_or_else(|| PathBuf::from(".roz"), |h| h.join(".roz"))
@@ -227,13 +533,23 @@ fn default_roz_home() -> PathBuf {
 ///
 /// Returns an error if the config file exists but cannot be parsed.
 pub fn load_config() -> Result<Config> {
+    load_config_from(&get_config_path())
+}
+
+/// Load configuration from `config_path` (defaults if it does not exist),
+/// then apply env overrides.
+///
+/// # Errors
+///
+/// Returns an error if the config file exists but cannot be parsed.
+pub fn load_config_from(config_path: &Path) -> Result<Config> {
     let mut config = Config::default();
 
     // Try to load config file
-    let config_path = get_config_path();
     if config_path.exists() {
-        let contents = fs::read_to_string(&config_path).map_err(Error::Storage)?;
+        let contents = fs::read_to_string(config_path).map_err(Error::Storage)?;
         config = toml::from_str(&contents).map_err(|e| Error::Config(e.to_string()))?;
+        validate_review(&config.review)?;
     }
 
     // Override with environment variables
@@ -242,6 +558,31 @@ pub fn load_config() -> Result<Config> {
     Ok(config)
 }
 
+/// Reject trigger rules and risk paths whose regex or glob does not compile.
+fn validate_review(review: &ReviewConfig) -> Result<()> {
+    for thresholds in review.risk.all() {
+        for glob in &thresholds.sensitive_paths {
+            if let Err(e) = glob::Pattern::new(glob) {
+                return Err(Error::Config(format!("invalid sensitive path {glob}: {e}")));
+            }
+        }
+    }
+
+    for rule in &review.triggers {
+        let valid = match rule {
+            TriggerRule::Regex(re) => regex::Regex::new(re).map(|_| ()).map_err(|e| e.to_string()),
+            TriggerRule::Branch(glob) | TriggerRule::Files(glob) => glob::Pattern::new(glob)
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            _ => Ok(()),
+        };
+        if let Err(e) = valid {
+            return Err(Error::Config(format!("invalid review trigger {rule}: {e}")));
+        }
+    }
+    Ok(())
+}
+
 /// Get the path to the config file.
 fn get_config_path() -> PathBuf {
     if let Ok(path) = env::var("ROZ_CONFIG") {
@@ -256,7 +597,7 @@ fn get_config_path() -> PathBuf {
 }
 
 /// Apply environment variable overrides to config.
-fn apply_env_overrides(config: &mut Config) {
+pub fn apply_env_overrides(config: &mut Config) {
     // Storage path
     if let Ok(path) = env::var("ROZ_STORAGE_PATH") {
         config.storage.path = PathBuf::from(path);
@@ -277,6 +618,14 @@ fn apply_env_overrides(config: &mut Config) {
         }
     }
 
+    if let Ok(action) = env::var("ROZ_ON_TRIP") {
+        config.circuit_breaker.on_trip = match action.to_lowercase().as_str() {
+            "ask" => TripAction::Ask,
+            "block" => TripAction::Block,
+            _ => TripAction::Approve,
+        };
+    }
+
     // Review mode
     if let Ok(mode) = env::var("ROZ_REVIEW_MODE") {
         config.review.mode = match mode.to_lowercase().as_str() {
@@ -286,6 +635,19 @@ fn apply_env_overrides(config: &mut Config) {
         };
     }
 
+    // Safety
+    if let Ok(mode) = env::var("ROZ_SAFETY_MODE") {
+        config.safety.mode = match mode.to_lowercase().as_str() {
+            "fail_closed" => SafetyMode::FailClosed,
+            _ => SafetyMode::FailOpen,
+        };
+    }
+
+    // Recording
+    if let Ok(val) = env::var("ROZ_RECORD") {
+        config.record.enabled = matches!(val.to_lowercase().as_str(), "1" | "true" | "yes");
+    }
+
     // Trace
     if let Ok(val) = env::var("ROZ_MAX_EVENTS") {
         if let Ok(max) = val.parse() {
@@ -313,6 +675,7 @@ mod tests {
         assert_eq!(config.trace.max_events, 500);
         assert_eq!(config.cleanup.retention_days, 7);
         assert_eq!(config.review.mode, ReviewMode::Prompt);
+        assert_eq!(config.safety.mode, SafetyMode::FailOpen);
     }
 
     #[test]
@@ -366,4 +729,188 @@ mod tests {
         assert_eq!(config.circuit_breaker.cooldown_seconds, 300); // Default
         assert_eq!(config.trace.max_events, 500); // Default
     }
+
+    #[test]
+    fn parse_safety_mode() {
+        let toml = r#"
+            [safety]
+            mode = "fail_closed"
+        "#;
+
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.safety.mode, SafetyMode::FailClosed);
+    }
+
+    #[test]
+    fn circuit_breaker_per_cause_limits_default_to_max_blocks() {
+        let toml = r#"
+            [circuit_breaker]
+            max_blocks = 4
+            max_issues_blocks = 8
+            on_trip = "ask"
+        "#;
+
+        let config: Config = toml::from_str(toml).unwrap();
+        let cb = &config.circuit_breaker;
+        assert_eq!(cb.not_spawned_limit(), 4);
+        assert_eq!(cb.issues_limit(), 8);
+        assert_eq!(cb.on_trip, TripAction::Ask);
+    }
+
+    #[test]
+    fn gate_breaker_limit_defaults_to_max_blocks_but_asks_on_trip() {
+        let cb = CircuitBreakerConfig::default();
+        assert_eq!(cb.gate_denial_limit(), cb.max_blocks);
+        assert_eq!(cb.on_gate_trip, TripAction::Ask);
+
+        // on_trip = "approve" does not leak into gates
+        let toml = r#"
+            [circuit_breaker]
+            on_trip = "approve"
+            max_gate_denials = 5
+        "#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.circuit_breaker.gate_denial_limit(), 5);
+        assert_eq!(config.circuit_breaker.on_gate_trip, TripAction::Ask);
+
+        let toml = r#"
+            [circuit_breaker]
+            on_gate_trip = "block"
+        "#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.circuit_breaker.on_gate_trip, TripAction::Block);
+    }
+
+    #[test]
+    fn cooldown_grows_exponentially_and_caps() {
+        let cb = CircuitBreakerConfig::default();
+        assert_eq!(cb.cooldown_for_trip(0), 300);
+        assert_eq!(cb.cooldown_for_trip(1), 300);
+        assert_eq!(cb.cooldown_for_trip(2), 600);
+        assert_eq!(cb.cooldown_for_trip(3), 1200);
+        assert_eq!(cb.cooldown_for_trip(5), 3600);
+        assert_eq!(cb.cooldown_for_trip(u32::MAX), 3600);
+
+        let flat = CircuitBreakerConfig {
+            backoff_multiplier: 1,
+            ..CircuitBreakerConfig::default()
+        };
+        assert_eq!(flat.cooldown_for_trip(10), 300);
+    }
+
+    #[test]
+    fn parse_review_triggers_and_opt_out() {
+        let config = Config::default();
+        assert_eq!(
+            config.review.triggers,
+            [TriggerRule::Prefix("#roz".to_string())]
+        );
+        assert_eq!(config.review.opt_out, ["#noroz"]);
+
+        let toml = r##"
+            [review]
+            triggers = [{ keyword = "deploy" }, { branch = "release/*" }, { permission_mode = "bypassPermissions" }]
+            opt_out = ["#skip"]
+        "##;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.review.triggers.len(), 3);
+        assert_eq!(config.review.triggers[1].to_string(), "branch:release/*");
+        assert_eq!(config.review.opt_out, ["#skip"]);
+    }
+
+    #[test]
+    fn invalid_trigger_regex_is_a_config_error() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("config.toml");
+        fs::write(&path, "[review]\ntriggers = [{ regex = \"(unclosed\" }]\n").unwrap();
+
+        let err = load_config_from(&path).unwrap_err();
+        assert!(matches!(err, Error::Config(_)));
+        assert!(err.to_string().contains("regex:(unclosed"));
+    }
+
+    #[test]
+    fn parse_risk_thresholds_per_project() {
+        let toml = r#"
+            [review.gates]
+            tools = ["Bash:git push*"]
+
+            [review.risk]
+            files_changed = 20
+            commands = ["Bash:git push*"]
+
+            [review.risk.projects."/work/billing"]
+            lines_changed = 200
+            sensitive_paths = ["src/payments/**"]
+
+            [review.risk.projects."/work/billing/tools"]
+            commands = ["Bash:terraform apply*"]
+        "#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let risk = &config.review.risk;
+        assert_eq!(risk.defaults.files_changed, Some(20));
+        assert_eq!(
+            risk.for_cwd(Path::new("/work/billing/src")).lines_changed,
+            Some(200)
+        );
+        assert_eq!(
+            risk.for_cwd(Path::new("/work/billing/tools")).commands,
+            ["Bash:terraform apply*"]
+        );
+        assert_eq!(risk.for_cwd(Path::new("/work/billing-v2")), &risk.defaults);
+        assert!(!RiskThresholds::default().is_enabled());
+
+        // Gates first, then edit tools and commands, without duplicates
+        assert_eq!(
+            config.review.hooked_tools(),
+            [
+                "Bash:git push*",
+                "Edit",
+                "MultiEdit",
+                "Write",
+                "NotebookEdit",
+                "Bash:terraform apply*"
+            ]
+        );
+    }
+
+    #[test]
+    fn risk_projects_rank_by_expanded_depth() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+        let thresholds = |lines| RiskThresholds {
+            lines_changed: Some(lines),
+            ..RiskThresholds::default()
+        };
+        let shallow = home.join("w").display().to_string();
+        let risk = RiskConfig {
+            defaults: RiskThresholds::default(),
+            // The `~/` key is shorter than the absolute one but deeper
+            projects: BTreeMap::from([
+                (shallow, thresholds(1)),
+                ("~/w/billing/api".to_string(), thresholds(2)),
+            ]),
+        };
+        let deep = home.join("w/billing/api/src");
+        assert_eq!(risk.for_cwd(&deep).lines_changed, Some(2));
+        assert_eq!(risk.for_cwd(&home.join("w/billing")).lines_changed, Some(1));
+    }
+
+    #[test]
+    fn parse_evidence_policy() {
+        assert!(!Config::default().review.evidence.is_enabled());
+
+        let toml = r"
+            [review.evidence]
+            require_context = true
+            read_changed_files = true
+        ";
+        let config: Config = toml::from_str(toml).unwrap();
+        let evidence = &config.review.evidence;
+        assert!(evidence.is_enabled());
+        assert!(evidence.require_context && evidence.read_changed_files);
+        assert!(!evidence.require_diff);
+        assert_eq!(evidence.min_files_read, 0);
+    }
 }
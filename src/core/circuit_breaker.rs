@@ -1,28 +1,53 @@
 //! Circuit breaker logic to prevent infinite blocking loops.
 //!
-//! The circuit breaker trips when the block count exceeds `max_blocks`,
-//! forcing an approve and logging a warning. After `cooldown_seconds`,
-//! the circuit breaker resets and blocking can resume.
+//! Blocks are counted per cause: the agent stopping without spawning the
+//! reviewer, and review issues left unresolved. The breaker trips when either
+//! count reaches its limit and the hooks apply the configured `on_trip`
+//! action. Each repeated trip multiplies the cooldown by `backoff_multiplier`;
+//! once it elapses, the circuit breaker resets and blocking can resume.
+//!
+//! `PreToolUse` gates have their own breaker per matched pattern: denials are
+//! counted until the reviewer posts a decision, and the pattern trips at
+//! `max_gate_denials`. While tripped, the hook applies `on_gate_trip`.
 
 use crate::config::CircuitBreakerConfig;
-use crate::core::state::SessionState;
-use chrono::{Duration, Utc};
+use crate::core::context::Context;
+use crate::core::state::{EventType, SessionState, TraceEvent, TripCause};
+use chrono::Duration;
+use serde_json::json;
+
+/// Check which per-cause limit, if any, has been reached.
+#[must_use]
+pub fn exceeded(state: &SessionState, config: &CircuitBreakerConfig) -> Option<TripCause> {
+    if state.review.not_spawned_blocks >= config.not_spawned_limit() {
+        Some(TripCause::ReviewerNotSpawned)
+    } else if state.review.issues_blocks >= config.issues_limit() {
+        Some(TripCause::IssuesUnresolved)
+    } else {
+        None
+    }
+}
+
+/// Cooldown for the current trip, grown by the number of repeated trips.
+#[must_use]
+pub fn cooldown(state: &SessionState, config: &CircuitBreakerConfig) -> Duration {
+    let secs = config.cooldown_for_trip(state.review.trip_count);
+    // Safe conversion: cap at i64::MAX for very large values
+    Duration::seconds(i64::try_from(secs).unwrap_or(i64::MAX))
+}
 
 /// Check if the circuit breaker should trip.
 ///
-/// Returns `true` if the circuit breaker should trip (block count exceeded
-/// and cooldown hasn't elapsed since last trip).
+/// Returns `true` if the circuit breaker should trip (a per-cause block limit
+/// was reached, or it is tripped and cooldown hasn't elapsed since the trip).
 #[must_use]
-pub fn should_trip(state: &SessionState, config: &CircuitBreakerConfig) -> bool {
+pub fn should_trip(state: &SessionState, config: &CircuitBreakerConfig, ctx: &Context) -> bool {
     // If previously tripped, check if cooldown has elapsed
     if state.review.circuit_breaker_tripped {
         if let Some(tripped_at) = state.review.circuit_breaker_tripped_at {
-            // Safe conversion: cap at i64::MAX for very large values
-            let cooldown_secs = i64::try_from(config.cooldown_seconds).unwrap_or(i64::MAX);
-            let cooldown = Duration::seconds(cooldown_secs);
-            let now = Utc::now();
+            let now = ctx.now();
             // If cooldown has elapsed, the breaker should NOT trip (allow retry)
-            if now >= tripped_at + cooldown {
+            if now >= tripped_at + cooldown(state, config) {
                 return false;
             }
         }
@@ -30,83 +55,198 @@ pub fn should_trip(state: &SessionState, config: &CircuitBreakerConfig) -> bool
         return true;
     }
 
-    // Check if we've hit the limit
-    state.review.block_count >= config.max_blocks
+    // Check if we've hit a limit
+    exceeded(state, config).is_some()
 }
 
-/// Reset the circuit breaker after cooldown has elapsed.
+/// Reset the circuit breaker after cooldown has elapsed (or by hand).
 ///
 /// Call this when `should_trip` returns false but `circuit_breaker_tripped` is true,
-/// to reset the state for a new blocking cycle.
-pub fn reset(state: &mut SessionState) {
+/// to reset the state for a new blocking cycle. `trip_count` is kept so the
+/// next trip backs off further.
+pub fn reset(state: &mut SessionState, ctx: &Context) {
     state.review.circuit_breaker_tripped = false;
     state.review.circuit_breaker_tripped_at = None;
+    state.review.trip_cause = None;
     state.review.block_count = 0;
+    state.review.not_spawned_blocks = 0;
+    state.review.issues_blocks = 0;
     state.review.enabled = true;
     state.review.decision = crate::core::state::Decision::Pending;
+    state.review.decided_by = None;
+
+    state.trace.push(TraceEvent {
+        id: ctx.generate_id(),
+        timestamp: ctx.now(),
+        event_type: EventType::CircuitBreakerReset,
+        payload: json!({ "trip_count": state.review.trip_count }),
+    });
 
     eprintln!(
-        "roz: info: circuit breaker reset after cooldown for session {}",
+        "roz: info: circuit breaker reset for session {}",
         state.session_id
     );
 }
 
 /// Trip the circuit breaker, updating the session state.
 ///
-/// This forces approval and logs a warning. The breaker remains tripped
-/// until the cooldown expires or a new session starts.
-pub fn trip(state: &mut SessionState) {
+/// This logs a warning and records the cause. With `on_trip = "approve"`
+/// review is disabled; the breaker remains tripped until the cooldown
+/// expires or a new session starts.
+pub fn trip(
+    state: &mut SessionState,
+    config: &CircuitBreakerConfig,
+    cause: TripCause,
+    ctx: &Context,
+) {
+    let now = ctx.now();
     state.review.circuit_breaker_tripped = true;
-    state.review.circuit_breaker_tripped_at = Some(Utc::now());
-    state.review.enabled = false;
+    state.review.circuit_breaker_tripped_at = Some(now);
+    state.review.trip_cause = Some(cause);
+    state.review.trip_count = state.review.trip_count.saturating_add(1);
+    if config.on_trip == crate::config::TripAction::Approve {
+        state.review.enabled = false;
+    }
+
+    state.trace.push(TraceEvent {
+        id: ctx.generate_id(),
+        timestamp: now,
+        event_type: EventType::CircuitBreakerTripped,
+        payload: json!({
+            "cause": cause,
+            "block_count": state.review.block_count,
+            "not_spawned_blocks": state.review.not_spawned_blocks,
+            "issues_blocks": state.review.issues_blocks,
+            "trip_count": state.review.trip_count,
+            "cooldown_seconds": config.cooldown_for_trip(state.review.trip_count),
+            "on_trip": config.on_trip,
+        }),
+    });
 
     eprintln!(
-        "roz: warning: circuit breaker tripped after {} blocks for session {}",
-        state.review.block_count, state.session_id
+        "roz: warning: circuit breaker tripped ({}) after {} blocks for session {}",
+        cause.as_str(),
+        state.review.block_count,
+        state.session_id
     );
 }
 
+/// Cooldown for a tripped gate pattern (no backoff).
+fn gate_cooldown(config: &CircuitBreakerConfig) -> Duration {
+    Duration::seconds(i64::try_from(config.cooldown_seconds).unwrap_or(i64::MAX))
+}
+
+/// Check whether the gate breaker for `pattern` is tripped.
+///
+/// A trip whose cooldown has elapsed is cleared along with the pattern's
+/// denial count, so gating resumes.
+pub fn gate_tripped(
+    state: &mut SessionState,
+    config: &CircuitBreakerConfig,
+    pattern: &str,
+    ctx: &Context,
+) -> bool {
+    let Some(&tripped_at) = state.review.gate_tripped_at.get(pattern) else {
+        return false;
+    };
+    if ctx.now() < tripped_at + gate_cooldown(config) {
+        return true;
+    }
+
+    state.review.gate_tripped_at.remove(pattern);
+    state.review.gate_denials.remove(pattern);
+    eprintln!(
+        "roz: info: gate breaker for `{pattern}` reset after cooldown for session {}",
+        state.session_id
+    );
+    false
+}
+
+/// Count a `PreToolUse` denial for `pattern`, tripping its breaker at the limit.
+///
+/// Returns `true` if this denial tripped the breaker.
+pub fn record_gate_denial(
+    state: &mut SessionState,
+    config: &CircuitBreakerConfig,
+    pattern: &str,
+    tool_key: &str,
+    ctx: &Context,
+) -> bool {
+    let count = state
+        .review
+        .gate_denials
+        .entry(pattern.to_string())
+        .or_insert(0);
+    *count = count.saturating_add(1);
+    let denials = *count;
+    if denials < config.gate_denial_limit() {
+        return false;
+    }
+
+    let now = ctx.now();
+    state
+        .review
+        .gate_tripped_at
+        .insert(pattern.to_string(), now);
+    state.trace.push(TraceEvent {
+        id: ctx.generate_id(),
+        timestamp: now,
+        event_type: EventType::GateBreakerTripped,
+        payload: json!({
+            "pattern": pattern,
+            "tool": tool_key,
+            "denials": denials,
+            "cooldown_seconds": config.cooldown_seconds,
+            "on_gate_trip": config.on_gate_trip,
+        }),
+    });
+
+    eprintln!(
+        "roz: warning: gate breaker for `{pattern}` tripped after {denials} denials for session {}",
+        state.session_id
+    );
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::TripAction;
+    use crate::core::context::{Clock, FixedClock, SeededRng};
+    use chrono::Utc;
+
+    fn ctx() -> Context<'static> {
+        Context::system()
+    }
 
     #[test]
     fn should_trip_below_limit() {
         let mut state = SessionState::new("test-123");
-        state.review.block_count = 2;
+        state.review.not_spawned_blocks = 2;
 
-        let config = CircuitBreakerConfig {
-            max_blocks: 3,
-            cooldown_seconds: 300,
-        };
+        let config = CircuitBreakerConfig::default();
 
-        assert!(!should_trip(&state, &config));
+        assert!(!should_trip(&state, &config, &ctx()));
     }
 
     #[test]
     fn should_trip_at_limit() {
         let mut state = SessionState::new("test-123");
-        state.review.block_count = 3;
+        state.review.not_spawned_blocks = 3;
 
-        let config = CircuitBreakerConfig {
-            max_blocks: 3,
-            cooldown_seconds: 300,
-        };
+        let config = CircuitBreakerConfig::default();
 
-        assert!(should_trip(&state, &config));
+        assert!(should_trip(&state, &config, &ctx()));
     }
 
     #[test]
     fn should_trip_above_limit() {
         let mut state = SessionState::new("test-123");
-        state.review.block_count = 5;
+        state.review.issues_blocks = 5;
 
-        let config = CircuitBreakerConfig {
-            max_blocks: 3,
-            cooldown_seconds: 300,
-        };
+        let config = CircuitBreakerConfig::default();
 
-        assert!(should_trip(&state, &config));
+        assert!(should_trip(&state, &config, &ctx()));
     }
 
     #[test]
@@ -116,13 +256,10 @@ mod tests {
         state.review.circuit_breaker_tripped_at = Some(Utc::now()); // Just tripped
         state.review.block_count = 1; // Below limit, but already tripped
 
-        let config = CircuitBreakerConfig {
-            max_blocks: 3,
-            cooldown_seconds: 300, // 5 minutes cooldown
-        };
+        let config = CircuitBreakerConfig::default(); // 5 minutes cooldown
 
         // Should still trip because cooldown hasn't elapsed
-        assert!(should_trip(&state, &config));
+        assert!(should_trip(&state, &config, &ctx()));
     }
 
     #[test]
@@ -133,13 +270,10 @@ mod tests {
         state.review.circuit_breaker_tripped_at = Some(Utc::now() - Duration::minutes(10));
         state.review.block_count = 1;
 
-        let config = CircuitBreakerConfig {
-            max_blocks: 3,
-            cooldown_seconds: 300, // 5 minutes cooldown (already elapsed)
-        };
+        let config = CircuitBreakerConfig::default(); // 5 minutes (already elapsed)
 
         // Should NOT trip because cooldown has elapsed
-        assert!(!should_trip(&state, &config));
+        assert!(!should_trip(&state, &config, &ctx()));
     }
 
     #[test]
@@ -148,13 +282,10 @@ mod tests {
         state.review.circuit_breaker_tripped = true;
         state.review.circuit_breaker_tripped_at = None; // Legacy state without timestamp
 
-        let config = CircuitBreakerConfig {
-            max_blocks: 3,
-            cooldown_seconds: 300,
-        };
+        let config = CircuitBreakerConfig::default();
 
         // Should still trip for legacy states (conservative behavior)
-        assert!(should_trip(&state, &config));
+        assert!(should_trip(&state, &config, &ctx()));
     }
 
     #[test]
@@ -163,11 +294,21 @@ mod tests {
         state.review.enabled = true;
         state.review.block_count = 3;
 
-        trip(&mut state);
+        trip(
+            &mut state,
+            &CircuitBreakerConfig::default(),
+            TripCause::ReviewerNotSpawned,
+            &ctx(),
+        );
 
         assert!(state.review.circuit_breaker_tripped);
         assert!(state.review.circuit_breaker_tripped_at.is_some());
         assert!(!state.review.enabled);
+        assert_eq!(state.review.trip_cause, Some(TripCause::ReviewerNotSpawned));
+        assert_eq!(state.review.trip_count, 1);
+        let event = state.trace.last().unwrap();
+        assert_eq!(event.event_type, EventType::CircuitBreakerTripped);
+        assert_eq!(event.payload["cause"], "reviewer_not_spawned");
     }
 
     #[test]
@@ -178,11 +319,156 @@ mod tests {
         state.review.block_count = 5;
         state.review.enabled = false;
 
-        reset(&mut state);
+        reset(&mut state, &ctx());
 
         assert!(!state.review.circuit_breaker_tripped);
         assert!(state.review.circuit_breaker_tripped_at.is_none());
         assert_eq!(state.review.block_count, 0);
         assert!(state.review.enabled);
+        assert_eq!(
+            state.trace.last().unwrap().event_type,
+            EventType::CircuitBreakerReset
+        );
+    }
+
+    #[test]
+    fn per_cause_limits_are_independent() {
+        let config = CircuitBreakerConfig {
+            max_not_spawned_blocks: Some(2),
+            max_issues_blocks: Some(5),
+            ..CircuitBreakerConfig::default()
+        };
+
+        let mut state = SessionState::new("test-123");
+        state.review.issues_blocks = 4;
+        state.review.not_spawned_blocks = 1;
+        assert_eq!(exceeded(&state, &config), None);
+
+        state.review.not_spawned_blocks = 2;
+        assert_eq!(
+            exceeded(&state, &config),
+            Some(TripCause::ReviewerNotSpawned)
+        );
+
+        state.review.not_spawned_blocks = 0;
+        state.review.issues_blocks = 5;
+        assert_eq!(exceeded(&state, &config), Some(TripCause::IssuesUnresolved));
+    }
+
+    #[test]
+    fn repeated_trips_back_off() {
+        let config = CircuitBreakerConfig::default();
+        let mut state = SessionState::new("test-123");
+        state.review.trip_count = 1;
+        state.review.circuit_breaker_tripped = true;
+        // 8 minutes: past the first cooldown (5m), within the second (10m)
+        state.review.circuit_breaker_tripped_at = Some(Utc::now() - Duration::minutes(8));
+        assert!(!should_trip(&state, &config, &ctx()));
+
+        state.review.trip_count = 2;
+        assert!(should_trip(&state, &config, &ctx()));
+        assert_eq!(cooldown(&state, &config), Duration::seconds(600));
+    }
+
+    #[test]
+    fn trip_keeps_review_enabled_unless_approving() {
+        let config = CircuitBreakerConfig {
+            on_trip: TripAction::Block,
+            ..CircuitBreakerConfig::default()
+        };
+        let mut state = SessionState::new("test-123");
+        state.review.enabled = true;
+
+        trip(&mut state, &config, TripCause::IssuesUnresolved, &ctx());
+        assert!(state.review.enabled);
+        assert_eq!(state.trace.last().unwrap().payload["on_trip"], "block");
+    }
+
+    #[test]
+    fn gate_denials_trip_per_pattern() {
+        let mut state = SessionState::new("test-123");
+        let config = CircuitBreakerConfig::default(); // 3 denials
+
+        for _ in 0..2 {
+            assert!(!record_gate_denial(
+                &mut state,
+                &config,
+                "Bash:git push*",
+                "Bash:git push",
+                &ctx()
+            ));
+        }
+        // Other patterns count separately
+        assert!(!record_gate_denial(
+            &mut state,
+            &config,
+            "Bash:gh pr merge*",
+            "Bash:gh pr merge 1",
+            &ctx()
+        ));
+        assert!(!gate_tripped(&mut state, &config, "Bash:git push*", &ctx()));
+
+        assert!(record_gate_denial(
+            &mut state,
+            &config,
+            "Bash:git push*",
+            "Bash:git push",
+            &ctx()
+        ));
+        assert!(gate_tripped(&mut state, &config, "Bash:git push*", &ctx()));
+        assert!(!gate_tripped(
+            &mut state,
+            &config,
+            "Bash:gh pr merge*",
+            &ctx()
+        ));
+
+        let event = state.trace.last().unwrap();
+        assert_eq!(event.event_type, EventType::GateBreakerTripped);
+        assert_eq!(event.payload["pattern"], "Bash:git push*");
+        assert_eq!(event.payload["denials"], 3);
+    }
+
+    #[test]
+    fn gate_trip_resets_after_cooldown() {
+        let mut state = SessionState::new("test-123");
+        let config = CircuitBreakerConfig::default();
+        state
+            .review
+            .gate_denials
+            .insert("Bash:git push*".to_string(), 3);
+        state.review.gate_tripped_at.insert(
+            "Bash:git push*".to_string(),
+            Utc::now() - Duration::minutes(10),
+        );
+
+        assert!(!gate_tripped(&mut state, &config, "Bash:git push*", &ctx()));
+        assert!(state.review.gate_denials.is_empty());
+        assert!(state.review.gate_tripped_at.is_empty());
+    }
+
+    #[test]
+    fn breaker_cools_down_on_a_fixed_clock() {
+        let clock = FixedClock::new(Utc::now());
+        let rng = SeededRng::new(1);
+        let ctx = Context::new(&clock, &rng);
+        let config = CircuitBreakerConfig::default(); // 5 minute cooldown
+        let mut state = SessionState::new("test-123");
+
+        trip(&mut state, &config, TripCause::ReviewerNotSpawned, &ctx);
+        assert_eq!(state.review.circuit_breaker_tripped_at, Some(clock.now()));
+
+        clock.advance(Duration::seconds(299));
+        assert!(should_trip(&state, &config, &ctx));
+        clock.advance(Duration::seconds(1));
+        assert!(!should_trip(&state, &config, &ctx));
+
+        // The second trip backs off to 10 minutes
+        reset(&mut state, &ctx);
+        trip(&mut state, &config, TripCause::ReviewerNotSpawned, &ctx);
+        clock.advance(Duration::minutes(9));
+        assert!(should_trip(&state, &config, &ctx));
+        clock.advance(Duration::minutes(1));
+        assert!(!should_trip(&state, &config, &ctx));
     }
 }
@@ -0,0 +1,200 @@
+//! Clock and randomness sources for the hook handlers.
+//!
+//! Handlers read the time and generate IDs through a [`Context`] passed
+//! alongside the store and config. Production code uses [`Context::system`];
+//! tests and `roz replay` use a [`FixedClock`] and [`SeededRng`] so TTLs,
+//! cooldowns and review-cycle windows can be exercised without sleeping.
+
+use chrono::{DateTime, Duration, Utc};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use uuid::{Builder, Uuid};
+
+/// Source of the current time.
+pub trait Clock: Send + Sync {
+    /// Current time.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// A shared clock, so a caller can keep a handle to a [`FixedClock`] it
+/// handed to an engine and move it later.
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> DateTime<Utc> {
+        (**self).now()
+    }
+}
+
+/// Source of random numbers (trace and attempt IDs).
+pub trait Rng: Send + Sync {
+    /// Next random 64-bit value.
+    fn next_u64(&self) -> u64;
+}
+
+/// The system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that only moves when told to.
+#[derive(Debug)]
+pub struct FixedClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl FixedClock {
+    /// Create a clock stopped at `now`.
+    #[must_use]
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Mutex::new(now),
+        }
+    }
+
+    /// Move the clock to `now`.
+    pub fn set(&self, now: DateTime<Utc>) {
+        *lock(&self.now) = now;
+    }
+
+    /// Move the clock forward by `by`.
+    pub fn advance(&self, by: Duration) {
+        let mut now = lock(&self.now);
+        *now += by;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *lock(&self.now)
+    }
+}
+
+/// Operating system randomness (via `uuid`'s v4 generator).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemRng;
+
+impl Rng for SystemRng {
+    fn next_u64(&self) -> u64 {
+        Uuid::new_v4().as_u64_pair().0
+    }
+}
+
+/// A deterministic generator (`SplitMix64`) for reproducible IDs.
+#[derive(Debug)]
+pub struct SeededRng {
+    state: Mutex<u64>,
+}
+
+impl SeededRng {
+    /// Create a generator from `seed`.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: Mutex::new(seed),
+        }
+    }
+}
+
+impl Rng for SeededRng {
+    fn next_u64(&self) -> u64 {
+        let mut state = lock(&self.state);
+        *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// Lock a mutex, recovering the value if a holder panicked.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+static SYSTEM_CLOCK: SystemClock = SystemClock;
+static SYSTEM_RNG: SystemRng = SystemRng;
+
+/// Clock and randomness for one hook or command run.
+#[derive(Clone, Copy)]
+pub struct Context<'a> {
+    /// Time source.
+    pub clock: &'a dyn Clock,
+
+    /// Randomness source.
+    pub rng: &'a dyn Rng,
+}
+
+impl Context<'static> {
+    /// The system clock and OS randomness.
+    #[must_use]
+    pub fn system() -> Self {
+        Self {
+            clock: &SYSTEM_CLOCK,
+            rng: &SYSTEM_RNG,
+        }
+    }
+}
+
+impl<'a> Context<'a> {
+    /// Create a context from explicit sources.
+    #[must_use]
+    pub fn new(clock: &'a dyn Clock, rng: &'a dyn Rng) -> Self {
+        Self { clock, rng }
+    }
+
+    /// Current time.
+    #[must_use]
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    /// Generate a v4 UUID string from the randomness source.
+    #[must_use]
+    pub fn generate_id(&self) -> String {
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&self.rng.next_u64().to_le_bytes());
+        bytes[8..].copy_from_slice(&self.rng.next_u64().to_le_bytes());
+        Builder::from_random_bytes(bytes).into_uuid().to_string()
+    }
+}
+
+impl std::fmt::Debug for Context<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Context")
+            .field("now", &self.now())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_clock_moves_only_when_told() {
+        let start = Utc::now();
+        let clock = FixedClock::new(start);
+        assert_eq!(clock.now(), start);
+
+        clock.advance(Duration::minutes(5));
+        assert_eq!(clock.now(), start + Duration::minutes(5));
+
+        clock.set(start);
+        assert_eq!(clock.now(), start);
+    }
+
+    #[test]
+    fn seeded_rng_ids_are_reproducible() {
+        let clock = FixedClock::new(Utc::now());
+        let (a, b) = (SeededRng::new(7), SeededRng::new(7));
+        let (ctx_a, ctx_b) = (Context::new(&clock, &a), Context::new(&clock, &b));
+
+        let id = ctx_a.generate_id();
+        assert_eq!(id, ctx_b.generate_id());
+        assert_ne!(id, ctx_a.generate_id());
+        assert_eq!(Uuid::parse_str(&id).unwrap().get_version_num(), 4);
+    }
+}
@@ -0,0 +1,291 @@
+//! Reviewer evidence.
+//!
+//! The roz:roz subagent's own transcript shows what it inspected before
+//! deciding: `roz context`, `git diff`, and the files it read.
+//! [`ReviewerEvidence`] summarizes those tool calls and
+//! [`missing_evidence`] checks them against `[review.evidence]`.
+
+use crate::config::EvidenceConfig;
+use crate::core::triggers::changed_files;
+use crate::transcript::ToolUse;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
+
+/// `git diff` / `git show`, allowing `git -C <dir>` in between.
+static GIT_DIFF: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\bgit(\s+-C\s+\S+)?\s+(diff|show)\b").expect("valid regex"));
+
+/// What the reviewer inspected, from its tool calls.
+#[derive(Debug, Default, Clone, Serialize, PartialEq, Eq)]
+pub struct ReviewerEvidence {
+    /// Ran `roz context`.
+    pub ran_context: bool,
+
+    /// Ran `git diff` or `git show`.
+    pub ran_diff: bool,
+
+    /// Files opened with `Read`, resolved against the cwd and normalized.
+    pub files_read: BTreeSet<PathBuf>,
+
+    /// `Grep` and `Glob` calls.
+    pub searches: u32,
+}
+
+impl ReviewerEvidence {
+    /// Summarize a reviewer's tool calls.
+    #[must_use]
+    pub fn from_tool_uses(uses: &[ToolUse], cwd: &Path) -> Self {
+        let mut evidence = Self::default();
+        for tool in uses {
+            let field = |key: &str| tool.input.get(key).and_then(Value::as_str);
+            match tool.name.as_str() {
+                "Read" => {
+                    if let Some(path) = field("file_path") {
+                        evidence.files_read.insert(normalize(&cwd.join(path)));
+                    }
+                }
+                "Grep" | "Glob" => evidence.searches += 1,
+                "Bash" => {
+                    let command = field("command").unwrap_or_default();
+                    evidence.ran_context |= command.contains("roz context");
+                    evidence.ran_diff |= GIT_DIFF.is_match(command);
+                }
+                _ => {}
+            }
+        }
+        evidence
+    }
+}
+
+/// Requirements of `policy` that `evidence` does not meet, phrased as what
+/// the reviewer still has to do (empty when the evidence is sufficient).
+#[must_use]
+pub fn missing_evidence(
+    policy: &EvidenceConfig,
+    evidence: &ReviewerEvidence,
+    session_id: &str,
+    cwd: &Path,
+) -> Vec<String> {
+    let mut missing = Vec::new();
+    if policy.require_context && !evidence.ran_context {
+        missing.push(format!("run `roz context {session_id}`"));
+    }
+    if policy.require_diff && !evidence.ran_diff {
+        missing.push("run `git diff` to see what actually changed".to_string());
+    }
+    if policy.read_changed_files {
+        let cwd = normalize(cwd);
+        let unread: Vec<String> = changed_paths(&cwd)
+            .into_iter()
+            .filter(|path| !evidence.files_read.contains(path))
+            .map(|path| {
+                path.strip_prefix(&cwd)
+                    .unwrap_or(&path)
+                    .display()
+                    .to_string()
+            })
+            .collect();
+        if !unread.is_empty() {
+            missing.push(format!("read the changed files: {}", unread.join(", ")));
+        }
+    }
+    let read = evidence.files_read.len();
+    if read < policy.min_files_read as usize {
+        missing.push(format!(
+            "read at least {} files (read {read})",
+            policy.min_files_read
+        ));
+    }
+    missing
+}
+
+/// Existing files changed in the working tree of `cwd`, as normalized
+/// absolute paths.
+fn changed_paths(cwd: &Path) -> Vec<PathBuf> {
+    let Some(root) = repo_root(cwd) else {
+        return Vec::new();
+    };
+    changed_files(cwd)
+        .into_iter()
+        .map(|file| root.join(file))
+        .filter(|path| path.is_file()) // Deleted files cannot be read
+        .map(|path| normalize(&path))
+        .collect()
+}
+
+/// `path` with symlinks resolved, so the reviewer's paths and git's agree
+/// (e.g. `/tmp` vs `/private/tmp`). Paths that do not exist are only cleaned
+/// up lexically (`.` dropped, `..` applied).
+fn normalize(path: &Path) -> PathBuf {
+    if let Ok(path) = path.canonicalize() {
+        return path;
+    }
+    let mut normal = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normal.pop();
+            }
+            other => normal.push(other),
+        }
+    }
+    normal
+}
+
+/// Top-level directory of the git repository containing `cwd`.
+fn repo_root(cwd: &Path) -> Option<PathBuf> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(cwd)
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+
+    fn tool(name: &str, input: Value) -> ToolUse {
+        ToolUse {
+            name: name.to_string(),
+            input,
+        }
+    }
+
+    #[test]
+    fn summarizes_reviewer_tool_calls() {
+        let cwd = Path::new("/work");
+        let uses = [
+            tool("Bash", json!({"command": "roz context abc --transcript"})),
+            tool("Bash", json!({"command": "git -C /work diff HEAD~1"})),
+            tool("Read", json!({"file_path": "src/lib.rs"})),
+            tool("Read", json!({"file_path": "/work/README.md"})),
+            tool("Read", json!({"file_path": "./docs/../src/lib.rs"})),
+            tool("Grep", json!({"pattern": "fn main"})),
+            tool("Edit", json!({"file_path": "src/lib.rs"})),
+        ];
+        let evidence = ReviewerEvidence::from_tool_uses(&uses, cwd);
+        assert!(evidence.ran_context);
+        assert!(evidence.ran_diff);
+        assert_eq!(
+            evidence.files_read,
+            BTreeSet::from([
+                PathBuf::from("/work/README.md"),
+                PathBuf::from("/work/src/lib.rs")
+            ])
+        );
+        assert_eq!(evidence.searches, 1);
+
+        let none = ReviewerEvidence::from_tool_uses(
+            &[tool("Bash", json!({"command": "git log --oneline"}))],
+            cwd,
+        );
+        assert!(!none.ran_context && !none.ran_diff);
+    }
+
+    #[test]
+    fn missing_evidence_lists_unmet_requirements() {
+        let cwd = Path::new("/work");
+        let policy = EvidenceConfig {
+            require_context: true,
+            require_diff: true,
+            min_files_read: 2,
+            ..EvidenceConfig::default()
+        };
+        let evidence = ReviewerEvidence {
+            ran_diff: true,
+            files_read: BTreeSet::from([PathBuf::from("/work/a.rs")]),
+            ..ReviewerEvidence::default()
+        };
+        assert_eq!(
+            missing_evidence(&policy, &evidence, "s1", cwd),
+            ["run `roz context s1`", "read at least 2 files (read 1)"]
+        );
+        assert!(missing_evidence(&EvidenceConfig::default(), &evidence, "s1", cwd).is_empty());
+    }
+
+    #[test]
+    fn changed_files_must_all_be_read() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cwd = temp.path();
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(cwd)
+            .args(["init", "--quiet"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        fs::create_dir_all(cwd.join("src")).unwrap();
+        fs::write(cwd.join("src/a.rs"), "").unwrap();
+        fs::write(cwd.join("src/b.rs"), "").unwrap();
+
+        let policy = EvidenceConfig {
+            read_changed_files: true,
+            ..EvidenceConfig::default()
+        };
+        let evidence = ReviewerEvidence::from_tool_uses(
+            &[tool("Read", json!({"file_path": "src/a.rs"}))],
+            cwd,
+        );
+        assert_eq!(
+            missing_evidence(&policy, &evidence, "s1", cwd),
+            ["read the changed files: src/b.rs"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn changed_files_match_through_a_symlinked_cwd() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let repo = temp.path().join("repo");
+        fs::create_dir_all(repo.join("src")).unwrap();
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(&repo)
+            .args(["init", "--quiet"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        fs::write(repo.join("src/a.rs"), "").unwrap();
+
+        // The session sees the repo through a link, as with /tmp on macOS
+        let cwd = temp.path().join("link");
+        std::os::unix::fs::symlink(&repo, &cwd).unwrap();
+
+        let policy = EvidenceConfig {
+            read_changed_files: true,
+            ..EvidenceConfig::default()
+        };
+        let unread = ReviewerEvidence::default();
+        assert_eq!(
+            missing_evidence(&policy, &unread, "s1", &cwd),
+            ["read the changed files: src/a.rs"]
+        );
+        for read in ["./src/a.rs", "src/a.rs"] {
+            let evidence =
+                ReviewerEvidence::from_tool_uses(&[tool("Read", json!({"file_path": read}))], &cwd);
+            assert!(
+                missing_evidence(&policy, &evidence, "s1", &cwd).is_empty(),
+                "{read}"
+            );
+        }
+        let evidence = ReviewerEvidence::from_tool_uses(
+            &[tool("Read", json!({"file_path": cwd.join("src/a.rs")}))],
+            Path::new("/elsewhere"),
+        );
+        assert!(missing_evidence(&policy, &evidence, "s1", &cwd).is_empty());
+    }
+}
@@ -1,24 +1,30 @@
 //! Hook handler implementations.
 
-use crate::config::{ApprovalScope, CircuitBreakerConfig, Config, GatesConfig};
+use crate::config::{
+    ApprovalScope, CircuitBreakerConfig, Config, EDIT_TOOLS, GatesConfig, ReviewMode,
+    RiskThresholds, TripAction,
+};
 use crate::core::circuit_breaker;
+use crate::core::context::Context;
+use crate::core::evidence::{ReviewerEvidence, missing_evidence};
+use crate::core::safety::{FailingInput, handle_error, report_incident};
 use crate::core::state::{
-    AttemptOutcome, Decision, EventType, GateTrigger, ReviewAttempt, SessionState, TraceEvent,
-    TruncatedInput,
+    AttemptOutcome, Decision, DecisionRecord, EventType, GateTrigger, ReviewAttempt,
+    SessionActivity, SessionState, TraceEvent, TruncatedInput,
+};
+use crate::core::triggers::review_trigger;
+use crate::hooks::{
+    HookCommon, HookOutput, NotificationEvent, PreCompactEvent, PreToolUseEvent, PreToolUseOutput,
+    SessionEndEvent, SessionStartEvent, StopEvent, SubagentStopEvent, UserPromptSubmitEvent,
 };
-use crate::hooks::{HookInput, HookOutput, PreToolUseOutput};
-use crate::storage::MessageStore;
-use crate::template::{load_template, select_template};
-use chrono::{Duration, Utc};
+use crate::storage::{IncidentKind, MessageStore};
+use crate::template::{load_reminder_template, load_template, render_reminder, select_template};
+use crate::transcript::read_tool_uses;
+use chrono::{DateTime, Duration, Utc};
 use glob::Pattern;
 use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use uuid::Uuid;
-
-/// Generate a unique ID for trace events.
-fn generate_id() -> String {
-    Uuid::new_v4().to_string()
-}
 
 /// Maximum size for stored user prompts (10KB).
 const MAX_PROMPT_SIZE: usize = 10 * 1024;
@@ -42,44 +48,98 @@ fn truncate_prompt(prompt: &str) -> String {
     }
 }
 
+/// Save session state, reporting a `StorageWrite` incident on failure.
+fn save_session(store: &dyn MessageStore, hook: &str, state: &SessionState, ctx: &Context) -> bool {
+    match store.put_session(state) {
+        Ok(()) => true,
+        Err(e) => {
+            report_incident(
+                store,
+                hook,
+                IncidentKind::StorageWrite,
+                Some(&state.session_id),
+                &format!("failed to save state: {e}"),
+                ctx,
+            );
+            false
+        }
+    }
+}
+
+/// Handle an error in a hook, blocking with a diagnostic if the safety mode says so.
+fn fail(
+    store: &dyn MessageStore,
+    config: &Config,
+    hook: &str,
+    input: FailingInput,
+    kind: IncidentKind,
+    message: &str,
+    ctx: &Context,
+) -> HookOutput {
+    match handle_error(store, config, hook, Some(input), kind, message, ctx) {
+        Some(diagnostic) => HookOutput::block(&diagnostic),
+        None => HookOutput::approve(), // Fail open
+    }
+}
+
 /// Handle the session-start hook.
 ///
 /// Initializes session state and detects available second opinion sources.
-pub fn handle_session_start(input: &HookInput, store: &dyn MessageStore) -> HookOutput {
-    let session_id = &input.session_id;
+pub fn handle_session_start(event: &SessionStartEvent, store: &dyn MessageStore) -> HookOutput {
+    handle_session_start_with_context(event, store, &Context::system())
+}
+
+/// Handle the session-start hook with an explicit clock and randomness source.
+pub fn handle_session_start_with_context(
+    event: &SessionStartEvent,
+    store: &dyn MessageStore,
+    ctx: &Context,
+) -> HookOutput {
+    let session_id = &event.common.session_id;
 
     // Get or create session state
-    let state = match store.get_session(session_id) {
+    let mut state = match store.get_session(session_id) {
         Ok(Some(s)) => s, // Resume existing session
         Ok(None) => {
             // New session
-            let mut state = SessionState::new(session_id);
+            let mut state = SessionState::new_at(session_id, ctx.now());
             state.trace.push(TraceEvent {
-                id: generate_id(),
-                timestamp: Utc::now(),
+                id: ctx.generate_id(),
+                timestamp: ctx.now(),
                 event_type: EventType::SessionStart,
                 payload: json!({
-                    "source": input.source,
-                    "cwd": input.cwd,
+                    "source": event.source,
+                    "cwd": event.common.cwd,
                 }),
             });
             state
         }
         Err(e) => {
-            eprintln!("roz: warning: storage error: {e}");
+            report_incident(
+                store,
+                "session-start",
+                IncidentKind::StorageRead,
+                Some(session_id),
+                &format!("storage error: {e}"),
+                ctx,
+            );
             return HookOutput::approve(); // Fail open
         }
     };
 
-    // Save state
-    if let Err(e) = store.put_session(&state) {
-        eprintln!("roz: warning: failed to save state: {e}");
-    }
+    record_session_paths(&mut state, &event.common);
+    save_session(store, "session-start", &state, ctx);
 
-    // Optionally inject context about available second opinion sources
-    match detect_second_opinion_context() {
-        Some(ctx) => HookOutput::approve_with_context("SessionStart", &ctx),
-        None => HookOutput::approve(),
+    // Optionally inject context about available second opinion sources, and
+    // after a compaction, re-inject any review the session still owes
+    let mut context: Vec<String> = detect_second_opinion_context().into_iter().collect();
+    if event.source == "compact" {
+        context.extend(pending_review_reminder(&state));
+    }
+    if context.is_empty() {
+        HookOutput::approve()
+    } else {
+        HookOutput::approve_with_context("SessionStart", &context.join("\n\n"))
     }
 }
 
@@ -87,43 +147,249 @@ pub fn handle_session_start(input: &HookInput, store: &dyn MessageStore) -> Hook
 ///
 /// Records a `SessionEnd` trace event and saves state.
 /// This hook has no decision control - it always approves.
-pub fn handle_session_end(input: &HookInput, store: &dyn MessageStore) -> HookOutput {
-    let session_id = &input.session_id;
+pub fn handle_session_end(event: &SessionEndEvent, store: &dyn MessageStore) -> HookOutput {
+    handle_session_end_with_context(event, store, &Context::system())
+}
+
+/// Handle the session-end hook with an explicit clock and randomness source.
+pub fn handle_session_end_with_context(
+    event: &SessionEndEvent,
+    store: &dyn MessageStore,
+    ctx: &Context,
+) -> HookOutput {
+    let session_id = &event.common.session_id;
 
     // Get existing session - if not found, nothing to do
     let mut state = match store.get_session(session_id) {
         Ok(Some(s)) => s,
         Ok(None) => {
-            eprintln!("roz: warning: session-end for unknown session: {session_id}");
+            report_incident(
+                store,
+                "session-end",
+                IncidentKind::SessionNotFound,
+                Some(session_id),
+                &format!("session-end for unknown session: {session_id}"),
+                ctx,
+            );
             return HookOutput::approve(); // Fail open
         }
         Err(e) => {
-            eprintln!("roz: warning: storage error: {e}");
+            report_incident(
+                store,
+                "session-end",
+                IncidentKind::StorageRead,
+                Some(session_id),
+                &format!("storage error: {e}"),
+                ctx,
+            );
             return HookOutput::approve(); // Fail open
         }
     };
 
-    let reason = input.reason.as_deref().unwrap_or("unknown");
+    // Any attempt still pending at session end never got a reviewer
+    resolve_pending_attempts(&mut state, &AttemptOutcome::NotSpawned);
 
     // Add SessionEnd trace event
     state.trace.push(TraceEvent {
-        id: generate_id(),
-        timestamp: Utc::now(),
+        id: ctx.generate_id(),
+        timestamp: ctx.now(),
         event_type: EventType::SessionEnd,
         payload: json!({
-            "reason": reason,
-            "cwd": input.cwd,
+            "reason": event.reason,
+            "cwd": event.common.cwd,
         }),
     });
 
-    // Save state
-    if let Err(e) = store.put_session(&state) {
-        eprintln!("roz: warning: failed to save state: {e}");
-    }
+    save_session(store, "session-end", &state, ctx);
+
+    HookOutput::approve()
+}
+
+/// Handle the pre-compact hook.
+///
+/// Records a `CompactionStarted` trace event noting whether a review is
+/// still owed. `PreCompact` cannot add context, so the reminder itself is
+/// injected by `SessionStart` with `source: compact` once compaction is done.
+/// Always approves.
+pub fn handle_pre_compact(
+    event: &PreCompactEvent,
+    store: &dyn MessageStore,
+    config: &Config,
+) -> HookOutput {
+    handle_pre_compact_with_context(event, store, config, &Context::system())
+}
+
+/// Handle the pre-compact hook with an explicit clock and randomness source.
+pub fn handle_pre_compact_with_context(
+    event: &PreCompactEvent,
+    store: &dyn MessageStore,
+    config: &Config,
+    ctx: &Context,
+) -> HookOutput {
+    let mut state = match store.get_session(&event.common.session_id) {
+        Ok(Some(s)) => s,
+        Ok(None) => return HookOutput::approve(), // No review state to preserve
+        Err(e) => {
+            report_incident(
+                store,
+                "pre-compact",
+                IncidentKind::StorageRead,
+                Some(&event.common.session_id),
+                &format!("storage error: {e}"),
+                ctx,
+            );
+            return HookOutput::approve(); // Fail open
+        }
+    };
+
+    let review_pending = pending_review_reminder(&state).is_some();
+    record_session_paths(&mut state, &event.common);
+    add_trace_event(
+        &mut state,
+        TraceEvent {
+            id: ctx.generate_id(),
+            timestamp: ctx.now(),
+            event_type: EventType::CompactionStarted,
+            payload: json!({
+                "trigger": event.trigger,
+                "review_pending": review_pending,
+            }),
+        },
+        config.trace.max_events,
+        ctx,
+    );
+    save_session(store, "pre-compact", &state, ctx);
+
+    HookOutput::approve()
+}
+
+/// Handle the notification hook.
+///
+/// Records permission and idle prompts as `NotificationReceived` trace
+/// events; other notifications are ignored. Always approves.
+pub fn handle_notification(
+    event: &NotificationEvent,
+    store: &dyn MessageStore,
+    config: &Config,
+) -> HookOutput {
+    handle_notification_with_context(event, store, config, &Context::system())
+}
+
+/// Handle the notification hook with an explicit clock and randomness source.
+pub fn handle_notification_with_context(
+    event: &NotificationEvent,
+    store: &dyn MessageStore,
+    config: &Config,
+    ctx: &Context,
+) -> HookOutput {
+    let Some(kind) = notification_kind(event) else {
+        return HookOutput::approve();
+    };
+
+    let mut state = match store.get_session(&event.common.session_id) {
+        Ok(Some(s)) => s,
+        Ok(None) => return HookOutput::approve(), // Not a session roz tracks
+        Err(e) => {
+            report_incident(
+                store,
+                "notification",
+                IncidentKind::StorageRead,
+                Some(&event.common.session_id),
+                &format!("storage error: {e}"),
+                ctx,
+            );
+            return HookOutput::approve(); // Fail open
+        }
+    };
+
+    record_session_paths(&mut state, &event.common);
+    add_trace_event(
+        &mut state,
+        TraceEvent {
+            id: ctx.generate_id(),
+            timestamp: ctx.now(),
+            event_type: EventType::NotificationReceived,
+            payload: json!({
+                "notification_type": kind,
+                "message": event.message,
+            }),
+        },
+        config.trace.max_events,
+        ctx,
+    );
+    save_session(store, "notification", &state, ctx);
 
     HookOutput::approve()
 }
 
+/// Classify a notification as `permission_prompt` or `idle_prompt`.
+///
+/// Older Claude Code versions omit `notification_type`, so fall back to the
+/// message text.
+fn notification_kind(event: &NotificationEvent) -> Option<&'static str> {
+    match event.notification_type.as_deref() {
+        Some("permission_prompt") => Some("permission_prompt"),
+        Some("idle_prompt") => Some("idle_prompt"),
+        Some(_) => None,
+        None => {
+            let message = &event.message;
+            if message.contains("needs your permission") {
+                Some("permission_prompt")
+            } else if message.contains("waiting for your input") {
+                Some("idle_prompt")
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Reminder of a review the session still owes, for re-injecting after
+/// compaction. `None` when review is off or the work was approved.
+fn pending_review_reminder(state: &SessionState) -> Option<String> {
+    if !state.review.enabled {
+        return None;
+    }
+
+    let session_id = &state.session_id;
+    match &state.review.decision {
+        Decision::Complete { .. } => None,
+        Decision::Issues {
+            summary,
+            message_to_agent,
+        } => Some(format!(
+            "roz: review of this session (session_id: {session_id}) found issues \
+             that still need to be addressed:\n\n{}\n\n\
+             After fixing, spawn roz:roz again to re-review before stopping.",
+            message_to_agent.as_deref().unwrap_or(summary)
+        )),
+        Decision::Pending => {
+            let mut reminder = format!(
+                "roz: this session (session_id: {session_id}) still owes an independent \
+                 review. Before stopping, spawn roz:roz to review your work."
+            );
+            let last_issues =
+                state
+                    .review
+                    .decision_history
+                    .iter()
+                    .rev()
+                    .find_map(|r| match &r.decision {
+                        Decision::Issues {
+                            summary,
+                            message_to_agent,
+                        } => Some(message_to_agent.as_deref().unwrap_or(summary)),
+                        _ => None,
+                    });
+            if let Some(issues) = last_issues {
+                reminder.push_str("\n\nThe last review found:\n\n");
+                reminder.push_str(issues);
+            }
+            Some(reminder)
+        }
+    }
+}
+
 /// Detect available second opinion sources.
 fn detect_second_opinion_context() -> Option<String> {
     let codex = command_exists("codex");
@@ -145,84 +411,126 @@ fn command_exists(cmd: &str) -> bool {
     Command::new("which")
         .arg(cmd)
         .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
+        .is_ok_and(|o| o.status.success())
 }
 
 /// Handle the user-prompt hook.
 ///
 /// Detects `#roz` prefix to enable review and stores the prompt.
-pub fn handle_user_prompt(input: &HookInput, store: &dyn MessageStore) -> HookOutput {
-    handle_user_prompt_with_config(input, store, &Config::default())
+pub fn handle_user_prompt(event: &UserPromptSubmitEvent, store: &dyn MessageStore) -> HookOutput {
+    handle_user_prompt_with_config(event, store, &Config::default())
 }
 
 /// Handle the user-prompt hook with explicit config.
 ///
-/// Detects `#roz` prefix or `ReviewMode::Always` to enable review and stores the prompt.
+/// Enables review in `ReviewMode::Always` or when a `[review] triggers` rule
+/// matches (unless the prompt opts out), and stores the prompt.
 pub fn handle_user_prompt_with_config(
-    input: &HookInput,
+    event: &UserPromptSubmitEvent,
     store: &dyn MessageStore,
     config: &Config,
 ) -> HookOutput {
-    use crate::config::ReviewMode;
+    handle_user_prompt_with_context(event, store, config, &Context::system())
+}
 
-    let session_id = &input.session_id;
-    let prompt = input.prompt.as_deref().unwrap_or("");
+/// Handle the user-prompt hook with explicit config, clock and randomness source.
+pub fn handle_user_prompt_with_context(
+    event: &UserPromptSubmitEvent,
+    store: &dyn MessageStore,
+    config: &Config,
+    ctx: &Context,
+) -> HookOutput {
+    let session_id = &event.common.session_id;
+    let prompt = &event.prompt;
 
     // Get or create session state
     let mut state = match store.get_session(session_id) {
         Ok(Some(s)) => s,
-        Ok(None) => SessionState::new(session_id),
+        Ok(None) => SessionState::new_at(session_id, ctx.now()),
         Err(e) => {
-            eprintln!("roz: warning: storage error: {e}");
-            return HookOutput::approve(); // Fail open
+            return fail(
+                store,
+                config,
+                "user-prompt",
+                (&event.common).into(),
+                IncidentKind::StorageRead,
+                &format!("storage error: {e}"),
+                ctx,
+            );
         }
     };
 
-    let now = Utc::now();
+    let now = ctx.now();
+    record_session_paths(&mut state, &event.common);
 
     // Always track last prompt time
     state.review.last_prompt_at = Some(now);
 
-    // Check if review should be enabled:
-    // 1. ReviewMode::Always enables review for all prompts
-    // 2. #roz prefix enables review for this prompt
-    // 3. ReviewMode::Never disables all review (skip even #roz prefix)
-    let should_enable = match config.review.mode {
-        ReviewMode::Always => true,
-        ReviewMode::Never => false,
-        ReviewMode::Prompt => prompt.trim_start().starts_with("#roz"),
+    // Issues from the current cycle are still unresolved when the next prompt
+    // arrives, even if that prompt starts a new cycle
+    let unresolved_issues = match &state.review.decision {
+        Decision::Issues {
+            summary,
+            message_to_agent,
+        } => Some(message_to_agent.clone().unwrap_or_else(|| summary.clone())),
+        _ => None,
     };
 
-    if should_enable {
+    // Check if review should be enabled: always mode, or the first matching
+    // trigger rule in prompt mode, unless the prompt opts out
+    if let Some(trigger) = review_trigger(&config.review, event) {
         state.review.enabled = true;
         state.review.user_prompts.push(truncate_prompt(prompt));
+        if matches!(state.review.decision, Decision::Complete { .. }) {
+            state.review.clear_claims();
+        }
         state.review.decision = Decision::Pending; // Reset for new review
+        state.review.decided_by = None;
 
         state.trace.push(TraceEvent {
-            id: generate_id(),
+            id: ctx.generate_id(),
             timestamp: now,
             event_type: EventType::PromptReceived,
-            payload: json!({ "prompt": prompt }),
+            payload: json!({ "prompt": prompt, "trigger": trigger }),
         });
     }
 
     state.updated_at = now;
 
-    // Save state
+    // An unsaved prompt can leave review disabled or a stale gate approval valid
     if let Err(e) = store.put_session(&state) {
-        eprintln!("roz: warning: failed to save state: {e}");
+        return fail(
+            store,
+            config,
+            "user-prompt",
+            (&event.common).into(),
+            IncidentKind::StorageWrite,
+            &format!("failed to save state: {e}"),
+            ctx,
+        );
     }
 
-    HookOutput::approve()
+    // Tell the agent up front that review is coming
+    let owes_review =
+        state.review.enabled && !matches!(state.review.decision, Decision::Complete { .. });
+    if !owes_review || config.templates.reminder == "off" {
+        return HookOutput::approve();
+    }
+    let reminder = render_reminder(
+        &load_reminder_template(&config.templates.reminder),
+        session_id,
+        &config.review.gates.tools,
+        unresolved_issues.as_deref(),
+    );
+    HookOutput::approve_with_context("UserPromptSubmit", &reminder)
 }
 
 /// Handle the stop hook.
 ///
 /// Blocks if review is enabled and pending.
 /// Includes circuit breaker logic to prevent infinite loops.
-pub fn handle_stop(input: &HookInput, store: &dyn MessageStore) -> HookOutput {
-    handle_stop_with_config(input, store, &Config::default())
+pub fn handle_stop(event: &StopEvent, store: &dyn MessageStore) -> HookOutput {
+    handle_stop_with_config(event, store, &Config::default())
 }
 
 /// Handle the stop hook with explicit config.
@@ -230,11 +538,21 @@ pub fn handle_stop(input: &HookInput, store: &dyn MessageStore) -> HookOutput {
 /// Blocks if review is enabled and pending.
 /// Includes circuit breaker logic to prevent infinite loops.
 pub fn handle_stop_with_config(
-    input: &HookInput,
+    event: &StopEvent,
+    store: &dyn MessageStore,
+    config: &Config,
+) -> HookOutput {
+    handle_stop_with_context(event, store, config, &Context::system())
+}
+
+/// Handle the stop hook with explicit config, clock and randomness source.
+pub fn handle_stop_with_context(
+    event: &StopEvent,
     store: &dyn MessageStore,
     config: &Config,
+    ctx: &Context,
 ) -> HookOutput {
-    let session_id = &input.session_id;
+    let session_id = &event.common.session_id;
 
     // Get session state
     let mut state = match store.get_session(session_id) {
@@ -244,54 +562,61 @@ pub fn handle_stop_with_config(
             return HookOutput::approve();
         }
         Err(e) => {
-            eprintln!("roz: warning: storage error: {e}");
-            return HookOutput::approve(); // Fail open
+            return fail(
+                store,
+                config,
+                "stop",
+                event.into(),
+                IncidentKind::StorageRead,
+                &format!("storage error: {e}"),
+                ctx,
+            );
         }
     };
 
-    let now = Utc::now();
+    let now = ctx.now();
+    record_session_paths(&mut state, &event.common);
 
-    // Defense-in-depth: when stop_hook_active is true, Claude Code is telling us
-    // we're in a block-continue loop. Reduce effective max_blocks by 1 (floor 1)
-    // to trip the circuit breaker one block sooner.
-    let stop_hook_active = input.stop_hook_active.unwrap_or(false);
-    let effective_cb = if stop_hook_active {
-        CircuitBreakerConfig {
-            max_blocks: config.circuit_breaker.max_blocks.saturating_sub(1).max(1),
-            ..config.circuit_breaker.clone()
-        }
-    } else {
-        config.circuit_breaker.clone()
-    };
+    let stop_hook_active = event.stop_hook_active;
+    let effective_cb = effective_breaker(&config.circuit_breaker, stop_hook_active);
 
-    // Log the stop hook call (include stop_hook_active and effective_max_blocks)
+    // Log the stop hook call (include stop_hook_active and effective limits)
     state.trace.push(TraceEvent {
-        id: generate_id(),
+        id: ctx.generate_id(),
         timestamp: now,
         event_type: EventType::StopHookCalled,
         payload: json!({
             "stop_hook_active": stop_hook_active,
             "effective_max_blocks": effective_cb.max_blocks,
+            "effective_max_not_spawned_blocks": effective_cb.not_spawned_limit(),
+            "effective_max_issues_blocks": effective_cb.issues_limit(),
         }),
     });
 
     // Check if review is enabled
     if !state.review.enabled {
         state.updated_at = now;
-        let _ = store.put_session(&state);
+        save_session(store, "stop", &state, ctx);
         return HookOutput::approve();
     }
 
-    // Check circuit breaker BEFORE incrementing block count
+    record_claimed_summary(&mut state, event, now);
+
+    // The agent is stopping again. A reviewer that ran would have resolved the
+    // previous attempt (decision posted, or NoDecision via subagent-stop), so
+    // anything still pending means roz:roz was never spawned.
+    resolve_pending_attempts(&mut state, &AttemptOutcome::NotSpawned);
+
+    // Check circuit breaker BEFORE incrementing block counts
     // If previously tripped but cooldown elapsed, reset the circuit breaker
-    if state.review.circuit_breaker_tripped && !circuit_breaker::should_trip(&state, &effective_cb)
-    {
-        circuit_breaker::reset(&mut state);
-    } else if circuit_breaker::should_trip(&state, &effective_cb) {
-        circuit_breaker::trip(&mut state);
-        state.updated_at = now;
-        let _ = store.put_session(&state);
-        return HookOutput::approve();
+    if state.review.circuit_breaker_tripped {
+        if circuit_breaker::should_trip(&state, &effective_cb, ctx) {
+            return tripped_stop(store, config, event, &mut state, ctx);
+        }
+        circuit_breaker::reset(&mut state, ctx);
+    } else if let Some(cause) = circuit_breaker::exceeded(&state, &effective_cb) {
+        circuit_breaker::trip(&mut state, &effective_cb, cause, ctx);
+        return tripped_stop(store, config, event, &mut state, ctx);
     }
 
     // Check decision - clone any needed data before mutable operations
@@ -299,21 +624,23 @@ pub fn handle_stop_with_config(
         Decision::Pending => {
             // Block and request review
             state.review.block_count += 1;
+            state.review.not_spawned_blocks += 1;
 
             // Check circuit breaker AFTER incrementing
-            if circuit_breaker::should_trip(&state, &effective_cb) {
-                circuit_breaker::trip(&mut state);
-                state.updated_at = now;
-                let _ = store.put_session(&state);
-                return HookOutput::approve();
+            if let Some(cause) = circuit_breaker::exceeded(&state, &effective_cb) {
+                circuit_breaker::trip(&mut state, &effective_cb, cause, ctx);
+                return tripped_stop(store, config, event, &mut state, ctx);
             }
 
             // Select template (supports A/B testing via random selection)
-            let template_id = select_template(&config.templates);
-            record_review_attempt(&mut state, &template_id);
+            let template_id = select_template(&config.templates, session_id);
+            record_review_attempt(&mut state, &template_id, now);
 
             let template = load_template(&template_id);
-            let message = template.replace("{{session_id}}", session_id);
+            let mut message = template.replace("{{session_id}}", session_id);
+            if let Some(threshold) = &state.review.activity.risk_trigger {
+                message = format!("roz enabled review automatically: {threshold}.\n\n{message}");
+            }
 
             HookOutput::block(&message)
         }
@@ -329,18 +656,17 @@ pub fn handle_stop_with_config(
             let msg = message_to_agent.clone().unwrap_or_else(|| summary.clone());
 
             state.review.block_count += 1;
+            state.review.issues_blocks += 1;
 
             // Check circuit breaker AFTER incrementing
-            if circuit_breaker::should_trip(&state, &effective_cb) {
-                circuit_breaker::trip(&mut state);
-                state.updated_at = now;
-                let _ = store.put_session(&state);
-                return HookOutput::approve();
+            if let Some(cause) = circuit_breaker::exceeded(&state, &effective_cb) {
+                circuit_breaker::trip(&mut state, &effective_cb, cause, ctx);
+                return tripped_stop(store, config, event, &mut state, ctx);
             }
 
             // Record attempt for issues re-review
-            let template_id = select_template(&config.templates);
-            record_review_attempt(&mut state, &template_id);
+            let template_id = select_template(&config.templates, session_id);
+            record_review_attempt(&mut state, &template_id, now);
 
             HookOutput::block(&format!(
                 "Review found issues that need to be addressed:\n\n{msg}\n\n\
@@ -350,9 +676,107 @@ pub fn handle_stop_with_config(
     };
 
     state.updated_at = now;
-    let _ = store.put_session(&state);
+    save_stop_state(store, config, event, &state, output, ctx)
+}
 
-    output
+/// Respond to a stop while the circuit breaker is tripped, per `on_trip`.
+fn tripped_stop(
+    store: &dyn MessageStore,
+    config: &Config,
+    event: &StopEvent,
+    state: &mut SessionState,
+    ctx: &Context,
+) -> HookOutput {
+    let output = if matches!(state.review.decision, Decision::Complete { .. }) {
+        HookOutput::approve()
+    } else {
+        match config.circuit_breaker.on_trip {
+            TripAction::Approve => HookOutput::approve(),
+            TripAction::Ask => HookOutput::approve_with_message(&tripped_message(state)),
+            TripAction::Block => HookOutput::block(&format!(
+                "{}\n\nStop working and ask the user to review.",
+                tripped_message(state)
+            )),
+        }
+    };
+
+    state.updated_at = ctx.now();
+    save_stop_state(store, config, event, state, output, ctx)
+}
+
+/// Explain a tripped circuit breaker and how a human can resolve it.
+fn tripped_message(state: &SessionState) -> String {
+    let session_id = &state.session_id;
+    let cause = state
+        .review
+        .trip_cause
+        .map_or("block limit reached", |c| c.as_str());
+    format!(
+        "roz circuit breaker tripped ({cause}) for session {session_id}; review was skipped.\n\
+         Decide with: roz approve {session_id} --reason \"...\"\n\
+         or: roz reject {session_id} --message \"what to fix\""
+    )
+}
+
+/// Breaker limits for a stop hook call.
+///
+/// Defense-in-depth: when `stop_hook_active` is true, Claude Code is telling us
+/// we're in a block-continue loop. Reduce every effective limit by 1 (floor 1)
+/// to trip the circuit breaker one block sooner.
+fn effective_breaker(cb: &CircuitBreakerConfig, stop_hook_active: bool) -> CircuitBreakerConfig {
+    if !stop_hook_active {
+        return cb.clone();
+    }
+    let tighten = |limit: u32| limit.saturating_sub(1).max(1);
+    CircuitBreakerConfig {
+        max_blocks: tighten(cb.max_blocks),
+        max_not_spawned_blocks: Some(tighten(cb.not_spawned_limit())),
+        max_issues_blocks: Some(tighten(cb.issues_limit())),
+        ..cb.clone()
+    }
+}
+
+/// Save state at the end of the stop hook.
+///
+/// An unsaved block is invisible to the circuit breaker, so a block only
+/// stands while the safety mode's own cap allows it.
+fn save_stop_state(
+    store: &dyn MessageStore,
+    config: &Config,
+    event: &StopEvent,
+    state: &SessionState,
+    output: HookOutput,
+    ctx: &Context,
+) -> HookOutput {
+    let Err(e) = store.put_session(state) else {
+        return output;
+    };
+
+    let message = format!("failed to save state: {e}");
+    if output.decision.is_none() {
+        report_incident(
+            store,
+            "stop",
+            IncidentKind::StorageWrite,
+            Some(&state.session_id),
+            &message,
+            ctx,
+        );
+        return output;
+    }
+
+    match handle_error(
+        store,
+        config,
+        "stop",
+        Some(event.into()),
+        IncidentKind::StorageWrite,
+        &message,
+        ctx,
+    ) {
+        Some(_) => output,
+        None => HookOutput::approve(), // Fail open
+    }
 }
 
 /// Handle the subagent-stop hook.
@@ -360,33 +784,77 @@ pub fn handle_stop_with_config(
 /// Validates that roz:roz posted a decision during its execution.
 /// Uses the parent session's `session_id` from the hook input directly,
 /// since `SubagentStop` fires in the context of the parent session.
-pub fn handle_subagent_stop(input: &HookInput, store: &dyn MessageStore) -> HookOutput {
+pub fn handle_subagent_stop(event: &SubagentStopEvent, store: &dyn MessageStore) -> HookOutput {
+    handle_subagent_stop_with_config(event, store, &Config::default())
+}
+
+/// Handle the subagent-stop hook with explicit config.
+///
+/// Validates that roz:roz posted a decision during its execution.
+pub fn handle_subagent_stop_with_config(
+    event: &SubagentStopEvent,
+    store: &dyn MessageStore,
+    config: &Config,
+) -> HookOutput {
+    handle_subagent_stop_with_context(event, store, config, &Context::system())
+}
+
+/// Handle the subagent-stop hook with explicit config, clock and randomness source.
+pub fn handle_subagent_stop_with_context(
+    event: &SubagentStopEvent,
+    store: &dyn MessageStore,
+    config: &Config,
+    ctx: &Context,
+) -> HookOutput {
     // Only validate roz:roz subagent
-    match &input.agent_type {
+    match &event.agent_type {
         Some(t) if t == "roz:roz" => {}
         _ => return HookOutput::approve(),
     }
 
-    let session_id = &input.session_id;
+    let session_id = &event.common.session_id;
 
     // Check if roz recorded a decision
-    let state = match store.get_session(session_id) {
+    let mut state = match store.get_session(session_id) {
         Ok(Some(s)) => s,
         Ok(None) => {
-            eprintln!("roz: warning: session {session_id} not found");
-            return HookOutput::approve(); // Fail open - can't verify
+            return fail(
+                store,
+                config,
+                "subagent-stop",
+                event.into(),
+                IncidentKind::SessionNotFound,
+                &format!("session {session_id} not found"),
+                ctx,
+            );
         }
         Err(e) => {
-            eprintln!("roz: warning: storage error: {e}");
-            return HookOutput::approve(); // Fail open
+            return fail(
+                store,
+                config,
+                "subagent-stop",
+                event.into(),
+                IncidentKind::StorageRead,
+                &format!("storage error: {e}"),
+                ctx,
+            );
         }
     };
 
-    if input.stop_hook_active.unwrap_or(false) {
+    if event.stop_hook_active {
         eprintln!("roz: info: subagent-stop for {session_id} with stop_hook_active=true");
     }
 
-    match &state.review.decision {
+    // Lower bound of the current review cycle: most recent block attempt
+    // (stop hook) or review start (gate).
+    let lower_bound = state
+        .review
+        .attempts
+        .last()
+        .map(|a| a.timestamp)
+        .or(state.review.review_started_at);
+
+    let output = match &state.review.decision {
         Decision::Pending => HookOutput::block(&format!(
             "roz:roz completed but did not record a decision.\n\n\
              Run: roz decide {session_id} COMPLETE \"summary\"\n\
@@ -394,43 +862,203 @@ pub fn handle_subagent_stop(input: &HookInput, store: &dyn MessageStore) -> Hook
         )),
         Decision::Complete { .. } | Decision::Issues { .. } => {
             // Verify decision was posted during the current review cycle.
-            // Lower bound: most recent block attempt (stop hook) or review start (gate).
             // Upper bound: now + 5s clock-skew buffer.
             let decision_time = state.updated_at;
 
-            let lower_bound = state
-                .review
-                .attempts
-                .last()
-                .map(|a| a.timestamp)
-                .or(state.review.review_started_at);
-
-            if let Some(lower) = lower_bound {
-                if decision_time < lower {
-                    return HookOutput::block(&format!(
-                        "Decision timestamp ({}) is before the current review cycle ({}). \
-                         Decision must be posted by roz:roz during its execution.",
-                        decision_time.format("%Y-%m-%dT%H:%M:%SZ"),
-                        lower.format("%Y-%m-%dT%H:%M:%SZ")
-                    ));
-                }
-            }
-
-            // Upper bound: now + clock skew buffer
             let buffer = Duration::seconds(5);
-            let now = Utc::now();
-            if decision_time > now + buffer {
-                return HookOutput::block(&format!(
+            let now = ctx.now();
+
+            if let Some(lower) = lower_bound.filter(|lower| decision_time < *lower) {
+                HookOutput::block(&format!(
+                    "Decision timestamp ({}) is before the current review cycle ({}). \
+                     Decision must be posted by roz:roz during its execution.",
+                    decision_time.format("%Y-%m-%dT%H:%M:%SZ"),
+                    lower.format("%Y-%m-%dT%H:%M:%SZ")
+                ))
+            } else if decision_time > now + buffer {
+                HookOutput::block(&format!(
                     "Decision timestamp ({}) is in the future (now: {}). \
                      Decision must be posted by roz:roz during its execution.",
                     decision_time.format("%Y-%m-%dT%H:%M:%SZ"),
                     now.format("%Y-%m-%dT%H:%M:%SZ")
-                ));
+                ))
+            } else {
+                return check_reviewer_evidence(event, store, config, ctx, state);
             }
+        }
+    };
 
-            HookOutput::approve()
+    // The reviewer ran but exited without a usable decision. If it tried to
+    // decide against an unknown session during this cycle, from inside this
+    // session's project, blame the ID.
+    let bad_ids = lower_bound
+        .map(|since| unknown_ids_from_project(store, session_id, &event.common.cwd, since))
+        .unwrap_or_default();
+
+    let (outcome, output) = if bad_ids.is_empty() {
+        (AttemptOutcome::NoDecision, output)
+    } else {
+        let reason = output.reason.unwrap_or_default();
+        (
+            AttemptOutcome::BadSessionId,
+            HookOutput::block(&format!(
+                "{reason}\n\nroz decide was called with unknown session ID(s): {}. \
+                 The correct SESSION_ID is {session_id}.",
+                bad_ids.join(", ")
+            )),
+        )
+    };
+
+    // Leave `updated_at` alone: it doubles as the decision timestamp
+    if resolve_latest_pending_attempt(&mut state, &outcome) {
+        save_session(store, "subagent-stop", &state, ctx);
+    }
+
+    output
+}
+
+/// IDs of unknown sessions decided on since `since` from inside the project
+/// of the session in `cwd`.
+///
+/// The log is shared by every session, so time alone would blame other
+/// projects' reviewers. A decision belongs to the session whose directory
+/// most closely contains the one it was posted from: when another session
+/// active since `since` runs deeper (a nested project), it is that one's.
+fn unknown_ids_from_project(
+    store: &dyn MessageStore,
+    session_id: &str,
+    cwd: &Path,
+    since: DateTime<Utc>,
+) -> Vec<String> {
+    if cwd.as_os_str().is_empty() {
+        return Vec::new();
+    }
+    let records: Vec<_> = store
+        .unknown_decisions_since(since)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|r| Some((r.cwd?, r.session_id)))
+        .filter(|(dir, _)| dir.starts_with(cwd))
+        .collect();
+    if records.is_empty() {
+        return Vec::new();
+    }
+
+    let depth = cwd.components().count();
+    let nested: Vec<PathBuf> = store
+        .sessions_updated_since(since)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|id| id != session_id)
+        .filter_map(|id| store.get_session(&id).ok().flatten()?.cwd)
+        .filter(|dir| dir.starts_with(cwd) && dir.components().count() > depth)
+        .collect();
+    records
+        .into_iter()
+        .filter(|(dir, _)| !nested.iter().any(|other| dir.starts_with(other)))
+        .map(|(_, id)| id)
+        .collect()
+}
+
+/// Trace what the reviewer inspected and hold a COMPLETE decision to
+/// `[review.evidence]`.
+///
+/// Evidence comes from the subagent's own transcript. A COMPLETE decision
+/// that falls short, or that cannot be checked because the transcript is
+/// missing or unreadable while the policy is on, is undone (the review state
+/// from before it is restored, the decision itself kept in history as
+/// rejected) and the reviewer is told what it still has to do.
+fn check_reviewer_evidence(
+    event: &SubagentStopEvent,
+    store: &dyn MessageStore,
+    config: &Config,
+    ctx: &Context,
+    mut state: SessionState,
+) -> HookOutput {
+    let session_id = &event.common.session_id;
+    let cwd = &event.common.cwd;
+    let uses = match &event.agent_transcript_path {
+        None => Err("no agent_transcript_path in the hook input".to_string()),
+        Some(path) => read_tool_uses(path).map_err(|e| {
+            eprintln!(
+                "roz: warning: cannot read reviewer transcript {}: {e}",
+                path.display()
+            );
+            format!("cannot read {}: {e}", path.display())
+        }),
+    };
+
+    let evidence = uses
+        .as_deref()
+        .map(|uses| ReviewerEvidence::from_tool_uses(uses, cwd))
+        .unwrap_or_default();
+    let complete = matches!(state.review.decision, Decision::Complete { .. });
+    // Only the reviewer's own COMPLETE is held to the policy; `roz decide`
+    // left the state to restore if it falls short
+    let held = complete && state.review.before_complete.is_some();
+    let policy = &config.review.evidence;
+    let missing = match &uses {
+        Ok(_) if held => missing_evidence(policy, &evidence, session_id, cwd),
+        Err(_) if held && policy.is_enabled() => {
+            vec!["inspect the work again; roz could not read your transcript".to_string()]
         }
+        _ => Vec::new(),
+    };
+
+    add_trace_event(
+        &mut state,
+        TraceEvent {
+            id: ctx.generate_id(),
+            timestamp: ctx.now(),
+            event_type: EventType::ReviewerEvidence,
+            payload: json!({
+                "decision": if complete { "complete" } else { "issues" },
+                "ran_context": evidence.ran_context,
+                "ran_diff": evidence.ran_diff,
+                "files_read": evidence.files_read,
+                "searches": evidence.searches,
+                "tool_calls": uses.as_ref().map_or(0, Vec::len),
+                "transcript_error": uses.as_ref().err(),
+                "missing": missing,
+                "accepted": missing.is_empty(),
+            }),
+        },
+        config.trace.max_events,
+        ctx,
+    );
+
+    if missing.is_empty() {
+        state.review.before_complete = None;
+        save_session(store, "subagent-stop", &state, ctx);
+        return HookOutput::approve();
+    }
+
+    // Undo everything `roz decide` did to the review, but keep the rejected
+    // decision on record
+    let rejected = DecisionRecord {
+        decision: state.review.decision.clone(),
+        timestamp: ctx.now(),
+        decided_by: state.review.decided_by,
+        rejected: true,
+    };
+    if let Some(before) = state.review.before_complete.take() {
+        state.review = *before;
     }
+    state.review.push_decision_record(rejected);
+    save_session(store, "subagent-stop", &state, ctx);
+
+    let reason = match &uses {
+        Ok(_) => "without enough evidence that it inspected the work".to_string(),
+        Err(e) => format!("but its transcript could not be checked ({e})"),
+    };
+    let steps: Vec<String> = missing.iter().map(|m| format!("- {m}")).collect();
+    HookOutput::block(&format!(
+        "roz:roz posted COMPLETE {reason}, so the decision was not accepted. \
+         Before deciding again:\n\n{}\n\n\
+         Then run: roz decide {session_id} COMPLETE \"summary\"\n\
+              or: roz decide {session_id} ISSUES \"summary\" --message \"what to fix\"",
+        steps.join("\n")
+    ))
 }
 
 // ============================================================================
@@ -440,18 +1068,32 @@ pub fn handle_subagent_stop(input: &HookInput, store: &dyn MessageStore) -> Hook
 /// Handle the pre-tool-use hook.
 ///
 /// Checks if the tool matches a gate pattern and blocks if review is needed.
+/// Also records edits and commands for `[review.risk]`.
 pub fn handle_pre_tool_use(
-    input: &HookInput,
+    event: &PreToolUseEvent,
     config: &Config,
     store: &dyn MessageStore,
 ) -> PreToolUseOutput {
+    handle_pre_tool_use_with_context(event, config, store, &Context::system())
+}
+
+/// Handle the pre-tool-use hook with an explicit clock and randomness source.
+pub fn handle_pre_tool_use_with_context(
+    event: &PreToolUseEvent,
+    config: &Config,
+    store: &dyn MessageStore,
+    ctx: &Context,
+) -> PreToolUseOutput {
+    let session_id = &event.common.session_id;
+    track_risk(event, config, store, ctx);
+
     // Check if gates are enabled (non-empty tools array)
     if !config.review.gates.is_enabled() {
         return PreToolUseOutput::allow();
     }
 
     // Format tool key for matching
-    let tool_key = format_tool_key(input.tool_name.as_deref(), input.tool_input.as_ref());
+    let tool_key = format_tool_key(&event.tool_name, &event.tool_input);
 
     // Check if tool matches any gate pattern
     let Some(matched_pattern) = find_matching_pattern(&tool_key, &config.review.gates.tools) else {
@@ -459,41 +1101,47 @@ pub fn handle_pre_tool_use(
     };
 
     // Get or create session state
-    let mut state = match store.get_session(&input.session_id) {
+    let mut state = match store.get_session(session_id) {
         Ok(Some(s)) => s,
-        Ok(None) => SessionState::new(&input.session_id),
+        Ok(None) => SessionState::new_at(session_id, ctx.now()),
         Err(e) => {
-            eprintln!("roz: warning: storage error: {e}");
-            return PreToolUseOutput::allow(); // Fail open
+            let message = format!("storage error: {e}");
+            return match handle_error(
+                store,
+                config,
+                "pre-tool-use",
+                Some((&event.common).into()),
+                IncidentKind::StorageRead,
+                &message,
+                ctx,
+            ) {
+                Some(diagnostic) => PreToolUseOutput::deny(&diagnostic),
+                None => PreToolUseOutput::allow(), // Fail open
+            };
         }
     };
+    record_session_paths(&mut state, &event.common);
 
-    // Check circuit breaker - if tripped, allow through
-    if state.review.circuit_breaker_tripped {
-        trace_gate_allowed(
-            &mut state,
-            &tool_key,
-            "circuit_breaker",
-            config.trace.max_events,
-        );
-        let _ = store.put_session(&state);
+    // Check approval based on configured scope
+    if is_gate_approved(&state, &config.review.gates, ctx.now()) {
+        trace_gate_allowed(&mut state, &tool_key, "approved", config, ctx);
+        save_session(store, "pre-tool-use", &state, ctx);
         return PreToolUseOutput::allow();
     }
 
-    // Check approval based on configured scope
-    if is_gate_approved(&state, &config.review.gates) {
-        trace_gate_allowed(&mut state, &tool_key, "approved", config.trace.max_events);
-        let _ = store.put_session(&state);
-        return PreToolUseOutput::allow();
+    // Check the session and gate breakers - if tripped, apply the configured action
+    if let Some(output) = breaker_output(&mut state, config, &tool_key, &matched_pattern, ctx) {
+        save_session(store, "pre-tool-use", &state, ctx);
+        return output;
     }
 
     // Store full gate context for roz to review
-    let now = Utc::now();
+    let now = ctx.now();
     state.review.enabled = true;
     state.review.review_started_at = Some(now); // Mark review cycle start
     state.review.gate_trigger = Some(GateTrigger {
         tool_name: tool_key.clone(),
-        tool_input: TruncatedInput::from_value(input.tool_input.clone().unwrap_or(Value::Null)),
+        tool_input: TruncatedInput::from_value(event.tool_input.clone()),
         triggered_at: now,
         pattern_matched: matched_pattern.clone(),
     });
@@ -502,7 +1150,7 @@ pub fn handle_pre_tool_use(
     add_trace_event(
         &mut state,
         TraceEvent {
-            id: generate_id(),
+            id: ctx.generate_id(),
             timestamp: now,
             event_type: EventType::GateBlocked,
             payload: json!({
@@ -511,32 +1159,272 @@ pub fn handle_pre_tool_use(
             }),
         },
         config.trace.max_events,
+        ctx,
     );
 
+    // Count the denial; at the limit the pattern trips instead of denying
+    let tripped = circuit_breaker::record_gate_denial(
+        &mut state,
+        &config.circuit_breaker,
+        &matched_pattern,
+        &tool_key,
+        ctx,
+    );
+    let trip_output =
+        tripped.then(|| gate_trip_output(&mut state, config, &tool_key, &matched_pattern, ctx));
+
     state.updated_at = now;
 
+    // Without the saved gate trigger roz cannot review this action
     if let Err(e) = store.put_session(&state) {
-        eprintln!("roz: warning: failed to save state: {e}");
-        return PreToolUseOutput::allow(); // Fail open
+        let blocked = handle_error(
+            store,
+            config,
+            "pre-tool-use",
+            Some((&event.common).into()),
+            IncidentKind::StorageWrite,
+            &format!("failed to save state: {e}"),
+            ctx,
+        );
+        if blocked.is_none() {
+            return PreToolUseOutput::allow(); // Fail open
+        }
+    }
+
+    if let Some(output) = trip_output {
+        return output;
     }
 
     PreToolUseOutput::deny(&format!(
         "Review required before this action.\n\n\
          Spawn **roz:roz** to review this session:\n\n\
          ```\n\
-         SESSION_ID={}\n\n\
+         SESSION_ID={session_id}\n\n\
          ## Summary\n\
          [What you did and why]\n\n\
          ## Files Changed\n\
          [List of modified files]\n\
          ```\n\n\
-         Triggered by: `{}`",
-        input.session_id, tool_key
+         Triggered by: `{tool_key}`"
     ))
 }
 
-/// Check if gate is approved based on configured scope.
-fn is_gate_approved(state: &SessionState, gates: &GatesConfig) -> bool {
+/// Record file and command activity for `[review.risk]`, enabling review
+/// once a threshold is crossed. Never blocks the tool itself.
+fn track_risk(event: &PreToolUseEvent, config: &Config, store: &dyn MessageStore, ctx: &Context) {
+    let session_id = &event.common.session_id;
+    let thresholds = config.review.risk.for_cwd(&event.common.cwd);
+    if !thresholds.is_enabled() || config.review.mode == ReviewMode::Never {
+        return;
+    }
+
+    let tool_key = format_tool_key(&event.tool_name, &event.tool_input);
+    let edit = file_edit(event).filter(|_| thresholds.watches_files());
+    let command = find_matching_pattern(&tool_key, &thresholds.commands);
+    if edit.is_none() && command.is_none() {
+        return;
+    }
+
+    let mut state = match store.get_session(session_id) {
+        Ok(Some(s)) => s,
+        Ok(None) => SessionState::new_at(session_id, ctx.now()),
+        Err(e) => {
+            report_incident(
+                store,
+                "pre-tool-use",
+                IncidentKind::StorageRead,
+                Some(session_id),
+                &format!("storage error: {e}"),
+                ctx,
+            );
+            return;
+        }
+    };
+    record_session_paths(&mut state, &event.common);
+
+    let now = ctx.now();
+    let activity = &mut state.review.activity;
+    if let Some((path, lines)) = &edit {
+        activity.files.insert(path.clone());
+        activity.lines_changed += lines;
+    }
+
+    let crossed = activity
+        .risk_trigger
+        .is_none()
+        .then(|| {
+            crossed_threshold(
+                thresholds,
+                activity,
+                edit.as_ref(),
+                command.as_deref(),
+                &tool_key,
+            )
+        })
+        .flatten();
+    if let Some(threshold) = crossed {
+        activity.risk_trigger = Some(threshold.clone());
+
+        // Start a review cycle unless one is already waiting on the reviewer
+        state.review.enabled = true;
+        if matches!(state.review.decision, Decision::Complete { .. }) {
+            state.review.decision = Decision::Pending;
+            state.review.decided_by = None;
+            state.review.clear_claims();
+        }
+        state.review.review_started_at = Some(now);
+
+        add_trace_event(
+            &mut state,
+            TraceEvent {
+                id: ctx.generate_id(),
+                timestamp: now,
+                event_type: EventType::RiskThresholdCrossed,
+                payload: json!({
+                    "threshold": threshold,
+                    "tool": tool_key,
+                }),
+            },
+            config.trace.max_events,
+            ctx,
+        );
+    }
+
+    state.updated_at = now;
+    save_session(store, "pre-tool-use", &state, ctx);
+}
+
+/// The first threshold this tool call crosses, described for the agent.
+fn crossed_threshold(
+    thresholds: &RiskThresholds,
+    activity: &SessionActivity,
+    edit: Option<&(String, u64)>,
+    command: Option<&str>,
+    tool_key: &str,
+) -> Option<String> {
+    if let Some(pattern) = command {
+        return Some(format!("command `{tool_key}` matches `{pattern}`"));
+    }
+    if let Some((path, _)) = edit
+        && let Some(pattern) = thresholds
+            .sensitive_paths
+            .iter()
+            .find(|p| glob_match(p, path))
+    {
+        return Some(format!("sensitive path `{path}` matches `{pattern}`"));
+    }
+    if let Some(limit) = thresholds.files_changed
+        && activity.files.len() >= limit as usize
+    {
+        return Some(format!(
+            "{} files changed (threshold {limit})",
+            activity.files.len()
+        ));
+    }
+    if let Some(limit) = thresholds.lines_changed
+        && activity.lines_changed >= u64::from(limit)
+    {
+        return Some(format!(
+            "{} lines changed (threshold {limit})",
+            activity.lines_changed
+        ));
+    }
+    None
+}
+
+/// The file an edit tool changes (relative to the cwd when inside it) and
+/// how many lines it adds plus removes.
+fn file_edit(event: &PreToolUseEvent) -> Option<(String, u64)> {
+    let tool = event.tool_name.as_str();
+    if !EDIT_TOOLS.contains(&tool) {
+        return None;
+    }
+    let tool_input = &event.tool_input;
+    let path = tool_input
+        .get("file_path")
+        .or_else(|| tool_input.get("notebook_path"))
+        .and_then(Value::as_str)?;
+    let path = Path::new(path)
+        .strip_prefix(&event.common.cwd)
+        .map_or_else(|_| path.to_string(), |p| p.display().to_string());
+
+    let lines = |value: &Value, key: &str| -> u64 {
+        value
+            .get(key)
+            .and_then(Value::as_str)
+            .map_or(0, |text| text.lines().count() as u64)
+    };
+    let changed = match tool {
+        "Write" => lines(tool_input, "content"),
+        "NotebookEdit" => lines(tool_input, "new_source"),
+        "MultiEdit" => tool_input
+            .get("edits")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|e| lines(e, "old_string") + lines(e, "new_string"))
+            .sum(),
+        _ => lines(tool_input, "old_string") + lines(tool_input, "new_string"),
+    };
+    Some((path, changed))
+}
+
+/// Output for a gated tool while the session breaker or `pattern`'s gate
+/// breaker is tripped (`None` if neither is).
+fn breaker_output(
+    state: &mut SessionState,
+    config: &Config,
+    tool_key: &str,
+    pattern: &str,
+    ctx: &Context,
+) -> Option<PreToolUseOutput> {
+    if state.review.circuit_breaker_tripped {
+        return Some(match config.circuit_breaker.on_trip {
+            TripAction::Approve => {
+                trace_gate_allowed(state, tool_key, "circuit_breaker", config, ctx);
+                PreToolUseOutput::allow()
+            }
+            TripAction::Ask => PreToolUseOutput::ask(&tripped_message(state)),
+            TripAction::Block => PreToolUseOutput::deny(&tripped_message(state)),
+        });
+    }
+
+    // Repeated denials of this pattern without a review
+    circuit_breaker::gate_tripped(state, &config.circuit_breaker, pattern, ctx)
+        .then(|| gate_trip_output(state, config, tool_key, pattern, ctx))
+}
+
+/// Output while `pattern`'s gate breaker is tripped, per `on_gate_trip`.
+fn gate_trip_output(
+    state: &mut SessionState,
+    config: &Config,
+    tool_key: &str,
+    pattern: &str,
+    ctx: &Context,
+) -> PreToolUseOutput {
+    let session_id = &state.session_id;
+    let message = format!(
+        "roz gate breaker tripped for `{pattern}` after repeated denials without a review \
+         (session {session_id}).\n\
+         Spawn **roz:roz** with SESSION_ID={session_id}, or decide with: \
+         roz approve {session_id} --reason \"...\""
+    );
+    match config.circuit_breaker.on_gate_trip {
+        TripAction::Approve => {
+            trace_gate_allowed(state, tool_key, "gate_breaker", config, ctx);
+            PreToolUseOutput::allow()
+        }
+        TripAction::Ask => PreToolUseOutput::ask(&message),
+        TripAction::Block => PreToolUseOutput::deny(&message),
+    }
+}
+
+/// Check if gate is approved at `now` based on configured scope.
+pub(crate) fn is_gate_approved(
+    state: &SessionState,
+    gates: &GatesConfig,
+    now: DateTime<Utc>,
+) -> bool {
     // Must have a Complete decision
     if !matches!(state.review.decision, Decision::Complete { .. }) {
         return false;
@@ -550,7 +1438,7 @@ fn is_gate_approved(state: &SessionState, gates: &GatesConfig) -> bool {
     if let Some(ttl_secs) = gates.approval_ttl_seconds {
         let ttl_secs_i64 = i64::try_from(ttl_secs).unwrap_or(i64::MAX);
         let expiry = approved_at + Duration::seconds(ttl_secs_i64);
-        if Utc::now() > expiry {
+        if now > expiry {
             return false; // Approval expired
         }
     }
@@ -581,25 +1469,58 @@ fn is_gate_approved(state: &SessionState, gates: &GatesConfig) -> bool {
     }
 }
 
+/// Keep the agent's final message as its claim of what was done, for the
+/// reviewer to compare against the work (not once approved).
+fn record_claimed_summary(state: &mut SessionState, event: &StopEvent, now: DateTime<Utc>) {
+    if matches!(state.review.decision, Decision::Complete { .. }) {
+        return;
+    }
+    if let Some(message) = event
+        .last_assistant_message
+        .as_deref()
+        .filter(|m| !m.trim().is_empty())
+    {
+        state.review.record_claim(&truncate_prompt(message), now);
+    }
+}
+
+/// Remember the session's working directory and transcript so `roz status`
+/// and `roz context --transcript` can find them.
+fn record_session_paths(state: &mut SessionState, common: &HookCommon) {
+    if !common.cwd.as_os_str().is_empty() {
+        state.cwd = Some(common.cwd.clone());
+    }
+    if let Some(path) = &common.transcript_path {
+        state.transcript_path = Some(path.clone());
+    }
+}
+
 /// Trace when gate allows (for debugging visibility).
-fn trace_gate_allowed(state: &mut SessionState, tool: &str, reason: &str, max_events: usize) {
+fn trace_gate_allowed(
+    state: &mut SessionState,
+    tool: &str,
+    reason: &str,
+    config: &Config,
+    ctx: &Context,
+) {
     add_trace_event(
         state,
         TraceEvent {
-            id: generate_id(),
-            timestamp: Utc::now(),
+            id: ctx.generate_id(),
+            timestamp: ctx.now(),
             event_type: EventType::GateAllowed,
             payload: json!({
                 "tool": tool,
                 "reason": reason,
             }),
         },
-        max_events,
+        config.trace.max_events,
+        ctx,
     );
 }
 
 /// Add trace event with size limiting (drops oldest events if over limit).
-fn add_trace_event(state: &mut SessionState, event: TraceEvent, max_events: usize) {
+fn add_trace_event(state: &mut SessionState, event: TraceEvent, max_events: usize, ctx: &Context) {
     state.trace.push(event);
 
     // Enforce limit by dropping oldest events (but keep first 10 for context)
@@ -614,8 +1535,8 @@ fn add_trace_event(state: &mut SessionState, event: TraceEvent, max_events: usiz
         let mut new_trace = Vec::with_capacity(max_events);
         new_trace.extend(state.trace.drain(..keep_start));
         new_trace.push(TraceEvent {
-            id: generate_id(),
-            timestamp: Utc::now(),
+            id: ctx.generate_id(),
+            timestamp: ctx.now(),
             event_type: EventType::TraceCompacted,
             payload: json!({
                 "dropped_events": dropped,
@@ -642,19 +1563,15 @@ fn add_trace_event(state: &mut SessionState, event: TraceEvent, max_events: usiz
 /// Format a tool key for pattern matching.
 ///
 /// For Bash tools, normalizes the command and prefixes with `Bash:`.
-fn format_tool_key(tool_name: Option<&str>, tool_input: Option<&Value>) -> String {
-    let name = tool_name.unwrap_or("unknown");
-
-    if name == "Bash" {
-        if let Some(input) = tool_input {
-            if let Some(cmd) = input.get("command").and_then(|c| c.as_str()) {
-                let normalized = normalize_bash_command(cmd);
-                return format!("Bash:{normalized}");
-            }
+fn format_tool_key(tool_name: &str, tool_input: &Value) -> String {
+    if tool_name == "Bash" {
+        if let Some(cmd) = tool_input.get("command").and_then(|c| c.as_str()) {
+            let normalized = normalize_bash_command(cmd);
+            return format!("Bash:{normalized}");
         }
     }
 
-    name.to_string()
+    tool_name.to_string()
 }
 
 /// Normalize a Bash command for matching.
@@ -709,11 +1626,9 @@ fn find_last_unquoted_pipe(cmd: &str) -> Option<usize> {
             '"' if !in_single_quote && prev_char != Some('\\') => {
                 in_double_quote = !in_double_quote;
             }
-            '|' if !in_single_quote && !in_double_quote => {
-                // Make sure it's not || (logical or)
-                if prev_char != Some('|') {
-                    last_pipe = Some(i);
-                }
+            // Make sure it's not || (logical or)
+            '|' if !in_single_quote && !in_double_quote && prev_char != Some('|') => {
+                last_pipe = Some(i);
             }
             _ => {}
         }
@@ -842,22 +1757,47 @@ fn glob_match(pattern: &str, tool_key: &str) -> bool {
 }
 
 // ============================================================================
-// Review Attempts (for future A/B testing)
+// Review Attempts (for A/B testing)
 // ============================================================================
 
 /// Record a review attempt when blocking.
-fn record_review_attempt(state: &mut SessionState, template_id: &str) {
+fn record_review_attempt(state: &mut SessionState, template_id: &str, now: DateTime<Utc>) {
     state.review.attempts.push(ReviewAttempt {
         template_id: template_id.to_string(),
-        timestamp: Utc::now(),
+        timestamp: now,
         outcome: AttemptOutcome::Pending,
     });
 }
 
+/// Resolve every still-pending review attempt with the given outcome.
+fn resolve_pending_attempts(state: &mut SessionState, outcome: &AttemptOutcome) {
+    for attempt in &mut state.review.attempts {
+        if attempt.outcome == AttemptOutcome::Pending {
+            attempt.outcome = outcome.clone();
+        }
+    }
+}
+
+/// Resolve the most recent attempt if it is still pending.
+///
+/// Returns `true` if an attempt was updated.
+fn resolve_latest_pending_attempt(state: &mut SessionState, outcome: &AttemptOutcome) -> bool {
+    match state.review.attempts.last_mut() {
+        Some(attempt) if attempt.outcome == AttemptOutcome::Pending => {
+            attempt.outcome = outcome.clone();
+            true
+        }
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::storage::MemoryBackend;
+    use crate::config::SafetyMode;
+    use crate::core::context::{Clock, FixedClock, SeededRng};
+    use crate::hooks::HookInput;
+    use crate::storage::{Incident, MemoryBackend};
     use std::path::PathBuf;
 
     // User prompt hook tests
@@ -883,9 +1823,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_user_prompt(&input, &store);
+        let output = handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
 
         assert!(output.decision.is_none());
 
@@ -916,9 +1860,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_user_prompt(&input, &store);
+        let output = handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
 
         assert!(output.decision.is_none());
 
@@ -950,8 +1898,12 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
-        handle_user_prompt(&input1, &store);
+        handle_user_prompt(&UserPromptSubmitEvent::from_input(&input1), &store);
 
         // Second prompt without #roz
         let input2 = HookInput {
@@ -972,8 +1924,12 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
-        handle_user_prompt(&input2, &store);
+        handle_user_prompt(&UserPromptSubmitEvent::from_input(&input2), &store);
 
         let state = store.get_session("test-789").unwrap().unwrap();
         assert!(state.review.enabled);
@@ -1004,9 +1960,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_stop(&input, &store);
+        let output = handle_stop(&StopEvent::from_input(&input), &store);
         assert!(output.decision.is_none());
     }
 
@@ -1038,9 +1998,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_stop(&input, &store);
+        let output = handle_stop(&StopEvent::from_input(&input), &store);
         assert!(matches!(
             output.decision,
             Some(crate::hooks::HookDecision::Block)
@@ -1083,9 +2047,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_stop(&input, &store);
+        let output = handle_stop(&StopEvent::from_input(&input), &store);
         assert!(output.decision.is_none());
     }
 
@@ -1120,9 +2088,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_stop(&input, &store);
+        let output = handle_stop(&StopEvent::from_input(&input), &store);
         assert!(matches!(
             output.decision,
             Some(crate::hooks::HookDecision::Block)
@@ -1158,9 +2130,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: Some(true),
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        handle_stop(&input, &store);
+        handle_stop(&StopEvent::from_input(&input), &store);
 
         // Verify stop_hook_active is recorded in the trace event
         let state = store.get_session("test-trace-active").unwrap().unwrap();
@@ -1199,9 +2175,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        handle_stop(&input, &store);
+        handle_stop(&StopEvent::from_input(&input), &store);
 
         let state = store.get_session("test-trace-inactive").unwrap().unwrap();
         let stop_event = state
@@ -1235,9 +2215,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_subagent_stop(&input, &store);
+        let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
         assert!(output.decision.is_none());
     }
 
@@ -1262,9 +2246,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_subagent_stop(&input, &store);
+        let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
         assert!(output.decision.is_none());
     }
 
@@ -1290,10 +2278,14 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
         // Fail open when session not found
-        let output = handle_subagent_stop(&input, &store);
+        let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
         assert!(output.decision.is_none());
     }
 
@@ -1326,9 +2318,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_subagent_stop(&input, &store);
+        let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
         assert!(matches!(
             output.decision,
             Some(crate::hooks::HookDecision::Block)
@@ -1370,9 +2366,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_subagent_stop(&input, &store);
+        let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
         assert!(output.decision.is_none());
     }
 
@@ -1417,9 +2417,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_subagent_stop(&input, &store);
+        let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
         assert!(matches!(
             output.decision,
             Some(crate::hooks::HookDecision::Block)
@@ -1476,9 +2480,13 @@ mod tests {
             last_assistant_message: Some("Review complete.".to_string()),
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_subagent_stop(&input, &store);
+        let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
         assert!(output.decision.is_none());
     }
 
@@ -1518,9 +2526,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_subagent_stop(&input, &store);
+        let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
         assert!(matches!(
             output.decision,
             Some(crate::hooks::HookDecision::Block)
@@ -1567,9 +2579,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_subagent_stop(&input, &store);
+        let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
         assert!(matches!(
             output.decision,
             Some(crate::hooks::HookDecision::Block)
@@ -1612,10 +2628,14 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
         // Without a lower bound, approves if decision exists and isn't in the future
-        let output = handle_subagent_stop(&input, &store);
+        let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
         assert!(output.decision.is_none());
     }
 
@@ -1642,15 +2662,20 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_session_start(&input, &store);
+        let output = handle_session_start(&SessionStartEvent::from_input(&input), &store);
         assert!(output.decision.is_none());
 
         let state = store.get_session("new-session").unwrap().unwrap();
         assert_eq!(state.session_id, "new-session");
         assert!(!state.trace.is_empty());
         assert_eq!(state.trace[0].event_type, EventType::SessionStart);
+        assert_eq!(state.cwd.as_deref(), Some(std::path::Path::new("/tmp")));
     }
 
     #[test]
@@ -1681,9 +2706,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_session_start(&input, &store);
+        let output = handle_session_start(&SessionStartEvent::from_input(&input), &store);
         assert!(output.decision.is_none());
 
         // Existing state should be preserved
@@ -1703,6 +2732,7 @@ mod tests {
         state.review.enabled = true;
         state.review.decision = Decision::Pending;
         state.review.block_count = 3; // At max_blocks
+        state.review.not_spawned_blocks = 3;
         store.put_session(&state).unwrap();
 
         let input = HookInput {
@@ -1723,10 +2753,14 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
         let config = Config::default(); // max_blocks = 3
-        let output = handle_stop_with_config(&input, &store, &config);
+        let output = handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
 
         // Should approve because circuit breaker tripped
         assert!(output.decision.is_none());
@@ -1766,10 +2800,14 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
         let config = Config::default();
-        let output = handle_stop_with_config(&input, &store, &config);
+        let output = handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
 
         assert!(output.decision.is_none());
     }
@@ -1786,6 +2824,7 @@ mod tests {
             message_to_agent: Some("Fix tests".to_string()),
         };
         state.review.block_count = 2; // One below max, will hit on increment
+        state.review.issues_blocks = 2;
         store.put_session(&state).unwrap();
 
         let mut config = Config::default();
@@ -1809,9 +2848,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_stop_with_config(&input, &store, &config);
+        let output = handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
 
         // Should approve because circuit breaker tripped after increment
         assert!(output.decision.is_none());
@@ -1855,9 +2898,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_pre_tool_use(&input, &config, &store);
+        let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
         assert_eq!(
             output.hook_specific_output.permission_decision,
             crate::hooks::PermissionDecision::Allow
@@ -1887,9 +2934,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_pre_tool_use(&input, &config, &store);
+        let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
         assert_eq!(
             output.hook_specific_output.permission_decision,
             crate::hooks::PermissionDecision::Allow
@@ -1919,9 +2970,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_pre_tool_use(&input, &config, &store);
+        let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
         assert_eq!(
             output.hook_specific_output.permission_decision,
             crate::hooks::PermissionDecision::Deny
@@ -1972,9 +3027,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_pre_tool_use(&input, &config, &store);
+        let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
         assert_eq!(
             output.hook_specific_output.permission_decision,
             crate::hooks::PermissionDecision::Allow
@@ -2013,9 +3072,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_pre_tool_use(&input, &config, &store);
+        let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
         assert_eq!(
             output.hook_specific_output.permission_decision,
             crate::hooks::PermissionDecision::Allow
@@ -2045,9 +3108,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_pre_tool_use(&input, &config, &store);
+        let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
         assert_eq!(
             output.hook_specific_output.permission_decision,
             crate::hooks::PermissionDecision::Deny
@@ -2078,9 +3145,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_pre_tool_use(&input, &config, &store);
+        let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
         assert_eq!(
             output.hook_specific_output.permission_decision,
             crate::hooks::PermissionDecision::Deny
@@ -2214,7 +3285,7 @@ mod tests {
             approval_ttl_seconds: None,
         };
 
-        assert!(is_gate_approved(&state, &gates));
+        assert!(is_gate_approved(&state, &gates, Utc::now()));
     }
 
     #[test]
@@ -2234,7 +3305,7 @@ mod tests {
             approval_ttl_seconds: None,
         };
 
-        assert!(is_gate_approved(&state, &gates));
+        assert!(is_gate_approved(&state, &gates, Utc::now()));
     }
 
     #[test]
@@ -2254,7 +3325,7 @@ mod tests {
             approval_ttl_seconds: None,
         };
 
-        assert!(!is_gate_approved(&state, &gates));
+        assert!(!is_gate_approved(&state, &gates, Utc::now()));
     }
 
     #[test]
@@ -2273,7 +3344,7 @@ mod tests {
         };
 
         // Tool scope always returns false (requires fresh review)
-        assert!(!is_gate_approved(&state, &gates));
+        assert!(!is_gate_approved(&state, &gates, Utc::now()));
     }
 
     #[test]
@@ -2292,7 +3363,7 @@ mod tests {
             approval_ttl_seconds: Some(3600), // 1 hour TTL
         };
 
-        assert!(!is_gate_approved(&state, &gates));
+        assert!(!is_gate_approved(&state, &gates, Utc::now()));
     }
 
     #[test]
@@ -2311,7 +3382,7 @@ mod tests {
             approval_ttl_seconds: Some(3600), // 1 hour TTL
         };
 
-        assert!(is_gate_approved(&state, &gates));
+        assert!(is_gate_approved(&state, &gates, Utc::now()));
     }
 
     // ========================================================================
@@ -2334,6 +3405,7 @@ mod tests {
                     payload: json!({"index": i}),
                 },
                 max_events,
+                &Context::system(),
             );
         }
 
@@ -2363,6 +3435,7 @@ mod tests {
                     payload: json!({"index": i}),
                 },
                 max_events,
+                &Context::system(),
             );
         }
 
@@ -2392,6 +3465,7 @@ mod tests {
                     payload: json!({"index": i}),
                 },
                 max_events,
+                &Context::system(),
             );
         }
 
@@ -2425,6 +3499,7 @@ mod tests {
                     payload: json!({"index": i}),
                 },
                 max_events,
+                &Context::system(),
             );
         }
 
@@ -2449,6 +3524,7 @@ mod tests {
                     payload: json!({"index": i}),
                 },
                 max_events,
+                &Context::system(),
             );
         }
 
@@ -2473,6 +3549,7 @@ mod tests {
                     payload: json!({"index": i}),
                 },
                 max_events,
+                &Context::system(),
             );
         }
 
@@ -2505,6 +3582,7 @@ mod tests {
                     payload: json!({"index": i}),
                 },
                 max_events,
+                &Context::system(),
             );
         }
 
@@ -2528,6 +3606,7 @@ mod tests {
                     payload: json!({"index": i}),
                 },
                 max_events,
+                &Context::system(),
             );
         }
 
@@ -2569,9 +3648,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        handle_user_prompt_with_config(&input, &store, &config);
+        handle_user_prompt_with_config(&UserPromptSubmitEvent::from_input(&input), &store, &config);
 
         let state = store.get_session("test-always-mode").unwrap().unwrap();
         assert!(state.review.enabled);
@@ -2604,9 +3687,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        handle_user_prompt_with_config(&input, &store, &config);
+        handle_user_prompt_with_config(&UserPromptSubmitEvent::from_input(&input), &store, &config);
 
         let state = store.get_session("test-never-mode").unwrap().unwrap();
         assert!(!state.review.enabled);
@@ -2640,9 +3727,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        handle_user_prompt_with_config(&input, &store, &config);
+        handle_user_prompt_with_config(&UserPromptSubmitEvent::from_input(&input), &store, &config);
         let state = store.get_session("test-prompt-mode-1").unwrap().unwrap();
         assert!(!state.review.enabled);
 
@@ -2665,9 +3756,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        handle_user_prompt_with_config(&input, &store, &config);
+        handle_user_prompt_with_config(&UserPromptSubmitEvent::from_input(&input), &store, &config);
         let state = store.get_session("test-prompt-mode-2").unwrap().unwrap();
         assert!(state.review.enabled);
     }
@@ -2790,8 +3885,12 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
-        handle_session_start(&input_start, &store);
+        handle_session_start(&SessionStartEvent::from_input(&input_start), &store);
 
         // Now send session-end
         let input = HookInput {
@@ -2812,9 +3911,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: Some("logout".to_string()),
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_session_end(&input, &store);
+        let output = handle_session_end(&SessionEndEvent::from_input(&input), &store);
         assert!(output.decision.is_none(), "session-end always approves");
 
         let state = store.get_session("end-test-1").unwrap().unwrap();
@@ -2849,9 +3952,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: Some("clear".to_string()),
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_session_end(&input, &store);
+        let output = handle_session_end(&SessionEndEvent::from_input(&input), &store);
         assert!(output.decision.is_none(), "fail-open for unknown session");
     }
 
@@ -2878,8 +3985,12 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
-        handle_session_start(&input_start, &store);
+        handle_session_start(&SessionStartEvent::from_input(&input_start), &store);
 
         // session-end with no reason
         let input = HookInput {
@@ -2900,9 +4011,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_session_end(&input, &store);
+        let output = handle_session_end(&SessionEndEvent::from_input(&input), &store);
         assert!(output.decision.is_none());
 
         let state = store.get_session("end-test-reason").unwrap().unwrap();
@@ -2937,8 +4052,12 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
-        handle_session_start(&input_start, &store);
+        handle_session_start(&SessionStartEvent::from_input(&input_start), &store);
 
         let input = HookInput {
             session_id: "end-test-cwd".to_string(),
@@ -2958,9 +4077,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: Some("prompt_input_exit".to_string()),
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        handle_session_end(&input, &store);
+        handle_session_end(&SessionEndEvent::from_input(&input), &store);
 
         let state = store.get_session("end-test-cwd").unwrap().unwrap();
         let end_event = state
@@ -3006,8 +4129,12 @@ mod tests {
                 last_assistant_message: None,
                 stop_hook_active: None,
                 reason: None,
+                trigger: None,
+                custom_instructions: None,
+                message: None,
+                notification_type: None,
             };
-            handle_session_start(&input_start, &store);
+            handle_session_start(&SessionStartEvent::from_input(&input_start), &store);
 
             let input = HookInput {
                 session_id: sid.clone(),
@@ -3027,9 +4154,13 @@ mod tests {
                 last_assistant_message: None,
                 stop_hook_active: None,
                 reason: Some(reason.to_string()),
+                trigger: None,
+                custom_instructions: None,
+                message: None,
+                notification_type: None,
             };
 
-            let output = handle_session_end(&input, &store);
+            let output = handle_session_end(&SessionEndEvent::from_input(&input), &store);
             assert!(output.decision.is_none(), "session-end always approves");
 
             let state = store.get_session(&sid).unwrap().unwrap();
@@ -3068,8 +4199,12 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
-        handle_session_start(&input_start, &store);
+        handle_session_start(&SessionStartEvent::from_input(&input_start), &store);
 
         // Add a user prompt event
         let input_prompt = HookInput {
@@ -3090,8 +4225,12 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
-        handle_user_prompt(&input_prompt, &store);
+        handle_user_prompt(&UserPromptSubmitEvent::from_input(&input_prompt), &store);
 
         let state_before = store.get_session("end-test-trace").unwrap().unwrap();
         let trace_count_before = state_before.trace.len();
@@ -3115,8 +4254,12 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: Some("logout".to_string()),
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
-        handle_session_end(&input, &store);
+        handle_session_end(&SessionEndEvent::from_input(&input), &store);
 
         let state = store.get_session("end-test-trace").unwrap().unwrap();
         // Should have all previous events plus SessionEnd
@@ -3151,10 +4294,14 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: Some("other".to_string()),
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
         // No session exists - should fail open
-        let output = handle_session_end(&input, &store);
+        let output = handle_session_end(&SessionEndEvent::from_input(&input), &store);
         assert!(output.decision.is_none(), "fail-open on missing session");
     }
 
@@ -3168,6 +4315,7 @@ mod tests {
         let mut state = SessionState::new("sha-accel");
         state.review.enabled = true;
         state.review.block_count = 1;
+        state.review.not_spawned_blocks = 1;
         store.put_session(&state).unwrap();
 
         // Config with max_blocks=3; stop_hook_active=true → effective=2
@@ -3176,6 +4324,7 @@ mod tests {
             circuit_breaker: crate::config::CircuitBreakerConfig {
                 max_blocks: 3,
                 cooldown_seconds: 300,
+                ..crate::config::CircuitBreakerConfig::default()
             },
             ..Config::default()
         };
@@ -3198,9 +4347,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: Some(true),
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_stop_with_config(&input, &store, &config);
+        let output = handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
 
         // Should approve because circuit breaker tripped early
         assert!(output.decision.is_none(), "expected approve (CB tripped)");
@@ -3217,12 +4370,14 @@ mod tests {
         let mut state = SessionState::new("sha-normal");
         state.review.enabled = true;
         state.review.block_count = 1;
+        state.review.not_spawned_blocks = 1;
         store.put_session(&state).unwrap();
 
         let config = Config {
             circuit_breaker: crate::config::CircuitBreakerConfig {
                 max_blocks: 3,
                 cooldown_seconds: 300,
+                ..crate::config::CircuitBreakerConfig::default()
             },
             ..Config::default()
         };
@@ -3245,9 +4400,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: Some(false),
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_stop_with_config(&input, &store, &config);
+        let output = handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
 
         // Should block because effective_max_blocks=3, block_count=2 < 3
         assert!(
@@ -3268,12 +4427,14 @@ mod tests {
         let mut state = SessionState::new("sha-none");
         state.review.enabled = true;
         state.review.block_count = 1;
+        state.review.not_spawned_blocks = 1;
         store.put_session(&state).unwrap();
 
         let config = Config {
             circuit_breaker: crate::config::CircuitBreakerConfig {
                 max_blocks: 3,
                 cooldown_seconds: 300,
+                ..crate::config::CircuitBreakerConfig::default()
             },
             ..Config::default()
         };
@@ -3296,9 +4457,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_stop_with_config(&input, &store, &config);
+        let output = handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
 
         // Should block same as active=false
         assert!(
@@ -3320,12 +4485,14 @@ mod tests {
         let mut state = SessionState::new("sha-floor");
         state.review.enabled = true;
         state.review.block_count = 0;
+        state.review.not_spawned_blocks = 0;
         store.put_session(&state).unwrap();
 
         let config = Config {
             circuit_breaker: crate::config::CircuitBreakerConfig {
                 max_blocks: 1,
                 cooldown_seconds: 300,
+                ..crate::config::CircuitBreakerConfig::default()
             },
             ..Config::default()
         };
@@ -3348,9 +4515,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: Some(true),
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_stop_with_config(&input, &store, &config);
+        let output = handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
 
         // Should approve because circuit breaker trips (floor of 1)
         assert!(
@@ -3374,6 +4545,7 @@ mod tests {
             circuit_breaker: crate::config::CircuitBreakerConfig {
                 max_blocks: 5,
                 cooldown_seconds: 300,
+                ..crate::config::CircuitBreakerConfig::default()
             },
             ..Config::default()
         };
@@ -3396,9 +4568,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: Some(true),
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        handle_stop_with_config(&input, &store, &config);
+        handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
 
         let state = store.get_session("sha-trace").unwrap().unwrap();
         let stop_event = state
@@ -3418,6 +4594,7 @@ mod tests {
         let mut state = SessionState::new("sha-issues");
         state.review.enabled = true;
         state.review.block_count = 1;
+        state.review.issues_blocks = 1;
         state.review.decision = Decision::Issues {
             summary: "Fix tests".to_string(),
             message_to_agent: Some("Add more tests".to_string()),
@@ -3428,6 +4605,7 @@ mod tests {
             circuit_breaker: crate::config::CircuitBreakerConfig {
                 max_blocks: 3,
                 cooldown_seconds: 300,
+                ..crate::config::CircuitBreakerConfig::default()
             },
             ..Config::default()
         };
@@ -3450,9 +4628,13 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: Some(true),
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         };
 
-        let output = handle_stop_with_config(&input, &store, &config);
+        let output = handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
 
         // effective_max_blocks=2, after increment block_count=2 >= 2 → trips
         assert!(
@@ -3463,4 +4645,1296 @@ mod tests {
         let state = store.get_session("sha-issues").unwrap().unwrap();
         assert!(state.review.circuit_breaker_tripped);
     }
+
+    // ========================================================================
+    // Incident tests
+    // ========================================================================
+
+    /// Store whose session reads and writes always fail; incidents still work.
+    struct BrokenStore {
+        inner: MemoryBackend,
+        fail_reads: bool,
+    }
+
+    impl BrokenStore {
+        fn new(fail_reads: bool) -> Self {
+            Self {
+                inner: MemoryBackend::new(),
+                fail_reads,
+            }
+        }
+
+        fn incidents(&self) -> Vec<Incident> {
+            self.inner
+                .incidents_since(chrono::DateTime::UNIX_EPOCH)
+                .unwrap()
+        }
+    }
+
+    impl MessageStore for BrokenStore {
+        fn get_session(&self, session_id: &str) -> crate::error::Result<Option<SessionState>> {
+            if self.fail_reads {
+                Err(std::io::Error::other("disk on fire").into())
+            } else {
+                self.inner.get_session(session_id)
+            }
+        }
+
+        fn put_session(&self, _state: &SessionState) -> crate::error::Result<()> {
+            Err(std::io::Error::other("disk on fire").into())
+        }
+
+        fn list_sessions(
+            &self,
+            limit: usize,
+        ) -> crate::error::Result<Vec<crate::storage::SessionSummary>> {
+            self.inner.list_sessions(limit)
+        }
+
+        fn sessions_updated_since(
+            &self,
+            since: chrono::DateTime<Utc>,
+        ) -> crate::error::Result<Vec<String>> {
+            self.inner.sessions_updated_since(since)
+        }
+
+        fn delete_session(&self, session_id: &str) -> crate::error::Result<()> {
+            self.inner.delete_session(session_id)
+        }
+
+        fn record_unknown_decision(
+            &self,
+            record: &crate::storage::UnknownDecision,
+        ) -> crate::error::Result<()> {
+            self.inner.record_unknown_decision(record)
+        }
+
+        fn unknown_decisions_since(
+            &self,
+            since: chrono::DateTime<Utc>,
+        ) -> crate::error::Result<Vec<crate::storage::UnknownDecision>> {
+            self.inner.unknown_decisions_since(since)
+        }
+
+        fn record_incident(&self, incident: &Incident) -> crate::error::Result<()> {
+            self.inner.record_incident(incident)
+        }
+
+        fn incidents_since(
+            &self,
+            since: chrono::DateTime<Utc>,
+        ) -> crate::error::Result<Vec<Incident>> {
+            self.inner.incidents_since(since)
+        }
+    }
+
+    fn incident_input(session_id: &str) -> HookInput {
+        serde_json::from_value(json!({
+            "session_id": session_id,
+            "cwd": "/tmp",
+            "prompt": "#roz test",
+            "tool_name": "Bash",
+            "tool_input": {"command": "gh pr merge 1"},
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn stop_storage_error_records_incident_and_fails_open() {
+        let store = BrokenStore::new(true);
+        let input = incident_input("broken-stop");
+
+        let output =
+            handle_stop_with_config(&StopEvent::from_input(&input), &store, &Config::default());
+        assert!(output.decision.is_none());
+
+        let incidents = store.incidents();
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(incidents[0].hook, "stop");
+        assert_eq!(incidents[0].kind, IncidentKind::StorageRead);
+        assert_eq!(incidents[0].session_id.as_deref(), Some("broken-stop"));
+        assert!(incidents[0].message.contains("disk on fire"));
+    }
+
+    #[test]
+    fn stop_storage_error_fail_closed_blocks() {
+        let store = BrokenStore::new(true);
+        let input = incident_input("broken-stop-closed");
+        let mut config = Config::default();
+        config.safety.mode = SafetyMode::FailClosed;
+
+        let output = handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
+        assert!(matches!(
+            output.decision,
+            Some(crate::hooks::HookDecision::Block)
+        ));
+        assert!(output.reason.unwrap().contains("fail_closed"));
+    }
+
+    #[test]
+    fn pre_tool_use_storage_error_fail_closed_denies() {
+        let store = BrokenStore::new(true);
+        let input = incident_input("broken-gate");
+        let mut config = make_gate_config();
+
+        let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
+        assert_eq!(
+            output.hook_specific_output.permission_decision,
+            crate::hooks::PermissionDecision::Allow
+        );
+
+        config.safety.mode = SafetyMode::FailClosed;
+        let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
+        assert_eq!(
+            output.hook_specific_output.permission_decision,
+            crate::hooks::PermissionDecision::Deny
+        );
+        assert_eq!(store.incidents().len(), 2);
+    }
+
+    #[test]
+    fn user_prompt_save_failure_records_incident() {
+        let store = BrokenStore::new(false);
+        let input = incident_input("broken-save");
+
+        let output = handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
+        assert!(output.decision.is_none());
+
+        let incidents = store.incidents();
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(incidents[0].hook, "user-prompt");
+        assert_eq!(incidents[0].kind, IncidentKind::StorageWrite);
+    }
+
+    #[test]
+    fn subagent_stop_unknown_session_records_incident() {
+        let store = MemoryBackend::new();
+        let mut input = incident_input("ghost");
+        input.agent_type = Some("roz:roz".to_string());
+
+        handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
+
+        let incidents = store.incidents_since(chrono::DateTime::UNIX_EPOCH).unwrap();
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(incidents[0].kind, IncidentKind::SessionNotFound);
+    }
+
+    #[test]
+    fn stop_save_failure_fail_closed_caps_blocks() {
+        let store = BrokenStore::new(false);
+        let mut state = SessionState::new("broken-block");
+        state.review.enabled = true;
+        store.inner.put_session(&state).unwrap();
+
+        let input = incident_input("broken-block");
+        let mut config = Config::default();
+
+        // Fail open: an unsaved block would never trip the breaker, so approve
+        let output = handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
+        assert!(output.decision.is_none());
+
+        // Fail closed: keep blocking with the review message, up to max_blocks
+        config.safety.mode = SafetyMode::FailClosed;
+        for _ in 0..config.circuit_breaker.max_blocks {
+            let output = handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
+            assert!(matches!(
+                output.decision,
+                Some(crate::hooks::HookDecision::Block)
+            ));
+        }
+        let output = handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
+        assert!(output.decision.is_none(), "fail-closed loop must be capped");
+    }
+
+    #[test]
+    fn user_prompt_save_failure_fails_open_even_when_fail_closed() {
+        let store = BrokenStore::new(false);
+        let input = incident_input("broken-prompt");
+        let mut config = Config::default();
+        config.safety.mode = SafetyMode::FailClosed;
+
+        // Blocking would only discard the user's prompt
+        let output = handle_user_prompt_with_config(
+            &UserPromptSubmitEvent::from_input(&input),
+            &store,
+            &config,
+        );
+        assert!(output.decision.is_none());
+        assert!(!store.incidents()[0].blocked);
+    }
+
+    #[test]
+    fn subagent_stop_unknown_session_fail_closed_blocks() {
+        let store = MemoryBackend::new();
+        let mut input = incident_input("ghost-closed");
+        input.agent_type = Some("roz:roz".to_string());
+        let mut config = Config::default();
+        config.safety.mode = SafetyMode::FailClosed;
+
+        let output = handle_subagent_stop_with_config(
+            &SubagentStopEvent::from_input(&input),
+            &store,
+            &config,
+        );
+        assert!(matches!(
+            output.decision,
+            Some(crate::hooks::HookDecision::Block)
+        ));
+    }
+
+    // ========================================================================
+    // Per-cause circuit breaker tests
+    // ========================================================================
+
+    fn issues_state(session_id: &str, issues_blocks: u32) -> SessionState {
+        let mut state = SessionState::new(session_id);
+        state.review.enabled = true;
+        state.review.issues_blocks = issues_blocks;
+        state.review.block_count = issues_blocks;
+        state.review.decision = Decision::Issues {
+            summary: "Found bugs".to_string(),
+            message_to_agent: None,
+        };
+        state
+    }
+
+    #[test]
+    fn stop_issues_use_their_own_limit() {
+        let store = MemoryBackend::new();
+        store.put_session(&issues_state("issues-limit", 3)).unwrap();
+
+        let mut config = Config::default();
+        config.circuit_breaker.max_issues_blocks = Some(5);
+
+        // Past max_blocks, but issues get 5
+        let output = handle_stop_with_config(
+            &StopEvent::from_input(&incident_input("issues-limit")),
+            &store,
+            &config,
+        );
+        assert!(matches!(
+            output.decision,
+            Some(crate::hooks::HookDecision::Block)
+        ));
+
+        let output = handle_stop_with_config(
+            &StopEvent::from_input(&incident_input("issues-limit")),
+            &store,
+            &config,
+        );
+        assert!(output.decision.is_none());
+
+        let state = store.get_session("issues-limit").unwrap().unwrap();
+        assert_eq!(
+            state.review.trip_cause,
+            Some(crate::core::state::TripCause::IssuesUnresolved)
+        );
+        assert_eq!(state.review.not_spawned_blocks, 0);
+        assert!(
+            state
+                .trace
+                .iter()
+                .any(|e| e.event_type == EventType::CircuitBreakerTripped)
+        );
+    }
+
+    #[test]
+    fn stop_on_trip_ask_approves_with_system_message() {
+        let store = MemoryBackend::new();
+        store.put_session(&issues_state("trip-ask", 2)).unwrap();
+
+        let mut config = Config::default();
+        config.circuit_breaker.on_trip = TripAction::Ask;
+
+        let output = handle_stop_with_config(
+            &StopEvent::from_input(&incident_input("trip-ask")),
+            &store,
+            &config,
+        );
+        assert!(output.decision.is_none());
+        let message = output.system_message.unwrap();
+        assert!(message.contains("issues_unresolved"));
+        assert!(message.contains("roz approve trip-ask"));
+
+        // Review stays enabled, so the next stop asks again
+        let state = store.get_session("trip-ask").unwrap().unwrap();
+        assert!(state.review.enabled);
+        let output = handle_stop_with_config(
+            &StopEvent::from_input(&incident_input("trip-ask")),
+            &store,
+            &config,
+        );
+        assert!(output.system_message.is_some());
+        assert_eq!(
+            store
+                .get_session("trip-ask")
+                .unwrap()
+                .unwrap()
+                .review
+                .trip_count,
+            1
+        );
+    }
+
+    #[test]
+    fn stop_on_trip_block_keeps_blocking() {
+        let store = MemoryBackend::new();
+        store.put_session(&issues_state("trip-block", 2)).unwrap();
+
+        let mut config = Config::default();
+        config.circuit_breaker.on_trip = TripAction::Block;
+
+        for _ in 0..3 {
+            let output = handle_stop_with_config(
+                &StopEvent::from_input(&incident_input("trip-block")),
+                &store,
+                &config,
+            );
+            assert!(matches!(
+                output.decision,
+                Some(crate::hooks::HookDecision::Block)
+            ));
+            assert!(output.reason.unwrap().contains("circuit breaker tripped"));
+        }
+    }
+
+    #[test]
+    fn stop_reset_after_backed_off_cooldown() {
+        let store = MemoryBackend::new();
+        let mut state = issues_state("trip-backoff", 3);
+        state.review.circuit_breaker_tripped = true;
+        state.review.trip_count = 2;
+        // Past the base cooldown (5m) but within the doubled one (10m)
+        state.review.circuit_breaker_tripped_at = Some(Utc::now() - Duration::minutes(7));
+        store.put_session(&state).unwrap();
+
+        let mut config = Config::default();
+        config.circuit_breaker.on_trip = TripAction::Block;
+        let output = handle_stop_with_config(
+            &StopEvent::from_input(&incident_input("trip-backoff")),
+            &store,
+            &config,
+        );
+        assert!(output.reason.unwrap().contains("circuit breaker tripped"));
+
+        let mut state = store.get_session("trip-backoff").unwrap().unwrap();
+        state.review.circuit_breaker_tripped_at = Some(Utc::now() - Duration::minutes(11));
+        store.put_session(&state).unwrap();
+
+        handle_stop_with_config(
+            &StopEvent::from_input(&incident_input("trip-backoff")),
+            &store,
+            &config,
+        );
+        let state = store.get_session("trip-backoff").unwrap().unwrap();
+        assert!(!state.review.circuit_breaker_tripped);
+        assert_eq!(state.review.trip_count, 2);
+        assert!(
+            state
+                .trace
+                .iter()
+                .any(|e| e.event_type == EventType::CircuitBreakerReset)
+        );
+    }
+
+    #[test]
+    fn pre_tool_use_tripped_breaker_follows_on_trip() {
+        let store = MemoryBackend::new();
+        let mut state = SessionState::new("gate-trip");
+        state.review.circuit_breaker_tripped = true;
+        state.review.circuit_breaker_tripped_at = Some(Utc::now());
+        store.put_session(&state).unwrap();
+
+        let input = incident_input("gate-trip");
+        let mut config = make_gate_config();
+
+        config.circuit_breaker.on_trip = TripAction::Ask;
+        let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
+        assert_eq!(
+            output.hook_specific_output.permission_decision,
+            crate::hooks::PermissionDecision::Ask
+        );
+
+        config.circuit_breaker.on_trip = TripAction::Block;
+        let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
+        assert_eq!(
+            output.hook_specific_output.permission_decision,
+            crate::hooks::PermissionDecision::Deny
+        );
+    }
+
+    #[test]
+    fn pre_tool_use_repeated_denials_trip_gate_breaker() {
+        let store = MemoryBackend::new();
+        let input = incident_input("gate-loop");
+        let config = make_gate_config();
+
+        for _ in 1..config.circuit_breaker.gate_denial_limit() {
+            let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
+            assert_eq!(
+                output.hook_specific_output.permission_decision,
+                crate::hooks::PermissionDecision::Deny
+            );
+        }
+
+        // The denial that reaches the limit escalates instead
+        let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
+        assert_eq!(
+            output.hook_specific_output.permission_decision,
+            crate::hooks::PermissionDecision::Ask
+        );
+        let state = store.get_session("gate-loop").unwrap().unwrap();
+        assert!(
+            state
+                .trace
+                .iter()
+                .any(|e| e.event_type == EventType::GateBreakerTripped)
+        );
+        assert!(
+            state
+                .review
+                .gate_tripped_at
+                .contains_key("Bash:gh pr merge*")
+        );
+
+        // Still tripped on the next call; the session breaker is untouched
+        let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
+        assert_eq!(
+            output.hook_specific_output.permission_decision,
+            crate::hooks::PermissionDecision::Ask
+        );
+        let state = store.get_session("gate-loop").unwrap().unwrap();
+        assert!(!state.review.circuit_breaker_tripped);
+    }
+
+    #[test]
+    fn pre_tool_use_gate_breaker_approve_allows() {
+        let store = MemoryBackend::new();
+        let mut state = SessionState::new("gate-approve");
+        state
+            .review
+            .gate_tripped_at
+            .insert("Bash:gh pr merge*".to_string(), Utc::now());
+        store.put_session(&state).unwrap();
+
+        let mut config = make_gate_config();
+        config.circuit_breaker.on_gate_trip = TripAction::Approve;
+        let output = handle_pre_tool_use(
+            &PreToolUseEvent::from_input(&incident_input("gate-approve")),
+            &config,
+            &store,
+        );
+        assert_eq!(
+            output.hook_specific_output.permission_decision,
+            crate::hooks::PermissionDecision::Allow
+        );
+
+        // Other patterns are still gated
+        let input: HookInput = serde_json::from_value(json!({
+            "session_id": "gate-approve",
+            "cwd": "/tmp",
+            "tool_name": "mcp__tissue__close_issue",
+        }))
+        .unwrap();
+        let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
+        assert_eq!(
+            output.hook_specific_output.permission_decision,
+            crate::hooks::PermissionDecision::Deny
+        );
+    }
+
+    #[test]
+    fn pre_tool_use_retried_gate_never_allowed_by_default() {
+        let store = MemoryBackend::new();
+        let input = incident_input("gate-retry");
+        let config = make_gate_config();
+
+        // Well past the denial limit: deny, then ask, never allow
+        for _ in 0..config.circuit_breaker.gate_denial_limit() * 3 {
+            let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
+            assert_ne!(
+                output.hook_specific_output.permission_decision,
+                crate::hooks::PermissionDecision::Allow
+            );
+        }
+    }
+
+    // ========================================================================
+    // Fixed Clock Tests
+    // ========================================================================
+
+    #[test]
+    fn new_sessions_are_created_on_the_context_clock() {
+        let start = Utc::now() - Duration::days(90);
+        let clock = FixedClock::new(start);
+        let rng = SeededRng::new(1);
+        let ctx = Context::new(&clock, &rng);
+        let store = MemoryBackend::new();
+        let config = make_gate_config();
+
+        let _ = handle_session_start_with_context(
+            &SessionStartEvent::from_input(&incident_input("clock-start")),
+            &store,
+            &ctx,
+        );
+        let _ = handle_user_prompt_with_context(
+            &UserPromptSubmitEvent::from_input(&incident_input("clock-prompt")),
+            &store,
+            &config,
+            &ctx,
+        );
+        let _ = handle_pre_tool_use_with_context(
+            &PreToolUseEvent::from_input(&incident_input("clock-gate")),
+            &config,
+            &store,
+            &ctx,
+        );
+        for id in ["clock-start", "clock-prompt", "clock-gate"] {
+            let state = store.get_session(id).unwrap().unwrap();
+            assert_eq!(state.created_at, start, "{id}");
+        }
+    }
+
+    #[test]
+    fn stop_breaker_cooldown_on_fixed_clock() {
+        let clock = FixedClock::new(Utc::now());
+        let rng = SeededRng::new(1);
+        let ctx = Context::new(&clock, &rng);
+        let store = MemoryBackend::new();
+        let mut config = Config::default(); // 3 blocks, 5 minute cooldown
+        config.circuit_breaker.on_trip = TripAction::Block;
+        let input = incident_input("clock-stop");
+        let tripped = |output: HookOutput| {
+            output
+                .reason
+                .is_some_and(|r| r.contains("circuit breaker tripped"))
+        };
+
+        handle_user_prompt_with_context(
+            &UserPromptSubmitEvent::from_input(&input),
+            &store,
+            &config,
+            &ctx,
+        );
+        for _ in 0..2 {
+            let output =
+                handle_stop_with_context(&StopEvent::from_input(&input), &store, &config, &ctx);
+            assert!(!tripped(output));
+        }
+        assert!(tripped(handle_stop_with_context(
+            &StopEvent::from_input(&input),
+            &store,
+            &config,
+            &ctx
+        )));
+
+        clock.advance(Duration::seconds(299));
+        assert!(tripped(handle_stop_with_context(
+            &StopEvent::from_input(&input),
+            &store,
+            &config,
+            &ctx
+        )));
+
+        // Cooldown elapsed: the breaker resets and review blocks again
+        clock.advance(Duration::seconds(1));
+        let output =
+            handle_stop_with_context(&StopEvent::from_input(&input), &store, &config, &ctx);
+        assert!(output.decision.is_some());
+        assert!(!tripped(output));
+        let state = store.get_session("clock-stop").unwrap().unwrap();
+        assert!(!state.review.circuit_breaker_tripped);
+        assert_eq!(state.updated_at, clock.now());
+    }
+
+    #[test]
+    fn gate_approval_expires_on_fixed_clock() {
+        let clock = FixedClock::new(Utc::now());
+        let rng = SeededRng::new(2);
+        let ctx = Context::new(&clock, &rng);
+        let store = MemoryBackend::new();
+        let mut config = make_gate_config();
+        config.review.gates.approval_ttl_seconds = Some(600);
+        let input = incident_input("clock-gate");
+
+        let mut state = SessionState::new("clock-gate");
+        state.review.decision = Decision::Complete {
+            summary: "Reviewed".to_string(),
+            second_opinions: None,
+        };
+        state.review.gate_approved_at = Some(clock.now());
+        store.put_session(&state).unwrap();
+
+        clock.advance(Duration::seconds(600));
+        let output = handle_pre_tool_use_with_context(
+            &PreToolUseEvent::from_input(&input),
+            &config,
+            &store,
+            &ctx,
+        );
+        assert_eq!(
+            output.hook_specific_output.permission_decision,
+            crate::hooks::PermissionDecision::Allow
+        );
+
+        clock.advance(Duration::seconds(1));
+        let output = handle_pre_tool_use_with_context(
+            &PreToolUseEvent::from_input(&input),
+            &config,
+            &store,
+            &ctx,
+        );
+        assert_eq!(
+            output.hook_specific_output.permission_decision,
+            crate::hooks::PermissionDecision::Deny
+        );
+        let state = store.get_session("clock-gate").unwrap().unwrap();
+        assert_eq!(state.review.gate_trigger.unwrap().triggered_at, clock.now());
+    }
+
+    #[test]
+    fn subagent_stop_skew_buffer_on_fixed_clock() {
+        let clock = FixedClock::new(Utc::now());
+        let rng = SeededRng::new(3);
+        let ctx = Context::new(&clock, &rng);
+        let store = MemoryBackend::new();
+        let mut input = incident_input("clock-subagent");
+        input.agent_type = Some("roz:roz".to_string());
+
+        let mut state = SessionState::new("clock-subagent");
+        state.review.decision = Decision::Complete {
+            summary: "Reviewed".to_string(),
+            second_opinions: None,
+        };
+        state.updated_at = clock.now() + Duration::seconds(6);
+        store.put_session(&state).unwrap();
+
+        // Six seconds ahead is past the 5s clock-skew buffer
+        let output = handle_subagent_stop_with_context(
+            &SubagentStopEvent::from_input(&input),
+            &store,
+            &Config::default(),
+            &ctx,
+        );
+        assert!(output.decision.is_some());
+
+        clock.advance(Duration::seconds(1));
+        let output = handle_subagent_stop_with_context(
+            &SubagentStopEvent::from_input(&input),
+            &store,
+            &Config::default(),
+            &ctx,
+        );
+        assert!(output.decision.is_none());
+    }
+
+    // PreCompact and Notification hook tests
+
+    fn event_input(session_id: &str, fields: &Value) -> HookInput {
+        let mut value = json!({ "session_id": session_id, "cwd": "/tmp" });
+        value
+            .as_object_mut()
+            .unwrap()
+            .extend(fields.as_object().unwrap().clone());
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn compaction_reinjects_pending_review_and_last_issues_on_session_start() {
+        let store = MemoryBackend::new();
+        let config = Config::default();
+        let mut state = SessionState::new("compact-1");
+        state.review.enabled = true;
+        state.review.decision_history.push(DecisionRecord {
+            decision: Decision::Issues {
+                summary: "Tests fail".to_string(),
+                message_to_agent: Some("Fix test_login".to_string()),
+            },
+            timestamp: Utc::now(),
+            decided_by: None,
+            rejected: false,
+        });
+        store.put_session(&state).unwrap();
+
+        // PreCompact only records the compaction; it has no context output
+        let input = event_input("compact-1", &json!({"trigger": "auto"}));
+        let output = handle_pre_compact(&PreCompactEvent::from_input(&input), &store, &config);
+        assert!(output.decision.is_none());
+        assert!(output.hook_specific_output.is_none());
+
+        let state = store.get_session("compact-1").unwrap().unwrap();
+        let event = state.trace.last().unwrap();
+        assert_eq!(event.event_type, EventType::CompactionStarted);
+        assert_eq!(event.payload["trigger"], "auto");
+        assert_eq!(event.payload["review_pending"], true);
+
+        // The reminder is re-injected when the session resumes after compaction
+        let input = event_input("compact-1", &json!({"source": "compact"}));
+        let context = handle_session_start(&SessionStartEvent::from_input(&input), &store)
+            .hook_specific_output
+            .unwrap();
+        assert_eq!(context.hook_event_name, "SessionStart");
+        assert!(context.additional_context.contains("compact-1"));
+        assert!(context.additional_context.contains("spawn roz:roz"));
+        assert!(context.additional_context.contains("Fix test_login"));
+    }
+
+    #[test]
+    fn pre_compact_without_owed_review_adds_no_context() {
+        let store = MemoryBackend::new();
+        let config = Config::default();
+        let mut state = SessionState::new("compact-2");
+        state.review.enabled = true;
+        state.review.decision = Decision::Complete {
+            summary: "Reviewed".to_string(),
+            second_opinions: None,
+        };
+        store.put_session(&state).unwrap();
+
+        let input = event_input("compact-2", &json!({"trigger": "manual"}));
+        let output = handle_pre_compact(&PreCompactEvent::from_input(&input), &store, &config);
+        assert!(output.hook_specific_output.is_none());
+
+        let output = handle_pre_compact(
+            &PreCompactEvent::from_input(&event_input("compact-none", &json!({}))),
+            &store,
+            &config,
+        );
+        assert!(output.hook_specific_output.is_none());
+        assert!(store.get_session("compact-none").unwrap().is_none());
+    }
+
+    #[test]
+    fn pre_compact_reminds_of_current_issues() {
+        let mut state = SessionState::new("compact-3");
+        state.review.enabled = true;
+        state.review.decision = Decision::Issues {
+            summary: "Missing tests".to_string(),
+            message_to_agent: None,
+        };
+        let reminder = pending_review_reminder(&state).unwrap();
+        assert!(reminder.contains("Missing tests"));
+        assert!(reminder.contains("re-review"));
+
+        state.review.enabled = false;
+        assert!(pending_review_reminder(&state).is_none());
+    }
+
+    #[test]
+    fn notification_records_permission_and_idle_prompts() {
+        let store = MemoryBackend::new();
+        let config = Config::default();
+        store.put_session(&SessionState::new("notify-1")).unwrap();
+
+        let notifications = [
+            json!({"message": "Claude needs your permission to use Bash", "notification_type": "permission_prompt"}),
+            json!({"message": "Claude is waiting for your input"}),
+            json!({"message": "Signed in", "notification_type": "auth_success"}),
+        ];
+        for fields in &notifications {
+            let output = handle_notification(
+                &NotificationEvent::from_input(&event_input("notify-1", fields)),
+                &store,
+                &config,
+            );
+            assert!(output.decision.is_none());
+        }
+
+        let state = store.get_session("notify-1").unwrap().unwrap();
+        let kinds: Vec<&Value> = state
+            .trace
+            .iter()
+            .filter(|e| e.event_type == EventType::NotificationReceived)
+            .map(|e| &e.payload["notification_type"])
+            .collect();
+        assert_eq!(kinds, [&json!("permission_prompt"), &json!("idle_prompt")]);
+
+        // Unknown sessions are not created
+        let fields = json!({"message": "x", "notification_type": "idle_prompt"});
+        handle_notification(
+            &NotificationEvent::from_input(&event_input("notify-none", &fields)),
+            &store,
+            &config,
+        );
+        assert!(store.get_session("notify-none").unwrap().is_none());
+    }
+
+    // User-prompt review reminder tests
+
+    #[test]
+    fn user_prompt_reminds_of_review_gates_and_unresolved_issues() {
+        let store = MemoryBackend::new();
+        let mut config = Config::default();
+        config.review.gates.tools = vec!["Bash:git push*".to_string()];
+
+        let mut state = SessionState::new("remind-1");
+        state.review.enabled = true;
+        state.review.decision = Decision::Issues {
+            summary: "Tests fail".to_string(),
+            message_to_agent: Some("Fix test_login".to_string()),
+        };
+        store.put_session(&state).unwrap();
+
+        let input = event_input("remind-1", &json!({"prompt": "#roz try again"}));
+        let output = handle_user_prompt_with_config(
+            &UserPromptSubmitEvent::from_input(&input),
+            &store,
+            &config,
+        );
+        assert!(output.decision.is_none());
+        let context = output.hook_specific_output.unwrap();
+        assert_eq!(context.hook_event_name, "UserPromptSubmit");
+        assert!(context.additional_context.contains("session_id: remind-1"));
+        assert!(context.additional_context.contains("`Bash:git push*`"));
+        assert!(context.additional_context.contains("Fix test_login"));
+
+        // The new cycle starts pending, so the issues are not repeated
+        let output = handle_user_prompt_with_config(
+            &UserPromptSubmitEvent::from_input(&input),
+            &store,
+            &config,
+        );
+        let context = output.hook_specific_output.unwrap();
+        assert!(!context.additional_context.contains("Fix test_login"));
+    }
+
+    #[test]
+    fn user_prompt_reminder_skipped_without_review_or_when_off() {
+        let store = MemoryBackend::new();
+        let mut config = Config::default();
+
+        let input = event_input("remind-2", &json!({"prompt": "just a question"}));
+        let output = handle_user_prompt_with_config(
+            &UserPromptSubmitEvent::from_input(&input),
+            &store,
+            &config,
+        );
+        assert!(output.hook_specific_output.is_none());
+
+        config.templates.reminder = "off".to_string();
+        let input = event_input("remind-2", &json!({"prompt": "#roz do it"}));
+        let output = handle_user_prompt_with_config(
+            &UserPromptSubmitEvent::from_input(&input),
+            &store,
+            &config,
+        );
+        assert!(output.hook_specific_output.is_none());
+        assert!(
+            store
+                .get_session("remind-2")
+                .unwrap()
+                .unwrap()
+                .review
+                .enabled
+        );
+    }
+
+    #[test]
+    fn user_prompt_records_trigger_and_honors_opt_out() {
+        let store = MemoryBackend::new();
+        let mut config = Config::default();
+        config.review.triggers = vec![crate::config::TriggerRule::Keyword("deploy".to_string())];
+
+        let input = event_input("trigger-1", &json!({"prompt": "now deploy it"}));
+        let _ = handle_user_prompt_with_config(
+            &UserPromptSubmitEvent::from_input(&input),
+            &store,
+            &config,
+        );
+        let state = store.get_session("trigger-1").unwrap().unwrap();
+        assert!(state.review.enabled);
+        let event = state.trace.last().unwrap();
+        assert_eq!(event.event_type, EventType::PromptReceived);
+        assert_eq!(event.payload["trigger"], "keyword:deploy");
+
+        config.review.mode = crate::config::ReviewMode::Always;
+        let input = event_input("trigger-2", &json!({"prompt": "#noroz what time is it"}));
+        let _ = handle_user_prompt_with_config(
+            &UserPromptSubmitEvent::from_input(&input),
+            &store,
+            &config,
+        );
+        assert!(
+            !store
+                .get_session("trigger-2")
+                .unwrap()
+                .unwrap()
+                .review
+                .enabled
+        );
+    }
+
+    // Risk threshold tests
+
+    fn edit_input(session_id: &str, tool: &str, tool_input: &Value) -> HookInput {
+        event_input(
+            session_id,
+            &json!({"tool_name": tool, "tool_input": tool_input}),
+        )
+    }
+
+    #[test]
+    fn risk_files_threshold_enables_review_and_explains_block() {
+        let store = MemoryBackend::new();
+        let mut config = Config::default();
+        config.review.risk.defaults.files_changed = Some(2);
+
+        let first = edit_input(
+            "risk-1",
+            "Edit",
+            &json!({"file_path": "/tmp/src/a.rs", "old_string": "a", "new_string": "b\nc"}),
+        );
+        let _ = handle_pre_tool_use(&PreToolUseEvent::from_input(&first), &config, &store);
+        let state = store.get_session("risk-1").unwrap().unwrap();
+        assert!(!state.review.enabled);
+        assert!(state.review.activity.files.contains("src/a.rs"));
+        assert_eq!(state.review.activity.lines_changed, 3);
+
+        let second = edit_input(
+            "risk-1",
+            "Write",
+            &json!({"file_path": "/elsewhere/b.rs", "content": "x\ny\n"}),
+        );
+        let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&second), &config, &store);
+        assert_eq!(
+            output.hook_specific_output.permission_decision,
+            crate::hooks::PermissionDecision::Allow
+        );
+
+        let state = store.get_session("risk-1").unwrap().unwrap();
+        assert!(state.review.enabled);
+        assert_eq!(state.review.decision, Decision::Pending);
+        assert_eq!(
+            state.review.activity.risk_trigger.as_deref(),
+            Some("2 files changed (threshold 2)")
+        );
+        let event = state.trace.last().unwrap();
+        assert_eq!(event.event_type, EventType::RiskThresholdCrossed);
+        assert_eq!(event.payload["tool"], "Write");
+
+        let stop = handle_stop_with_config(
+            &StopEvent::from_input(&event_input("risk-1", &json!({}))),
+            &store,
+            &config,
+        );
+        assert_eq!(stop.decision, Some(crate::hooks::HookDecision::Block));
+        assert!(
+            stop.reason
+                .unwrap()
+                .starts_with("roz enabled review automatically: 2 files changed (threshold 2).")
+        );
+    }
+
+    #[test]
+    fn risk_sensitive_paths_and_commands_per_project() {
+        let store = MemoryBackend::new();
+        let mut config = Config::default();
+        config.review.risk.projects.insert(
+            "/tmp".to_string(),
+            RiskThresholds {
+                sensitive_paths: vec!["migrations/**".to_string()],
+                commands: vec!["Bash:terraform apply*".to_string()],
+                ..RiskThresholds::default()
+            },
+        );
+
+        let edit = edit_input(
+            "risk-2",
+            "MultiEdit",
+            &json!({
+                "file_path": "/tmp/migrations/001.sql",
+                "edits": [{"old_string": "a", "new_string": "b"}],
+            }),
+        );
+        let _ = handle_pre_tool_use(&PreToolUseEvent::from_input(&edit), &config, &store);
+        let state = store.get_session("risk-2").unwrap().unwrap();
+        assert!(state.review.enabled);
+        assert_eq!(
+            state.review.activity.risk_trigger.as_deref(),
+            Some("sensitive path `migrations/001.sql` matches `migrations/**`")
+        );
+
+        let command = edit_input(
+            "risk-3",
+            "Bash",
+            &json!({"command": "terraform apply -auto-approve"}),
+        );
+        let _ = handle_pre_tool_use(&PreToolUseEvent::from_input(&command), &config, &store);
+        let state = store.get_session("risk-3").unwrap().unwrap();
+        assert_eq!(
+            state.review.activity.risk_trigger.as_deref(),
+            Some("command `Bash:terraform apply -auto-approve` matches `Bash:terraform apply*`")
+        );
+
+        // Sessions outside the project are not tracked
+        let mut outside = edit_input("risk-4", "Bash", &json!({"command": "terraform apply"}));
+        outside.cwd = "/srv".into();
+        let _ = handle_pre_tool_use(&PreToolUseEvent::from_input(&outside), &config, &store);
+        assert!(store.get_session("risk-4").unwrap().is_none());
+    }
+
+    #[test]
+    fn risk_threshold_reopens_review_after_approval() {
+        let store = MemoryBackend::new();
+        let mut config = Config::default();
+        config.review.risk.defaults.lines_changed = Some(2);
+
+        let mut state = SessionState::new("risk-5");
+        state.review.enabled = true;
+        state.review.decision = Decision::Complete {
+            summary: "ok".to_string(),
+            second_opinions: None,
+        };
+        store.put_session(&state).unwrap();
+
+        let edit = edit_input(
+            "risk-5",
+            "Write",
+            &json!({"file_path": "/tmp/a.rs", "content": "1\n2\n"}),
+        );
+        let _ = handle_pre_tool_use(&PreToolUseEvent::from_input(&edit), &config, &store);
+        let state = store.get_session("risk-5").unwrap().unwrap();
+        assert_eq!(state.review.decision, Decision::Pending);
+        assert!(state.review.activity.risk_trigger.is_some());
+    }
+
+    #[test]
+    fn risk_thresholds_respect_review_mode_never() {
+        let store = MemoryBackend::new();
+        let mut config = Config::default();
+        config.review.mode = ReviewMode::Never;
+        config.review.risk.defaults.lines_changed = Some(1);
+
+        let edit = edit_input(
+            "risk-6",
+            "Write",
+            &json!({"file_path": "/tmp/a.rs", "content": "1\n2\n"}),
+        );
+        let _ = handle_pre_tool_use(&PreToolUseEvent::from_input(&edit), &config, &store);
+        assert!(store.get_session("risk-6").unwrap().is_none());
+    }
+
+    #[test]
+    fn hooks_record_transcript_path() {
+        let store = MemoryBackend::new();
+        let input = event_input(
+            "transcript-1",
+            &json!({"prompt": "#roz go", "transcript_path": "/tmp/t.jsonl"}),
+        );
+        let _ = handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
+        let state = store.get_session("transcript-1").unwrap().unwrap();
+        assert_eq!(state.transcript_path, Some(PathBuf::from("/tmp/t.jsonl")));
+    }
+
+    // Reviewer evidence tests
+
+    fn reviewer_transcript(dir: &Path, commands: &[&str]) -> PathBuf {
+        let path = dir.join("agent.jsonl");
+        let lines: Vec<String> = commands
+            .iter()
+            .map(|command| {
+                json!({"type": "assistant", "isSidechain": true, "message": {"content": [
+                    {"type": "tool_use", "id": command, "name": "Bash", "input": {"command": command}},
+                ]}})
+                .to_string()
+            })
+            .collect();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        path
+    }
+
+    fn reviewed_session(store: &MemoryBackend, session_id: &str) {
+        let mut state = SessionState::new(session_id);
+        state.review.enabled = true;
+        state.review.decision = Decision::Issues {
+            summary: "Missing test".to_string(),
+            message_to_agent: None,
+        };
+        state.review.issues_blocks = 2;
+        let before = state.review.clone();
+
+        // What `roz decide` leaves behind for a COMPLETE
+        state.review.push_decision_record(DecisionRecord {
+            decision: before.decision.clone(),
+            timestamp: Utc::now(),
+            decided_by: None,
+            rejected: false,
+        });
+        state.review.decision = Decision::Complete {
+            summary: "Looks good".to_string(),
+            second_opinions: None,
+        };
+        state.review.gate_approved_at = Some(Utc::now());
+        state.review.issues_blocks = 0;
+        state.review.before_complete = Some(Box::new(before));
+        store.put_session(&state).unwrap();
+    }
+
+    #[test]
+    fn subagent_stop_blocks_complete_without_required_evidence() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let store = MemoryBackend::new();
+        let mut config = Config::default();
+        config.review.evidence.require_context = true;
+        config.review.evidence.require_diff = true;
+        reviewed_session(&store, "evidence-1");
+
+        let mut input = event_input(
+            "evidence-1",
+            &json!({
+                "agent_type": "roz:roz",
+                "agent_transcript_path": reviewer_transcript(temp.path(), &["git diff HEAD"]),
+            }),
+        );
+        let output = handle_subagent_stop_with_config(
+            &SubagentStopEvent::from_input(&input),
+            &store,
+            &config,
+        );
+        assert_eq!(output.decision, Some(crate::hooks::HookDecision::Block));
+        let reason = output.reason.unwrap();
+        assert!(reason.contains("- run `roz context evidence-1`"));
+        assert!(!reason.contains("git diff` to see"));
+
+        // The COMPLETE was undone, kept on record, and the trace says why
+        let state = store.get_session("evidence-1").unwrap().unwrap();
+        assert!(matches!(state.review.decision, Decision::Issues { .. }));
+        assert_eq!(state.review.issues_blocks, 2);
+        assert!(state.review.gate_approved_at.is_none());
+        assert!(state.review.before_complete.is_none());
+        let [rejected] = state.review.decision_history.as_slice() else {
+            panic!("expected only the rejected decision in history");
+        };
+        assert!(rejected.rejected);
+        assert!(matches!(rejected.decision, Decision::Complete { .. }));
+        assert_eq!(state.review.cycle_started_at(), None);
+        let event = state.trace.last().unwrap();
+        assert_eq!(event.event_type, EventType::ReviewerEvidence);
+        assert_eq!(event.payload["ran_diff"], true);
+        assert_eq!(event.payload["accepted"], false);
+        assert_eq!(event.payload["missing"][0], "run `roz context evidence-1`");
+
+        // With the evidence in place the decision stands
+        reviewed_session(&store, "evidence-1");
+        input.agent_transcript_path = Some(reviewer_transcript(
+            temp.path(),
+            &["roz context evidence-1", "git diff HEAD"],
+        ));
+        let output = handle_subagent_stop_with_config(
+            &SubagentStopEvent::from_input(&input),
+            &store,
+            &config,
+        );
+        assert!(output.decision.is_none());
+        let state = store.get_session("evidence-1").unwrap().unwrap();
+        assert!(matches!(state.review.decision, Decision::Complete { .. }));
+        assert!(state.review.before_complete.is_none());
+        assert_eq!(state.trace.last().unwrap().payload["accepted"], true);
+    }
+
+    #[test]
+    fn subagent_stop_without_agent_transcript_blocks_complete_under_policy() {
+        let store = MemoryBackend::new();
+        let mut config = Config::default();
+        config.review.evidence.require_context = true;
+
+        for (session_id, extra) in [
+            ("evidence-2", json!({"agent_type": "roz:roz"})),
+            (
+                "evidence-3",
+                json!({"agent_type": "roz:roz", "agent_transcript_path": "/nonexistent/agent.jsonl"}),
+            ),
+        ] {
+            reviewed_session(&store, session_id);
+            let output = handle_subagent_stop_with_config(
+                &SubagentStopEvent::from_input(&event_input(session_id, &extra)),
+                &store,
+                &config,
+            );
+            assert_eq!(output.decision, Some(crate::hooks::HookDecision::Block));
+            assert!(
+                output
+                    .reason
+                    .unwrap()
+                    .contains("transcript could not be checked")
+            );
+
+            let state = store.get_session(session_id).unwrap().unwrap();
+            assert!(matches!(state.review.decision, Decision::Issues { .. }));
+            assert!(state.review.before_complete.is_none());
+            assert!(state.review.decision_history[0].rejected);
+            let event = state.trace.last().unwrap();
+            assert_eq!(event.event_type, EventType::ReviewerEvidence);
+            assert_eq!(event.payload["accepted"], false);
+            assert!(event.payload["transcript_error"].is_string());
+        }
+    }
+
+    #[test]
+    fn subagent_stop_without_agent_transcript_keeps_decision_without_policy() {
+        let store = MemoryBackend::new();
+        reviewed_session(&store, "evidence-4");
+
+        let input = event_input("evidence-4", &json!({"agent_type": "roz:roz"}));
+        let output = handle_subagent_stop_with_config(
+            &SubagentStopEvent::from_input(&input),
+            &store,
+            &Config::default(),
+        );
+        assert!(output.decision.is_none());
+        let state = store.get_session("evidence-4").unwrap().unwrap();
+        assert!(matches!(state.review.decision, Decision::Complete { .. }));
+        assert!(state.review.before_complete.is_none());
+        let event = state.trace.last().unwrap();
+        assert_eq!(event.event_type, EventType::ReviewerEvidence);
+        assert_eq!(event.payload["accepted"], true);
+        assert!(event.payload["transcript_error"].is_string());
+    }
+
+    #[test]
+    fn stop_records_claimed_summary_across_issues() {
+        let store = MemoryBackend::new();
+        let mut state = SessionState::new("claims-1");
+        state.review.enabled = true;
+        store.put_session(&state).unwrap();
+
+        let stop = |message: &str| {
+            let input = event_input(
+                "claims-1",
+                &json!({"stop_hook_active": false, "last_assistant_message": message}),
+            );
+            let _ = handle_stop(&StopEvent::from_input(&input), &store);
+        };
+        stop("Added logout");
+        let mut state = store.get_session("claims-1").unwrap().unwrap();
+        assert_eq!(
+            state.review.claimed_summary.as_ref().unwrap().summary,
+            "Added logout"
+        );
+
+        state.review.decision = Decision::Issues {
+            summary: "Session not cleared".to_string(),
+            message_to_agent: None,
+        };
+        store.put_session(&state).unwrap();
+        stop("Added logout; it now clears the session");
+        let state = store.get_session("claims-1").unwrap().unwrap();
+        let claim = state.review.claimed_summary.as_ref().unwrap();
+        assert_eq!(claim.after_issues.as_deref(), Some("Session not cleared"));
+        assert_eq!(state.review.claimed_summary_history.len(), 1);
+
+        // A new review after approval starts without the old claims
+        let mut state = state;
+        state.review.decision = Decision::Complete {
+            summary: "ok".to_string(),
+            second_opinions: None,
+        };
+        store.put_session(&state).unwrap();
+        stop("Ignored once approved");
+        let input = event_input("claims-1", &json!({"prompt": "#roz next task"}));
+        let _ = handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
+        let state = store.get_session("claims-1").unwrap().unwrap();
+        assert!(state.review.claimed_summary.is_none());
+        assert!(state.review.claimed_summary_history.is_empty());
+    }
 }
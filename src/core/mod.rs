@@ -1,15 +1,25 @@
 //! Core types and hook logic.
 
 pub mod circuit_breaker;
+pub mod context;
+pub mod evidence;
 pub mod hooks;
+pub mod safety;
 pub mod state;
+pub mod triggers;
 
+pub use context::{Clock, Context, FixedClock, Rng, SeededRng, SystemClock, SystemRng};
 pub use hooks::{
-    handle_pre_tool_use, handle_session_end, handle_session_start, handle_stop,
-    handle_stop_with_config, handle_subagent_stop, handle_user_prompt,
-    handle_user_prompt_with_config,
+    handle_notification, handle_notification_with_context, handle_pre_compact,
+    handle_pre_compact_with_context, handle_pre_tool_use, handle_pre_tool_use_with_context,
+    handle_session_end, handle_session_end_with_context, handle_session_start,
+    handle_session_start_with_context, handle_stop, handle_stop_with_config,
+    handle_stop_with_context, handle_subagent_stop, handle_subagent_stop_with_config,
+    handle_subagent_stop_with_context, handle_user_prompt, handle_user_prompt_with_config,
+    handle_user_prompt_with_context,
 };
 pub use state::{
-    AttemptOutcome, Decision, DecisionRecord, EventType, GateTrigger, ReviewAttempt, ReviewState,
-    SessionState, TraceEvent, TruncatedInput,
+    AttemptOutcome, ClaimedSummary, Decision, DecisionRecord, EventType, GateTrigger,
+    ReviewAttempt, ReviewState, SessionActivity, SessionState, TraceEvent, TripCause,
+    TruncatedInput,
 };
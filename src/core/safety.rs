@@ -0,0 +1,421 @@
+//! Safety mode: what hooks do when an error keeps them from checking review state.
+//!
+//! Every such error is recorded as an incident. In `fail_open` mode (the
+//! default) the hook then approves. In `fail_closed` mode hooks with decision
+//! control block (or deny, for `PreToolUse`) with a diagnostic instead.
+//!
+//! A persistent error would block forever, so fail-closed blocks are capped
+//! like the circuit breaker: after `max_blocks` blocked incidents for the same
+//! hook and session within `cooldown_seconds`, the hook fails open.
+
+use crate::config::{Config, SafetyMode};
+use crate::core::Context;
+use crate::hooks::{HookCommon, HookInput, StopEvent, SubagentStopEvent};
+use crate::storage::file::append_incident;
+use crate::storage::{Incident, IncidentKind, MessageStore};
+use chrono::Duration;
+use std::path::Path;
+
+/// What error handling needs from the failing hook's input.
+#[derive(Debug, Clone, Copy)]
+pub struct FailingInput<'a> {
+    /// Session identifier.
+    pub session_id: &'a str,
+
+    /// Whether a stop hook already blocked this stop.
+    pub stop_hook_active: bool,
+}
+
+impl<'a> From<&'a HookInput> for FailingInput<'a> {
+    fn from(input: &'a HookInput) -> Self {
+        Self {
+            session_id: &input.session_id,
+            stop_hook_active: input.stop_hook_active.unwrap_or(false),
+        }
+    }
+}
+
+impl<'a> From<&'a StopEvent> for FailingInput<'a> {
+    fn from(event: &'a StopEvent) -> Self {
+        Self {
+            session_id: &event.common.session_id,
+            stop_hook_active: event.stop_hook_active,
+        }
+    }
+}
+
+impl<'a> From<&'a SubagentStopEvent> for FailingInput<'a> {
+    fn from(event: &'a SubagentStopEvent) -> Self {
+        Self {
+            session_id: &event.common.session_id,
+            stop_hook_active: event.stop_hook_active,
+        }
+    }
+}
+
+impl<'a> From<&'a HookCommon> for FailingInput<'a> {
+    fn from(common: &'a HookCommon) -> Self {
+        Self {
+            session_id: &common.session_id,
+            stop_hook_active: false,
+        }
+    }
+}
+
+/// Handle an error that keeps `hook` from checking review state.
+///
+/// Records an incident and returns the diagnostic to block with, or `None`
+/// to fail open.
+#[must_use]
+pub fn handle_error(
+    store: &dyn MessageStore,
+    config: &Config,
+    hook: &str,
+    input: Option<FailingInput<'_>>,
+    kind: IncidentKind,
+    message: &str,
+    ctx: &Context,
+) -> Option<String> {
+    let session_id = input.map(|i| i.session_id);
+    let prior = prior_blocks(store, config, hook, session_id, ctx);
+    let blocked = should_block(config, hook, input, prior);
+
+    let incident = new_incident(hook, kind, session_id, message, blocked, ctx);
+    if let Err(e) = store.record_incident(&incident) {
+        eprintln!("roz: warning: failed to record incident: {e}");
+    }
+
+    blocked.then(|| fail_closed_message(message))
+}
+
+/// Handle an error that happened before a storage backend exists.
+///
+/// The incident is appended to `base_dir` directly. Prior incidents cannot be
+/// counted, so the loop is capped by `stop_hook_active` instead.
+#[must_use]
+pub fn handle_init_error(
+    base_dir: &Path,
+    config: &Config,
+    hook: &str,
+    input: Option<FailingInput<'_>>,
+    message: &str,
+    ctx: &Context,
+) -> Option<String> {
+    let blocked = should_block(config, hook, input, None);
+
+    let session_id = input.map(|i| i.session_id);
+    let incident = new_incident(
+        hook,
+        IncidentKind::StorageInit,
+        session_id,
+        message,
+        blocked,
+        ctx,
+    );
+    if let Err(e) = append_incident(base_dir, &incident) {
+        eprintln!("roz: warning: failed to record incident: {e}");
+    }
+
+    blocked.then(|| fail_closed_message(message))
+}
+
+/// Record an incident for an error that never blocks (safety mode ignored).
+pub fn report_incident(
+    store: &dyn MessageStore,
+    hook: &str,
+    kind: IncidentKind,
+    session_id: Option<&str>,
+    message: &str,
+    ctx: &Context,
+) {
+    let incident = new_incident(hook, kind, session_id, message, false, ctx);
+    if let Err(e) = store.record_incident(&incident) {
+        eprintln!("roz: warning: failed to record incident: {e}");
+    }
+}
+
+/// Diagnostic shown to the agent when a hook fails closed.
+#[must_use]
+pub fn fail_closed_message(message: &str) -> String {
+    format!(
+        "roz could not check review state: {message}\n\n\
+         [safety] mode is fail_closed, so this is blocked instead of bypassing review. \
+         Run `roz doctor` to diagnose."
+    )
+}
+
+/// Build an incident and warn on stderr.
+fn new_incident(
+    hook: &str,
+    kind: IncidentKind,
+    session_id: Option<&str>,
+    message: &str,
+    blocked: bool,
+    ctx: &Context,
+) -> Incident {
+    eprintln!("roz: warning: {message}");
+    Incident {
+        timestamp: ctx.now(),
+        hook: hook.to_string(),
+        kind,
+        session_id: session_id.map(String::from),
+        message: message.to_string(),
+        blocked,
+    }
+}
+
+/// Decide whether a failing hook blocks.
+///
+/// `prior` is the number of recent fail-closed blocks for this hook and
+/// session, or `None` if they could not be counted.
+fn should_block(
+    config: &Config,
+    hook: &str,
+    input: Option<FailingInput<'_>>,
+    prior: Option<usize>,
+) -> bool {
+    if config.safety.mode == SafetyMode::FailOpen {
+        return false;
+    }
+
+    // These hooks have no decision control
+    if matches!(
+        hook,
+        "session-start" | "session-end" | "pre-compact" | "notification"
+    ) {
+        return false;
+    }
+
+    // Blocking a prompt throws away what the user typed without guarding
+    // anything: review is enforced at Stop and at gated tools
+    if hook == "user-prompt" {
+        return false;
+    }
+
+    let capped = match prior {
+        Some(count) => count >= config.circuit_breaker.max_blocks as usize,
+        None => input.is_some_and(|i| i.stop_hook_active),
+    };
+    if capped {
+        eprintln!("roz: warning: fail-closed limit reached for {hook}, failing open");
+    }
+    !capped
+}
+
+/// Count fail-closed blocks for `hook` and `session_id` within the cooldown.
+fn prior_blocks(
+    store: &dyn MessageStore,
+    config: &Config,
+    hook: &str,
+    session_id: Option<&str>,
+    ctx: &Context,
+) -> Option<usize> {
+    let cooldown_secs = i64::try_from(config.circuit_breaker.cooldown_seconds).unwrap_or(i64::MAX);
+    let since = ctx.now() - Duration::seconds(cooldown_secs);
+    let incidents = store.incidents_since(since).ok()?;
+    Some(
+        incidents
+            .iter()
+            .filter(|i| i.blocked && i.hook == hook && i.session_id.as_deref() == session_id)
+            .count(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::context::{FixedClock, SeededRng};
+    use crate::storage::MemoryBackend;
+    use chrono::DateTime;
+    use serde_json::json;
+
+    fn ctx() -> Context<'static> {
+        Context::system()
+    }
+
+    fn fail_closed() -> Config {
+        let mut config = Config::default();
+        config.safety.mode = SafetyMode::FailClosed;
+        config
+    }
+
+    fn make_input(stop_hook_active: bool) -> HookInput {
+        serde_json::from_value(json!({
+            "session_id": "safety-test",
+            "cwd": "/tmp",
+            "stop_hook_active": stop_hook_active,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn fail_open_never_blocks() {
+        let store = MemoryBackend::new();
+        let input = make_input(false);
+
+        let result = handle_error(
+            &store,
+            &Config::default(),
+            "stop",
+            Some((&input).into()),
+            IncidentKind::StorageRead,
+            "storage error",
+            &ctx(),
+        );
+        assert!(result.is_none());
+
+        let incidents = store.incidents_since(DateTime::UNIX_EPOCH).unwrap();
+        assert_eq!(incidents.len(), 1);
+        assert!(!incidents[0].blocked);
+    }
+
+    #[test]
+    fn fail_closed_blocks_until_max_blocks() {
+        let store = MemoryBackend::new();
+        let config = fail_closed();
+        let input = make_input(false);
+
+        for _ in 0..config.circuit_breaker.max_blocks {
+            let result = handle_error(
+                &store,
+                &config,
+                "stop",
+                Some((&input).into()),
+                IncidentKind::StorageRead,
+                "storage error",
+                &ctx(),
+            );
+            assert!(result.unwrap().contains("storage error"));
+        }
+
+        // Cap reached: fail open so a persistent error cannot loop forever
+        let result = handle_error(
+            &store,
+            &config,
+            "stop",
+            Some((&input).into()),
+            IncidentKind::StorageRead,
+            "storage error",
+            &ctx(),
+        );
+        assert!(result.is_none());
+
+        // Other hooks have their own count
+        let result = handle_error(
+            &store,
+            &config,
+            "pre-tool-use",
+            Some((&input).into()),
+            IncidentKind::StorageRead,
+            "storage error",
+            &ctx(),
+        );
+        assert!(result.is_some());
+    }
+
+    #[test]
+    fn incidents_and_block_cap_follow_the_context_clock() {
+        let store = MemoryBackend::new();
+        let config = fail_closed();
+        let input = make_input(false);
+        let start = DateTime::UNIX_EPOCH + Duration::days(365);
+        let clock = FixedClock::new(start);
+        let rng = SeededRng::new(1);
+        let ctx = Context::new(&clock, &rng);
+
+        for _ in 0..config.circuit_breaker.max_blocks {
+            let _ = handle_error(
+                &store,
+                &config,
+                "stop",
+                Some((&input).into()),
+                IncidentKind::StorageRead,
+                "storage error",
+                &ctx,
+            );
+        }
+        let incidents = store.incidents_since(DateTime::UNIX_EPOCH).unwrap();
+        assert!(incidents.iter().all(|i| i.timestamp == start));
+
+        // Once the cooldown has passed on the context clock, blocking resumes
+        clock.advance(Duration::seconds(
+            i64::try_from(config.circuit_breaker.cooldown_seconds).unwrap() + 1,
+        ));
+        let result = handle_error(
+            &store,
+            &config,
+            "stop",
+            Some((&input).into()),
+            IncidentKind::StorageRead,
+            "storage error",
+            &ctx,
+        );
+        assert!(result.is_some());
+    }
+
+    #[test]
+    fn fail_closed_ignores_hooks_without_decision_control() {
+        let store = MemoryBackend::new();
+        let input = make_input(false);
+
+        let result = handle_error(
+            &store,
+            &fail_closed(),
+            "session-start",
+            Some((&input).into()),
+            IncidentKind::StorageRead,
+            "storage error",
+            &ctx(),
+        );
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn fail_closed_never_blocks_user_prompts() {
+        let store = MemoryBackend::new();
+        let input = make_input(false);
+
+        let result = handle_error(
+            &store,
+            &fail_closed(),
+            "user-prompt",
+            Some((&input).into()),
+            IncidentKind::StorageRead,
+            "storage error",
+            &ctx(),
+        );
+        assert!(result.is_none());
+
+        // Still recorded, just not blocking
+        let incidents = store.incidents_since(DateTime::UNIX_EPOCH).unwrap();
+        assert_eq!(incidents.len(), 1);
+        assert!(!incidents[0].blocked);
+    }
+
+    #[test]
+    fn init_error_capped_by_stop_hook_active() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config = fail_closed();
+
+        let first = handle_init_error(
+            temp.path(),
+            &config,
+            "stop",
+            Some((&make_input(false)).into()),
+            "storage init failed",
+            &ctx(),
+        );
+        assert!(first.is_some());
+
+        let looping = handle_init_error(
+            temp.path(),
+            &config,
+            "stop",
+            Some((&make_input(true)).into()),
+            "storage init failed",
+            &ctx(),
+        );
+        assert!(looping.is_none());
+        assert!(temp.path().join("incidents.jsonl").exists());
+    }
+}
@@ -4,6 +4,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
 
 /// Session state stored on disk.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,19 +24,34 @@ pub struct SessionState {
 
     /// When the session was last updated.
     pub updated_at: DateTime<Utc>,
+
+    /// Working directory reported by the most recent hook.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+
+    /// Claude Code transcript reported by the most recent hook.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript_path: Option<PathBuf>,
 }
 
 impl SessionState {
     /// Create a new session state.
     #[must_use]
     pub fn new(session_id: &str) -> Self {
-        let now = Utc::now();
+        Self::new_at(session_id, Utc::now())
+    }
+
+    /// Create a new session state created at `now`.
+    #[must_use]
+    pub fn new_at(session_id: &str, now: DateTime<Utc>) -> Self {
         Self {
             session_id: session_id.to_string(),
             review: ReviewState::default(),
             trace: Vec::new(),
             created_at: now,
             updated_at: now,
+            cwd: None,
+            transcript_path: None,
         }
     }
 }
@@ -48,6 +65,10 @@ pub struct ReviewState {
     /// Current decision status.
     pub decision: Decision,
 
+    /// Who made the current decision (`None` while pending).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decided_by: Option<DecidedBy>,
+
     /// History of decisions for debugging.
     pub decision_history: Vec<DecisionRecord>,
 
@@ -72,6 +93,14 @@ pub struct ReviewState {
     /// Number of times the stop hook has blocked.
     pub block_count: u32,
 
+    /// Blocks since the reviewer last posted a decision (reviewer not spawned).
+    #[serde(default)]
+    pub not_spawned_blocks: u32,
+
+    /// Blocks for review issues since the last COMPLETE decision.
+    #[serde(default)]
+    pub issues_blocks: u32,
+
     /// Whether the circuit breaker has tripped.
     #[serde(default)]
     pub circuit_breaker_tripped: bool,
@@ -80,9 +109,149 @@ pub struct ReviewState {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub circuit_breaker_tripped_at: Option<DateTime<Utc>>,
 
+    /// Why the circuit breaker last tripped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trip_cause: Option<TripCause>,
+
+    /// Trips since the last COMPLETE decision (drives cooldown backoff).
+    #[serde(default)]
+    pub trip_count: u32,
+
+    /// `PreToolUse` denials per gate pattern since the reviewer last posted a decision.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub gate_denials: BTreeMap<String, u32>,
+
+    /// When each tripped gate pattern's breaker tripped.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub gate_tripped_at: BTreeMap<String, DateTime<Utc>>,
+
     /// Track each block attempt for A/B testing.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub attempts: Vec<ReviewAttempt>,
+
+    /// Activity since the last approval, for `[review.risk]` thresholds.
+    #[serde(default, skip_serializing_if = "SessionActivity::is_empty")]
+    pub activity: SessionActivity,
+
+    /// The agent's final message at the most recent Stop.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claimed_summary: Option<ClaimedSummary>,
+
+    /// Earlier claims in this review, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub claimed_summary_history: Vec<ClaimedSummary>,
+
+    /// Review state from before the reviewer's COMPLETE, restored if
+    /// `[review.evidence]` rejects it (cleared by the next decision).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before_complete: Option<Box<ReviewState>>,
+}
+
+/// Maximum superseded claims kept in `claimed_summary_history`.
+const MAX_CLAIM_HISTORY: usize = 10;
+
+/// Maximum records kept in `decision_history`.
+const MAX_DECISION_HISTORY: usize = 50;
+
+/// What the agent said it did when it tried to stop.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ClaimedSummary {
+    /// The agent's final message (`last_assistant_message`).
+    pub summary: String,
+
+    /// When the agent stopped with it.
+    pub claimed_at: DateTime<Utc>,
+
+    /// Summary of the ISSUES decision the claim answers (`None` for the
+    /// first attempt).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_issues: Option<String>,
+}
+
+/// File and command activity seen by the pre-tool-use hook.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct SessionActivity {
+    /// Distinct files changed (relative to the session's cwd when inside it).
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub files: BTreeSet<String>,
+
+    /// Lines changed (added plus removed).
+    #[serde(default)]
+    pub lines_changed: u64,
+
+    /// The threshold that enabled review, once one is crossed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk_trigger: Option<String>,
+}
+
+impl ReviewState {
+    /// When the current review cycle started: the previous decision, or for a
+    /// COMPLETE decision the one before it (`None` for the first cycle).
+    #[must_use]
+    pub fn cycle_started_at(&self) -> Option<DateTime<Utc>> {
+        // Each history record is stamped when the next decision replaced it;
+        // rejected decisions never took effect
+        let skip = usize::from(matches!(self.decision, Decision::Complete { .. }));
+        self.decision_history
+            .iter()
+            .rev()
+            .filter(|record| !record.rejected)
+            .nth(skip)
+            .map(|record| record.timestamp)
+    }
+
+    /// Append `record` to the decision history, dropping the oldest records
+    /// beyond the cap.
+    pub fn push_decision_record(&mut self, record: DecisionRecord) {
+        self.decision_history.push(record);
+        if self.decision_history.len() > MAX_DECISION_HISTORY {
+            let excess = self.decision_history.len() - MAX_DECISION_HISTORY;
+            self.decision_history.drain(0..excess);
+        }
+    }
+
+    /// Record the agent's final message as its claim, moving a different
+    /// earlier claim into history. Repeating the same claim changes nothing.
+    pub fn record_claim(&mut self, summary: &str, at: DateTime<Utc>) {
+        if self
+            .claimed_summary
+            .as_ref()
+            .is_some_and(|claim| claim.summary == summary)
+        {
+            return;
+        }
+        let after_issues = match &self.decision {
+            Decision::Issues { summary, .. } => Some(summary.clone()),
+            _ => None,
+        };
+        let claim = ClaimedSummary {
+            summary: summary.to_string(),
+            claimed_at: at,
+            after_issues,
+        };
+        if let Some(previous) = self.claimed_summary.replace(claim) {
+            self.claimed_summary_history.push(previous);
+            let excess = self
+                .claimed_summary_history
+                .len()
+                .saturating_sub(MAX_CLAIM_HISTORY);
+            self.claimed_summary_history.drain(..excess);
+        }
+    }
+
+    /// Forget the claims of a finished review.
+    pub fn clear_claims(&mut self) {
+        self.claimed_summary = None;
+        self.claimed_summary_history.clear();
+    }
+}
+
+impl SessionActivity {
+    /// Whether nothing has been recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 /// Review decision.
@@ -113,6 +282,43 @@ pub enum Decision {
     },
 }
 
+/// Why the circuit breaker tripped.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TripCause {
+    /// The agent kept stopping without spawning the reviewer.
+    ReviewerNotSpawned,
+
+    /// The reviewer kept finding issues the agent did not resolve.
+    IssuesUnresolved,
+
+    /// A human tripped it with `roz breaker trip`.
+    Manual,
+}
+
+impl TripCause {
+    /// Short name (matches the serialized form).
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ReviewerNotSpawned => "reviewer_not_spawned",
+            Self::IssuesUnresolved => "issues_unresolved",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+/// Who made a review decision.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DecidedBy {
+    /// The roz:roz reviewer agent (`roz decide`).
+    Reviewer,
+
+    /// A human at the terminal (`roz approve` / `roz reject`).
+    Human,
+}
+
 /// Record of a past decision.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DecisionRecord {
@@ -121,6 +327,14 @@ pub struct DecisionRecord {
 
     /// When the decision was made.
     pub timestamp: DateTime<Utc>,
+
+    /// Who made the decision (absent for pending and legacy records).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decided_by: Option<DecidedBy>,
+
+    /// A COMPLETE decision `[review.evidence]` rejected; it never took effect.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub rejected: bool,
 }
 
 /// Context about what triggered the gate (stored for roz to review).
@@ -271,14 +485,17 @@ pub enum AttemptOutcome {
         blocks_needed: u32,
     },
 
-    /// Agent didn't spawn roz (circuit breaker tripped).
+    /// Agent stopped again (or the session ended) without spawning roz.
     NotSpawned,
 
-    /// Roz spawned but didn't post decision.
+    /// Roz spawned but exited without posting a decision.
     NoDecision,
 
-    /// Roz spawned but `SESSION_ID` was missing/wrong.
+    /// Roz spawned but posted its decision against a wrong `SESSION_ID`.
     BadSessionId,
+
+    /// A human decided (`roz approve` / `roz reject`) before roz did.
+    Overridden,
 }
 
 /// Trace event for debugging.
@@ -315,8 +532,26 @@ pub enum EventType {
     StopHookCalled,
     /// Roz decision recorded.
     RozDecision,
+    /// Human override recorded (`roz approve` / `roz reject`).
+    HumanDecision,
+    /// Circuit breaker tripped.
+    CircuitBreakerTripped,
+    /// Circuit breaker reset after cooldown.
+    CircuitBreakerReset,
+    /// A gate pattern's breaker tripped after repeated `PreToolUse` denials.
+    GateBreakerTripped,
+    /// A human reset or tripped the breaker (`roz breaker reset` / `trip`).
+    ManualBreakerAction,
     /// Trace was truncated due to `max_events` limit.
     TraceCompacted,
+    /// Session activity crossed a `[review.risk]` threshold and enabled review.
+    RiskThresholdCrossed,
+    /// What the reviewer inspected before its decision, from its transcript.
+    ReviewerEvidence,
+    /// The conversation was about to be compacted.
+    CompactionStarted,
+    /// Claude Code showed a permission or idle prompt.
+    NotificationReceived,
     /// Session ended.
     SessionEnd,
 }
@@ -324,6 +559,7 @@ pub enum EventType {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
     use serde_json::json;
 
     #[test]
@@ -527,4 +763,67 @@ mod tests {
             r#"{"type":"not_spawned"}"#
         );
     }
+
+    #[test]
+    fn cycle_started_at_follows_decisions() {
+        let at = |minute: u32| Utc.with_ymd_and_hms(2026, 1, 1, 10, minute, 0).unwrap();
+        let record = |minute| DecisionRecord {
+            decision: Decision::Pending,
+            timestamp: at(minute),
+            decided_by: None,
+            rejected: false,
+        };
+        let mut review = ReviewState::default();
+        assert!(review.cycle_started_at().is_none());
+
+        // ISSUES posted at :05, so the fix cycle starts there
+        review.decision_history.push(record(5));
+        review.decision = Decision::Issues {
+            summary: "x".to_string(),
+            message_to_agent: None,
+        };
+        assert_eq!(review.cycle_started_at(), Some(at(5)));
+
+        // COMPLETE posted at :20 closes the cycle that started at :05
+        review.decision_history.push(record(20));
+        review.decision = Decision::Complete {
+            summary: "ok".to_string(),
+            second_opinions: None,
+        };
+        assert_eq!(review.cycle_started_at(), Some(at(5)));
+
+        // A new prompt resets to pending; the next cycle starts at :20
+        review.decision = Decision::Pending;
+        assert_eq!(review.cycle_started_at(), Some(at(20)));
+    }
+
+    #[test]
+    fn record_claim_keeps_history_across_issues() {
+        let at = |minute: u32| Utc.with_ymd_and_hms(2026, 1, 1, 10, minute, 0).unwrap();
+        let mut review = ReviewState::default();
+        review.record_claim("Fixed login", at(0));
+        review.record_claim("Fixed login", at(1));
+        assert!(review.claimed_summary_history.is_empty());
+        assert_eq!(review.claimed_summary.as_ref().unwrap().claimed_at, at(0));
+
+        review.decision = Decision::Issues {
+            summary: "No test".to_string(),
+            message_to_agent: None,
+        };
+        review.record_claim("Fixed login, added test", at(5));
+        let current = review.claimed_summary.as_ref().unwrap();
+        assert_eq!(current.after_issues.as_deref(), Some("No test"));
+        assert_eq!(review.claimed_summary_history.len(), 1);
+        assert_eq!(review.claimed_summary_history[0].summary, "Fixed login");
+        assert!(review.claimed_summary_history[0].after_issues.is_none());
+
+        for i in 0..20 {
+            review.record_claim(&format!("attempt {i}"), at(10));
+        }
+        assert_eq!(review.claimed_summary_history.len(), MAX_CLAIM_HISTORY);
+        assert_eq!(review.claimed_summary_history[9].summary, "attempt 18");
+
+        review.clear_claims();
+        assert!(review.claimed_summary.is_none() && review.claimed_summary_history.is_empty());
+    }
 }
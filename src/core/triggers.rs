@@ -0,0 +1,238 @@
+//! Review activation triggers.
+//!
+//! Decides whether a prompt turns review on: `always` mode reviews every
+//! prompt, `prompt` mode checks `[review] triggers` in order, and an opt-out
+//! marker such as `#noroz` skips review in either mode. Branch and file rules
+//! ask git about the session's working directory, only when reached.
+
+use crate::config::{ReviewConfig, ReviewMode, TriggerRule};
+use crate::hooks::UserPromptSubmitEvent;
+use glob::Pattern;
+use regex::Regex;
+use std::path::Path;
+use std::process::Command;
+
+/// Which trigger enables review for this prompt, if any.
+///
+/// Returns `"always"` in `always` mode, otherwise the first matching rule
+/// (e.g. `"branch:release/*"`).
+#[must_use]
+pub fn review_trigger(review: &ReviewConfig, event: &UserPromptSubmitEvent) -> Option<String> {
+    let prompt = &event.prompt;
+    if review
+        .opt_out
+        .iter()
+        .any(|marker| prompt.contains(marker.as_str()))
+    {
+        return None;
+    }
+
+    match review.mode {
+        ReviewMode::Never => None,
+        ReviewMode::Always => Some("always".to_string()),
+        ReviewMode::Prompt => review
+            .triggers
+            .iter()
+            .find(|rule| fires(rule, prompt, event))
+            .map(ToString::to_string),
+    }
+}
+
+/// Check one rule against the prompt and session.
+fn fires(rule: &TriggerRule, prompt: &str, event: &UserPromptSubmitEvent) -> bool {
+    let cwd = &event.common.cwd;
+    match rule {
+        TriggerRule::Prefix(prefix) => prompt.trim_start().starts_with(prefix.as_str()),
+        TriggerRule::Keyword(word) => contains_word(prompt, word),
+        // Patterns are validated when the config loads
+        TriggerRule::Regex(re) => Regex::new(re).is_ok_and(|re| re.is_match(prompt)),
+        TriggerRule::Branch(glob) => current_branch(cwd)
+            .is_some_and(|branch| Pattern::new(glob).is_ok_and(|p| p.matches(&branch))),
+        TriggerRule::Files(glob) => {
+            Pattern::new(glob).is_ok_and(|p| changed_files(cwd).iter().any(|f| p.matches(f)))
+        }
+        TriggerRule::PermissionMode(mode) => event.common.permission_mode.as_deref() == Some(mode),
+    }
+}
+
+/// Case-insensitive whole-word search.
+fn contains_word(text: &str, word: &str) -> bool {
+    if word.is_empty() {
+        return false;
+    }
+    let text = text.to_lowercase();
+    let word = word.to_lowercase();
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+
+    text.match_indices(&word).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + word.len()..].chars().next();
+        !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+    })
+}
+
+/// Current git branch of `cwd` (works before the first commit).
+fn current_branch(cwd: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(cwd)
+        .args(["symbolic-ref", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Files modified, staged or untracked in the working tree of `cwd`,
+/// relative to the repository root.
+pub(crate) fn changed_files(cwd: &Path) -> Vec<String> {
+    let Ok(output) = Command::new("git")
+        .arg("-C")
+        .arg(cwd)
+        .args(["status", "--porcelain=v1", "-z", "--untracked-files=all"])
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    // Entries are "XY path\0"; renames and copies add "orig\0" after
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut files = Vec::new();
+    let mut entries = stdout.split('\0').filter(|e| e.len() > 3);
+    while let Some(entry) = entries.next() {
+        let (status, path) = entry.split_at(3);
+        files.push(path.to_string());
+        if status.contains(['R', 'C']) {
+            entries.next();
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::HookCommon;
+    use std::fs;
+
+    fn input(prompt: &str, cwd: &Path) -> UserPromptSubmitEvent {
+        UserPromptSubmitEvent {
+            common: HookCommon {
+                session_id: "trigger-test".to_string(),
+                cwd: cwd.to_path_buf(),
+                transcript_path: None,
+                permission_mode: Some("default".to_string()),
+            },
+            prompt: prompt.to_string(),
+        }
+    }
+
+    fn review(mode: ReviewMode, triggers: Vec<TriggerRule>) -> ReviewConfig {
+        ReviewConfig {
+            mode,
+            triggers,
+            ..ReviewConfig::default()
+        }
+    }
+
+    #[test]
+    fn default_rules_match_roz_prefix_and_honor_opt_out() {
+        let cwd = Path::new("/tmp");
+        let config = ReviewConfig::default();
+        assert_eq!(
+            review_trigger(&config, &input("  #roz fix it", cwd)).as_deref(),
+            Some("prefix:#roz")
+        );
+        assert!(review_trigger(&config, &input("fix it #roz", cwd)).is_none());
+        assert!(review_trigger(&config, &input("#roz #noroz", cwd)).is_none());
+
+        let always = review(ReviewMode::Always, Vec::new());
+        assert_eq!(
+            review_trigger(&always, &input("anything", cwd)).as_deref(),
+            Some("always")
+        );
+        assert!(review_trigger(&always, &input("quick #noroz question", cwd)).is_none());
+
+        let never = review(ReviewMode::Never, vec![TriggerRule::Keyword("x".into())]);
+        assert!(review_trigger(&never, &input("x", cwd)).is_none());
+    }
+
+    #[test]
+    fn keyword_regex_and_permission_mode_rules() {
+        let cwd = Path::new("/tmp");
+        let config = review(
+            ReviewMode::Prompt,
+            vec![
+                TriggerRule::Keyword("Deploy".into()),
+                TriggerRule::Regex(r"(?i)\bmigrat(e|ion)s?\b".into()),
+                TriggerRule::PermissionMode("bypassPermissions".into()),
+            ],
+        );
+        assert_eq!(
+            review_trigger(&config, &input("please deploy now", cwd)).as_deref(),
+            Some("keyword:Deploy")
+        );
+        assert!(review_trigger(&config, &input("redeployment", cwd)).is_none());
+        assert_eq!(
+            review_trigger(&config, &input("Add a Migration", cwd)).as_deref(),
+            Some(r"regex:(?i)\bmigrat(e|ion)s?\b")
+        );
+
+        let mut bypass = input("tidy up", cwd);
+        assert!(review_trigger(&config, &bypass).is_none());
+        bypass.common.permission_mode = Some("bypassPermissions".into());
+        assert_eq!(
+            review_trigger(&config, &bypass).as_deref(),
+            Some("permission_mode:bypassPermissions")
+        );
+    }
+
+    #[test]
+    fn branch_and_files_rules_ask_git() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(temp.path())
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {args:?} failed");
+        };
+        git(&["init", "--quiet"]);
+        git(&["checkout", "--quiet", "-b", "release/1.2"]);
+        fs::create_dir_all(temp.path().join("src/auth")).unwrap();
+        fs::write(temp.path().join("src/auth/login.rs"), "").unwrap();
+
+        let branch = review(
+            ReviewMode::Prompt,
+            vec![TriggerRule::Branch("release/*".into())],
+        );
+        assert_eq!(
+            review_trigger(&branch, &input("tidy up", temp.path())).as_deref(),
+            Some("branch:release/*")
+        );
+
+        let files = review(
+            ReviewMode::Prompt,
+            vec![TriggerRule::Files("src/auth/**".into())],
+        );
+        assert!(review_trigger(&files, &input("tidy up", temp.path())).is_some());
+        let other = review(
+            ReviewMode::Prompt,
+            vec![TriggerRule::Files("docs/**".into())],
+        );
+        assert!(review_trigger(&other, &input("tidy up", temp.path())).is_none());
+
+        // Outside a repository neither rule fires
+        let outside = tempfile::TempDir::new().unwrap();
+        assert!(review_trigger(&branch, &input("tidy up", outside.path())).is_none());
+        assert!(review_trigger(&files, &input("tidy up", outside.path())).is_none());
+    }
+}
@@ -0,0 +1,504 @@
+//! Library facade for embedding roz in other tools.
+//!
+//! [`RozEngine`] bundles a store, config, clock and randomness source, and
+//! exposes each hook and reporting command as a method that returns a value
+//! instead of printing. An optional [`EventSink`] sees every trace event the
+//! engine's calls add to a session.
+//!
+//! ```no_run
+//! use roz::engine::RozEngine;
+//! use roz::storage::MemoryBackend;
+//!
+//! let engine = RozEngine::builder()
+//!     .store(MemoryBackend::new())
+//!     .event_sink(|session_id: &str, event: &roz::core::TraceEvent| {
+//!         eprintln!("{session_id}: {:?}", event.event_type);
+//!     })
+//!     .build()?;
+//! let report = engine.stats(30, 30)?;
+//! # Ok::<(), roz::Error>(())
+//! ```
+
+use crate::cli::clean::CleanReport;
+use crate::cli::context::ContextReport;
+use crate::cli::stats::StatsReport;
+use crate::cli::{clean, context, decide, hook, stats};
+use crate::config::Config;
+use crate::core::context::{Clock, Context, Rng, SystemClock, SystemRng};
+use crate::core::state::{Decision, TraceEvent};
+use crate::core::{
+    handle_notification_with_context, handle_pre_compact_with_context,
+    handle_pre_tool_use_with_context, handle_session_end_with_context,
+    handle_session_start_with_context, handle_stop_with_context, handle_subagent_stop_with_context,
+    handle_user_prompt_with_context,
+};
+use crate::error::{Error, Result};
+use crate::hooks::{
+    EventOutput, HookEvent, HookOutput, NotificationEvent, PreCompactEvent, PreToolUseEvent,
+    PreToolUseOutput, SessionEndEvent, SessionStartEvent, StopEvent, SubagentStopEvent,
+    UserPromptSubmitEvent, dispatch_event_with_context,
+};
+use crate::storage::MessageStore;
+use crate::storage::file::{FileBackend, get_roz_home};
+use crate::transcript::TranscriptLimits;
+use chrono::Duration;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Receives trace events added by engine calls.
+pub trait EventSink: Send + Sync {
+    /// Called once per new event, oldest first.
+    fn emit(&self, session_id: &str, event: &TraceEvent);
+}
+
+impl<F> EventSink for F
+where
+    F: Fn(&str, &TraceEvent) + Send + Sync,
+{
+    fn emit(&self, session_id: &str, event: &TraceEvent) {
+        self(session_id, event);
+    }
+}
+
+/// Builder for [`RozEngine`].
+///
+/// Every part is optional: the store defaults to the file backend under
+/// `ROZ_HOME`, the config to `Config::default()` (pass `load_config()?` for
+/// the user's config), and the clock and randomness to the system's.
+#[derive(Default)]
+pub struct RozEngineBuilder {
+    store: Option<Box<dyn MessageStore>>,
+    config: Option<Config>,
+    clock: Option<Box<dyn Clock>>,
+    rng: Option<Box<dyn Rng>>,
+    sink: Option<Box<dyn EventSink>>,
+}
+
+impl RozEngineBuilder {
+    /// Use `store` for session state.
+    #[must_use]
+    pub fn store(mut self, store: impl MessageStore + 'static) -> Self {
+        self.store = Some(Box::new(store));
+        self
+    }
+
+    /// Use `config`.
+    #[must_use]
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Read the time from `clock`.
+    #[must_use]
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// Generate IDs from `rng`.
+    #[must_use]
+    pub fn rng(mut self, rng: impl Rng + 'static) -> Self {
+        self.rng = Some(Box::new(rng));
+        self
+    }
+
+    /// Send new trace events to `sink`.
+    #[must_use]
+    pub fn event_sink(mut self, sink: impl EventSink + 'static) -> Self {
+        self.sink = Some(Box::new(sink));
+        self
+    }
+
+    /// Build the engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no store was given and the file backend cannot be
+    /// created.
+    pub fn build(self) -> Result<RozEngine> {
+        let store = match self.store {
+            Some(store) => store,
+            None => Box::new(FileBackend::new(get_roz_home())?),
+        };
+        Ok(RozEngine {
+            store,
+            config: self.config.unwrap_or_default(),
+            clock: self.clock.unwrap_or_else(|| Box::new(SystemClock)),
+            rng: self.rng.unwrap_or_else(|| Box::new(SystemRng)),
+            sink: self.sink,
+        })
+    }
+}
+
+/// roz hooks and commands over one store and config.
+pub struct RozEngine {
+    store: Box<dyn MessageStore>,
+    config: Config,
+    clock: Box<dyn Clock>,
+    rng: Box<dyn Rng>,
+    sink: Option<Box<dyn EventSink>>,
+}
+
+impl RozEngine {
+    /// Start building an engine.
+    #[must_use]
+    pub fn builder() -> RozEngineBuilder {
+        RozEngineBuilder::default()
+    }
+
+    /// The engine's config.
+    #[must_use]
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// The engine's store.
+    #[must_use]
+    pub fn store(&self) -> &dyn MessageStore {
+        self.store.as_ref()
+    }
+
+    /// Handle the session-start hook.
+    #[must_use]
+    pub fn session_start(&self, event: &SessionStartEvent) -> HookOutput {
+        self.observe(&event.common.session_id, |ctx| {
+            handle_session_start_with_context(event, self.store(), ctx)
+        })
+    }
+
+    /// Handle the session-end hook.
+    #[must_use]
+    pub fn session_end(&self, event: &SessionEndEvent) -> HookOutput {
+        self.observe(&event.common.session_id, |ctx| {
+            handle_session_end_with_context(event, self.store(), ctx)
+        })
+    }
+
+    /// Handle the user-prompt hook.
+    #[must_use]
+    pub fn user_prompt(&self, event: &UserPromptSubmitEvent) -> HookOutput {
+        self.observe(&event.common.session_id, |ctx| {
+            handle_user_prompt_with_context(event, self.store(), &self.config, ctx)
+        })
+    }
+
+    /// Handle the stop hook.
+    #[must_use]
+    pub fn stop(&self, event: &StopEvent) -> HookOutput {
+        self.observe(&event.common.session_id, |ctx| {
+            handle_stop_with_context(event, self.store(), &self.config, ctx)
+        })
+    }
+
+    /// Handle the subagent-stop hook.
+    #[must_use]
+    pub fn subagent_stop(&self, event: &SubagentStopEvent) -> HookOutput {
+        self.observe(&event.common.session_id, |ctx| {
+            handle_subagent_stop_with_context(event, self.store(), &self.config, ctx)
+        })
+    }
+
+    /// Handle the pre-compact hook.
+    #[must_use]
+    pub fn pre_compact(&self, event: &PreCompactEvent) -> HookOutput {
+        self.observe(&event.common.session_id, |ctx| {
+            handle_pre_compact_with_context(event, self.store(), &self.config, ctx)
+        })
+    }
+
+    /// Handle the notification hook.
+    #[must_use]
+    pub fn notification(&self, event: &NotificationEvent) -> HookOutput {
+        self.observe(&event.common.session_id, |ctx| {
+            handle_notification_with_context(event, self.store(), &self.config, ctx)
+        })
+    }
+
+    /// Handle the pre-tool-use hook.
+    #[must_use]
+    pub fn pre_tool_use(&self, event: &PreToolUseEvent) -> PreToolUseOutput {
+        self.observe(&event.common.session_id, |ctx| {
+            handle_pre_tool_use_with_context(event, &self.config, self.store(), ctx)
+        })
+    }
+
+    /// Dispatch a typed event to its handler.
+    #[must_use]
+    pub fn dispatch(&self, event: &HookEvent) -> EventOutput {
+        self.observe(event.session_id(), |ctx| {
+            dispatch_event_with_context(event, self.store(), &self.config, ctx)
+        })
+    }
+
+    /// Run hook `name` on raw JSON input, as `roz hook <name>` does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the output cannot be serialized.
+    pub fn hook_json(&self, name: &str, input_json: &str) -> Result<Value> {
+        let session_id = serde_json::from_str::<Value>(input_json)
+            .ok()
+            .and_then(|v| v.get("session_id")?.as_str().map(String::from))
+            .unwrap_or_default();
+        self.observe(&session_id, |ctx| {
+            hook::respond(name, input_json, &self.config, None, self.store(), ctx)
+        })
+    }
+
+    /// Post a reviewer decision (`COMPLETE` or `ISSUES`), returning it
+    /// normalized to uppercase.
+    ///
+    /// `cwd` is the reviewer's working directory: a decision for an unknown
+    /// session is remembered there so the subagent-stop hook can blame the
+    /// wrong ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage backend fails, the session is not
+    /// found, or the decision type is invalid.
+    pub fn decide(
+        &self,
+        session_id: &str,
+        decision: &str,
+        summary: &str,
+        message: Option<&str>,
+        opinions: Option<&str>,
+        cwd: Option<&Path>,
+    ) -> Result<String> {
+        self.observe(session_id, |ctx| {
+            let result = decide::record_decision_with_context(
+                self.store(),
+                session_id,
+                decision,
+                summary,
+                message,
+                opinions,
+                ctx,
+            );
+            if matches!(result, Err(Error::SessionNotFound(_))) {
+                decide::record_unknown_session(self.store(), session_id, cwd, ctx);
+            }
+            result
+        })
+    }
+
+    /// Approve a session as a human.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage backend fails or the session is not found.
+    pub fn approve(&self, session_id: &str, reason: &str) -> Result<()> {
+        let decision = Decision::Complete {
+            summary: reason.to_string(),
+            second_opinions: None,
+        };
+        self.observe(session_id, |ctx| {
+            decide::record_human_decision_with_context(self.store(), session_id, decision, ctx)
+        })
+    }
+
+    /// Reject a session as a human, sending `message` to the agent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage backend fails or the session is not found.
+    pub fn reject(&self, session_id: &str, message: &str) -> Result<()> {
+        let decision = Decision::Issues {
+            summary: message.to_string(),
+            message_to_agent: Some(message.to_string()),
+        };
+        self.observe(session_id, |ctx| {
+            decide::record_human_decision_with_context(self.store(), session_id, decision, ctx)
+        })
+    }
+
+    /// Review context for a session (`roz context`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage backend fails or the session is not found.
+    pub fn context(&self, session_id: &str) -> Result<ContextReport> {
+        context::collect(self.store(), session_id)
+    }
+
+    /// Review context with a transcript excerpt (`roz context --transcript`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage backend fails or the session is not found.
+    pub fn context_with_transcript(
+        &self,
+        session_id: &str,
+        limits: &TranscriptLimits,
+    ) -> Result<ContextReport> {
+        context::collect_with_transcript(self.store(), session_id, limits)
+    }
+
+    /// Template statistics for the last `days` days (`roz stats`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if storage operations fail.
+    pub fn stats(&self, days: u32, min_samples: u32) -> Result<StatsReport> {
+        stats::collect_at(self.store(), days, min_samples, self.clock.now())
+    }
+
+    /// Remove sessions older than `older_than` that are not awaiting review
+    /// (`roz clean`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage backend fails.
+    pub fn clean(&self, older_than: Duration) -> Result<CleanReport> {
+        clean::collect(self.store(), self.clock.now() - older_than)
+    }
+
+    /// Run `f` with the engine's context, then send the session's new trace
+    /// events to the sink.
+    fn observe<T>(&self, session_id: &str, f: impl FnOnce(&Context) -> T) -> T {
+        let ctx = Context::new(self.clock.as_ref(), self.rng.as_ref());
+        let Some(sink) = &self.sink else {
+            return f(&ctx);
+        };
+
+        let seen: HashSet<String> = self
+            .store
+            .get_session(session_id)
+            .ok()
+            .flatten()
+            .map(|state| state.trace.into_iter().map(|e| e.id).collect())
+            .unwrap_or_default();
+        let output = f(&ctx);
+
+        if let Ok(Some(state)) = self.store.get_session(session_id) {
+            for event in state.trace.iter().filter(|e| !seen.contains(&e.id)) {
+                sink.emit(session_id, event);
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::context::{FixedClock, SeededRng};
+    use crate::core::state::EventType;
+    use crate::hooks::{HookCommon, HookDecision};
+    use crate::storage::MemoryBackend;
+    use chrono::Utc;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    fn common(session_id: &str) -> HookCommon {
+        HookCommon {
+            session_id: session_id.to_string(),
+            cwd: "/tmp".into(),
+            transcript_path: None,
+            permission_mode: None,
+        }
+    }
+
+    fn stop(session_id: &str) -> StopEvent {
+        StopEvent {
+            common: common(session_id),
+            stop_hook_active: false,
+            last_assistant_message: None,
+        }
+    }
+
+    #[test]
+    fn engine_runs_review_cycle_and_emits_events() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink_events = Arc::clone(&events);
+        let start = Utc::now();
+        let engine = RozEngine::builder()
+            .store(MemoryBackend::new())
+            .clock(FixedClock::new(start))
+            .rng(SeededRng::new(9))
+            .event_sink(move |session_id: &str, event: &TraceEvent| {
+                sink_events
+                    .lock()
+                    .unwrap()
+                    .push((session_id.to_string(), event.event_type.clone()));
+            })
+            .build()
+            .unwrap();
+
+        let _ = engine.user_prompt(&UserPromptSubmitEvent {
+            common: common("engine-1"),
+            prompt: "#roz fix it".to_string(),
+        });
+        let output = engine.stop(&stop("engine-1"));
+        assert!(matches!(output.decision, Some(HookDecision::Block)));
+
+        let decision = engine
+            .decide("engine-1", "complete", "Looks good", None, None, None)
+            .unwrap();
+        assert_eq!(decision, "COMPLETE");
+        assert!(engine.stop(&stop("engine-1")).decision.is_none());
+
+        let context = engine.context("engine-1").unwrap();
+        assert_eq!(context.user_prompts, ["#roz fix it"]);
+        assert_eq!(context.updated_at, start);
+
+        let types: Vec<EventType> = events.lock().unwrap().iter().map(|e| e.1.clone()).collect();
+        assert_eq!(
+            types,
+            [
+                EventType::PromptReceived,
+                EventType::StopHookCalled,
+                EventType::RozDecision,
+                EventType::StopHookCalled,
+            ]
+        );
+    }
+
+    #[test]
+    fn engine_reports_use_engine_clock() {
+        let start = Utc::now() - Duration::days(365);
+        let clock = Arc::new(FixedClock::new(start));
+        let engine = RozEngine::builder()
+            .store(MemoryBackend::new())
+            .clock(Arc::clone(&clock))
+            .build()
+            .unwrap();
+
+        // Created a year ago by the engine's clock, which is also "now"
+        let _ = engine.session_start(&SessionStartEvent {
+            common: common("engine-old"),
+            source: "startup".to_string(),
+            model: None,
+        });
+        let state = engine.store().get_session("engine-old").unwrap().unwrap();
+        assert_eq!(state.created_at, start);
+        assert_eq!(engine.stats(30, 30).unwrap().sessions_analyzed, 1);
+        assert!(engine.clean(Duration::days(7)).unwrap().removed.is_empty());
+
+        // 60 days later on the same clock the session is out of range
+        clock.advance(Duration::days(60));
+        assert_eq!(engine.stats(30, 30).unwrap().sessions_analyzed, 0);
+
+        let report = engine.clean(Duration::days(7)).unwrap();
+        assert_eq!(report.cutoff, start + Duration::days(53));
+        assert_eq!(report.removed.len(), 1);
+        assert!(engine.store().get_session("engine-old").unwrap().is_none());
+    }
+
+    #[test]
+    fn hook_json_matches_roz_hook() {
+        let engine = RozEngine::builder()
+            .store(MemoryBackend::new())
+            .build()
+            .unwrap();
+        let output = engine
+            .hook_json("stop", r#"{"session_id":"engine-json","cwd":"/tmp"}"#)
+            .unwrap();
+        assert_eq!(output, json!({}));
+
+        let output = engine.hook_json("stop", "not json").unwrap();
+        assert_eq!(output, json!({}));
+    }
+}
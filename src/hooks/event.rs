@@ -0,0 +1,545 @@
+//! Typed hook events.
+//!
+//! Claude Code names the event in every hook's input (`hook_event_name`).
+//! [`HookEvent`] turns the flat [`HookInput`] into one variant per event,
+//! with the fields that event always carries made non-optional, so a
+//! malformed input is rejected up front instead of defaulting deep inside a
+//! handler. Each variant holds the struct its handler takes.
+
+use crate::error::{Error, Result};
+use crate::hooks::HookInput;
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Fields common to every hook event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HookCommon {
+    /// Session identifier.
+    pub session_id: String,
+
+    /// Current working directory.
+    pub cwd: PathBuf,
+
+    /// Path to conversation transcript JSON.
+    pub transcript_path: Option<PathBuf>,
+
+    /// Current permission mode.
+    pub permission_mode: Option<String>,
+}
+
+/// A hook event, keyed by `hook_event_name`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HookEvent {
+    /// A session started or resumed.
+    SessionStart(SessionStartEvent),
+
+    /// The user submitted a prompt.
+    UserPromptSubmit(UserPromptSubmitEvent),
+
+    /// A tool is about to run.
+    PreToolUse(PreToolUseEvent),
+
+    /// A tool finished.
+    PostToolUse(PostToolUseEvent),
+
+    /// The main agent is about to stop.
+    Stop(StopEvent),
+
+    /// A subagent is about to stop.
+    SubagentStop(SubagentStopEvent),
+
+    /// The session ended.
+    SessionEnd(SessionEndEvent),
+
+    /// The conversation is about to be compacted.
+    PreCompact(PreCompactEvent),
+
+    /// Claude Code sent a notification.
+    Notification(NotificationEvent),
+}
+
+/// A session started or resumed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionStartEvent {
+    /// Common fields.
+    pub common: HookCommon,
+    /// `startup`, `resume`, `clear` or `compact`.
+    pub source: String,
+    /// Model identifier.
+    pub model: Option<String>,
+}
+
+/// The user submitted a prompt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserPromptSubmitEvent {
+    /// Common fields.
+    pub common: HookCommon,
+    /// The prompt text.
+    pub prompt: String,
+}
+
+/// A tool is about to run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreToolUseEvent {
+    /// Common fields.
+    pub common: HookCommon,
+    /// Tool name.
+    pub tool_name: String,
+    /// Tool arguments.
+    pub tool_input: Value,
+}
+
+/// A tool finished.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostToolUseEvent {
+    /// Common fields.
+    pub common: HookCommon,
+    /// Tool name.
+    pub tool_name: String,
+    /// Tool arguments.
+    pub tool_input: Value,
+    /// Tool result.
+    pub tool_response: Value,
+}
+
+/// The main agent is about to stop.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StopEvent {
+    /// Common fields.
+    pub common: HookCommon,
+    /// Whether a stop hook already blocked this stop.
+    pub stop_hook_active: bool,
+    /// The agent's last message.
+    pub last_assistant_message: Option<String>,
+}
+
+/// A subagent is about to stop.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubagentStopEvent {
+    /// Common fields.
+    pub common: HookCommon,
+    /// Whether a stop hook already blocked this stop.
+    pub stop_hook_active: bool,
+    /// Agent type name (e.g. `roz:roz`).
+    pub agent_type: Option<String>,
+    /// Unique agent identifier.
+    pub agent_id: Option<String>,
+    /// Path to the subagent's transcript.
+    pub agent_transcript_path: Option<PathBuf>,
+    /// The subagent's last message.
+    pub last_assistant_message: Option<String>,
+}
+
+/// The session ended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionEndEvent {
+    /// Common fields.
+    pub common: HookCommon,
+    /// Why it ended (`clear`, `logout`, ...).
+    pub reason: String,
+}
+
+/// The conversation is about to be compacted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreCompactEvent {
+    /// Common fields.
+    pub common: HookCommon,
+    /// `manual` or `auto`.
+    pub trigger: String,
+    /// Instructions passed to `/compact`.
+    pub custom_instructions: Option<String>,
+}
+
+/// Claude Code sent a notification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotificationEvent {
+    /// Common fields.
+    pub common: HookCommon,
+    /// Notification text.
+    pub message: String,
+    /// Kind of notification.
+    pub notification_type: Option<String>,
+}
+
+// Input without `hook_event_name` comes from Claude Code versions that did not
+// always send every field, so it is dispatched by hook name with the defaults
+// roz has always used for missing fields.
+
+impl HookCommon {
+    /// The common fields of flat input.
+    #[must_use]
+    pub fn from_input(input: &HookInput) -> Self {
+        Self {
+            session_id: input.session_id.clone(),
+            cwd: input.cwd.clone(),
+            transcript_path: input.transcript_path.clone(),
+            permission_mode: input.permission_mode.clone(),
+        }
+    }
+}
+
+impl SessionStartEvent {
+    /// Build from flat input; a missing `source` is empty.
+    #[must_use]
+    pub fn from_input(input: &HookInput) -> Self {
+        Self {
+            common: HookCommon::from_input(input),
+            source: input.source.clone().unwrap_or_default(),
+            model: input.model.clone(),
+        }
+    }
+}
+
+impl UserPromptSubmitEvent {
+    /// Build from flat input; a missing `prompt` is empty.
+    #[must_use]
+    pub fn from_input(input: &HookInput) -> Self {
+        Self {
+            common: HookCommon::from_input(input),
+            prompt: input.prompt.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl PreToolUseEvent {
+    /// Build from flat input; a missing `tool_name` is `unknown` and a
+    /// missing `tool_input` null.
+    #[must_use]
+    pub fn from_input(input: &HookInput) -> Self {
+        Self {
+            common: HookCommon::from_input(input),
+            tool_name: input
+                .tool_name
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string()),
+            tool_input: input.tool_input.clone().unwrap_or(Value::Null),
+        }
+    }
+}
+
+impl StopEvent {
+    /// Build from flat input; a missing `stop_hook_active` is false.
+    #[must_use]
+    pub fn from_input(input: &HookInput) -> Self {
+        Self {
+            common: HookCommon::from_input(input),
+            stop_hook_active: input.stop_hook_active.unwrap_or(false),
+            last_assistant_message: input.last_assistant_message.clone(),
+        }
+    }
+}
+
+impl SubagentStopEvent {
+    /// Build from flat input; a missing `stop_hook_active` is false.
+    #[must_use]
+    pub fn from_input(input: &HookInput) -> Self {
+        Self {
+            common: HookCommon::from_input(input),
+            stop_hook_active: input.stop_hook_active.unwrap_or(false),
+            agent_type: input.agent_type.clone(),
+            agent_id: input.agent_id.clone(),
+            agent_transcript_path: input.agent_transcript_path.clone(),
+            last_assistant_message: input.last_assistant_message.clone(),
+        }
+    }
+}
+
+impl SessionEndEvent {
+    /// Build from flat input; a missing `reason` is `unknown`.
+    #[must_use]
+    pub fn from_input(input: &HookInput) -> Self {
+        Self {
+            common: HookCommon::from_input(input),
+            reason: input
+                .reason
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string()),
+        }
+    }
+}
+
+impl PreCompactEvent {
+    /// Build from flat input; a missing `trigger` is empty.
+    #[must_use]
+    pub fn from_input(input: &HookInput) -> Self {
+        Self {
+            common: HookCommon::from_input(input),
+            trigger: input.trigger.clone().unwrap_or_default(),
+            custom_instructions: input.custom_instructions.clone(),
+        }
+    }
+}
+
+impl NotificationEvent {
+    /// Build from flat input; a missing `message` is empty.
+    #[must_use]
+    pub fn from_input(input: &HookInput) -> Self {
+        Self {
+            common: HookCommon::from_input(input),
+            message: input.message.clone().unwrap_or_default(),
+            notification_type: input.notification_type.clone(),
+        }
+    }
+}
+
+impl HookEvent {
+    /// Every `hook_event_name` this version parses.
+    pub const NAMES: &'static [&'static str] = &[
+        "SessionStart",
+        "UserPromptSubmit",
+        "PreToolUse",
+        "PostToolUse",
+        "Stop",
+        "SubagentStop",
+        "SessionEnd",
+        "PreCompact",
+        "Notification",
+    ];
+
+    /// Whether `name` is an event this version parses.
+    #[must_use]
+    pub fn is_known(name: &str) -> bool {
+        Self::NAMES.contains(&name)
+    }
+
+    /// Parse and validate raw hook input.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is not valid JSON, lacks
+    /// `hook_event_name` or a field its event requires, or names an unknown
+    /// event.
+    pub fn parse(input_str: &str) -> Result<Self> {
+        Self::try_from(serde_json::from_str::<HookInput>(input_str)?)
+    }
+
+    /// The event's name as Claude Code sends it (e.g. `"Stop"`).
+    #[must_use]
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            Self::SessionStart(_) => "SessionStart",
+            Self::UserPromptSubmit(_) => "UserPromptSubmit",
+            Self::PreToolUse(_) => "PreToolUse",
+            Self::PostToolUse(_) => "PostToolUse",
+            Self::Stop(_) => "Stop",
+            Self::SubagentStop(_) => "SubagentStop",
+            Self::SessionEnd(_) => "SessionEnd",
+            Self::PreCompact(_) => "PreCompact",
+            Self::Notification(_) => "Notification",
+        }
+    }
+
+    /// The `roz hook` name that handles this event (e.g. `"stop"`).
+    #[must_use]
+    pub fn hook_name(&self) -> &'static str {
+        match self {
+            Self::SessionStart(_) => "session-start",
+            Self::UserPromptSubmit(_) => "user-prompt",
+            Self::PreToolUse(_) => "pre-tool-use",
+            Self::PostToolUse(_) => "post-tool-use",
+            Self::Stop(_) => "stop",
+            Self::SubagentStop(_) => "subagent-stop",
+            Self::SessionEnd(_) => "session-end",
+            Self::PreCompact(_) => "pre-compact",
+            Self::Notification(_) => "notification",
+        }
+    }
+
+    /// Fields common to every event.
+    #[must_use]
+    pub fn common(&self) -> &HookCommon {
+        match self {
+            Self::SessionStart(SessionStartEvent { common, .. })
+            | Self::UserPromptSubmit(UserPromptSubmitEvent { common, .. })
+            | Self::PreToolUse(PreToolUseEvent { common, .. })
+            | Self::PostToolUse(PostToolUseEvent { common, .. })
+            | Self::Stop(StopEvent { common, .. })
+            | Self::SubagentStop(SubagentStopEvent { common, .. })
+            | Self::SessionEnd(SessionEndEvent { common, .. })
+            | Self::PreCompact(PreCompactEvent { common, .. })
+            | Self::Notification(NotificationEvent { common, .. }) => common,
+        }
+    }
+
+    /// Session identifier.
+    #[must_use]
+    pub fn session_id(&self) -> &str {
+        &self.common().session_id
+    }
+}
+
+impl TryFrom<HookInput> for HookEvent {
+    type Error = Error;
+
+    fn try_from(input: HookInput) -> Result<Self> {
+        let name = input
+            .hook_event_name
+            .ok_or_else(|| Error::MissingField("hook_event_name".to_string()))?;
+        let required = |field: &str| Error::MissingField(format!("{field} ({name})"));
+        let common = HookCommon {
+            session_id: input.session_id,
+            cwd: input.cwd,
+            transcript_path: input.transcript_path,
+            permission_mode: input.permission_mode,
+        };
+
+        Ok(match name.as_str() {
+            "SessionStart" => Self::SessionStart(SessionStartEvent {
+                common,
+                source: input.source.ok_or_else(|| required("source"))?,
+                model: input.model,
+            }),
+            "UserPromptSubmit" => Self::UserPromptSubmit(UserPromptSubmitEvent {
+                common,
+                prompt: input.prompt.ok_or_else(|| required("prompt"))?,
+            }),
+            "PreToolUse" => Self::PreToolUse(PreToolUseEvent {
+                common,
+                tool_name: input.tool_name.ok_or_else(|| required("tool_name"))?,
+                tool_input: input.tool_input.ok_or_else(|| required("tool_input"))?,
+            }),
+            "PostToolUse" => Self::PostToolUse(PostToolUseEvent {
+                common,
+                tool_name: input.tool_name.ok_or_else(|| required("tool_name"))?,
+                tool_input: input.tool_input.ok_or_else(|| required("tool_input"))?,
+                tool_response: input
+                    .tool_response
+                    .ok_or_else(|| required("tool_response"))?,
+            }),
+            "Stop" => Self::Stop(StopEvent {
+                common,
+                stop_hook_active: input
+                    .stop_hook_active
+                    .ok_or_else(|| required("stop_hook_active"))?,
+                last_assistant_message: input.last_assistant_message,
+            }),
+            "SubagentStop" => Self::SubagentStop(SubagentStopEvent {
+                common,
+                stop_hook_active: input
+                    .stop_hook_active
+                    .ok_or_else(|| required("stop_hook_active"))?,
+                agent_type: input.agent_type,
+                agent_id: input.agent_id,
+                agent_transcript_path: input.agent_transcript_path,
+                last_assistant_message: input.last_assistant_message,
+            }),
+            "SessionEnd" => Self::SessionEnd(SessionEndEvent {
+                common,
+                reason: input.reason.ok_or_else(|| required("reason"))?,
+            }),
+            "PreCompact" => Self::PreCompact(PreCompactEvent {
+                common,
+                trigger: input.trigger.ok_or_else(|| required("trigger"))?,
+                custom_instructions: input.custom_instructions,
+            }),
+            "Notification" => Self::Notification(NotificationEvent {
+                common,
+                message: input.message.ok_or_else(|| required("message"))?,
+                notification_type: input.notification_type,
+            }),
+            other => {
+                return Err(Error::InvalidState(format!("unknown hook event: {other}")));
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_each_event() {
+        let cases = [
+            (
+                r#""hook_event_name":"SessionStart","source":"startup""#,
+                "session-start",
+            ),
+            (
+                r#""hook_event_name":"UserPromptSubmit","prompt":"hi""#,
+                "user-prompt",
+            ),
+            (
+                r#""hook_event_name":"PreToolUse","tool_name":"Bash","tool_input":{}"#,
+                "pre-tool-use",
+            ),
+            (
+                r#""hook_event_name":"PostToolUse","tool_name":"Bash","tool_input":{},"tool_response":{}"#,
+                "post-tool-use",
+            ),
+            (
+                r#""hook_event_name":"Stop","stop_hook_active":false"#,
+                "stop",
+            ),
+            (
+                r#""hook_event_name":"SubagentStop","stop_hook_active":true"#,
+                "subagent-stop",
+            ),
+            (
+                r#""hook_event_name":"SessionEnd","reason":"logout""#,
+                "session-end",
+            ),
+            (
+                r#""hook_event_name":"PreCompact","trigger":"auto""#,
+                "pre-compact",
+            ),
+            (
+                r#""hook_event_name":"Notification","message":"idle""#,
+                "notification",
+            ),
+        ];
+        for (fields, hook) in cases {
+            let json = format!(r#"{{"session_id":"ev-1","cwd":"/tmp",{fields}}}"#);
+            let event = HookEvent::parse(&json).unwrap();
+            assert_eq!(event.hook_name(), hook);
+            assert!(HookEvent::is_known(event.event_name()));
+            assert_eq!(event.session_id(), "ev-1");
+        }
+    }
+
+    #[test]
+    fn from_input_fills_missing_fields_with_old_defaults() {
+        let input: HookInput =
+            serde_json::from_str(r#"{"session_id":"ev-4","cwd":"/tmp"}"#).unwrap();
+        let stop = StopEvent::from_input(&input);
+        assert_eq!(stop.common.session_id, "ev-4");
+        assert!(!stop.stop_hook_active);
+        assert_eq!(SessionEndEvent::from_input(&input).reason, "unknown");
+        assert_eq!(PreToolUseEvent::from_input(&input).tool_name, "unknown");
+        assert_eq!(UserPromptSubmitEvent::from_input(&input).prompt, "");
+
+        // Present fields agree with the strict parse
+        let json = r#"{"session_id":"ev-4","cwd":"/tmp","hook_event_name":"Stop","stop_hook_active":true}"#;
+        let input: HookInput = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            HookEvent::parse(json).unwrap(),
+            HookEvent::Stop(StopEvent::from_input(&input))
+        );
+    }
+
+    #[test]
+    fn missing_required_field_is_reported() {
+        let json = r#"{"session_id":"ev-2","cwd":"/tmp","hook_event_name":"UserPromptSubmit"}"#;
+        let err = HookEvent::parse(json).unwrap_err();
+        assert!(matches!(err, Error::MissingField(_)));
+        assert!(err.to_string().contains("prompt (UserPromptSubmit)"));
+
+        let json = r#"{"session_id":"ev-2","cwd":"/tmp","hook_event_name":"Stop"}"#;
+        let err = HookEvent::parse(json).unwrap_err();
+        assert!(err.to_string().contains("stop_hook_active (Stop)"));
+    }
+
+    #[test]
+    fn missing_or_unknown_event_name_fails() {
+        let json = r#"{"session_id":"ev-3","cwd":"/tmp"}"#;
+        assert!(matches!(
+            HookEvent::parse(json).unwrap_err(),
+            Error::MissingField(f) if f == "hook_event_name"
+        ));
+
+        let json = r#"{"session_id":"ev-3","cwd":"/tmp","hook_event_name":"Teleport"}"#;
+        let err = HookEvent::parse(json).unwrap_err();
+        assert!(err.to_string().contains("unknown hook event: Teleport"));
+        assert!(!HookEvent::is_known("Teleport"));
+    }
+}
@@ -5,7 +5,7 @@ use serde_json::Value;
 use std::path::PathBuf;
 
 /// Input received from Claude Code hooks.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct HookInput {
     /// Session identifier.
     pub session_id: String,
@@ -72,6 +72,22 @@ pub struct HookInput {
     /// Reason for session end (for session-end hook).
     #[serde(default)]
     pub reason: Option<String>,
+
+    /// What started a compaction, `manual` or `auto` (for pre-compact hook).
+    #[serde(default)]
+    pub trigger: Option<String>,
+
+    /// Instructions passed to `/compact` (for pre-compact hook).
+    #[serde(default)]
+    pub custom_instructions: Option<String>,
+
+    /// Notification text (for notification hook).
+    #[serde(default)]
+    pub message: Option<String>,
+
+    /// Kind of notification, e.g. `permission_prompt` (for notification hook).
+    #[serde(default)]
+    pub notification_type: Option<String>,
 }
 
 #[cfg(test)]
@@ -1,9 +1,17 @@
 //! Hook input/output types and dispatch.
 
+pub mod event;
 pub mod input;
 pub mod output;
 pub mod runner;
 
+pub use event::{
+    HookCommon, HookEvent, NotificationEvent, PostToolUseEvent, PreCompactEvent, PreToolUseEvent,
+    SessionEndEvent, SessionStartEvent, StopEvent, SubagentStopEvent, UserPromptSubmitEvent,
+};
 pub use input::HookInput;
 pub use output::{ContextOutput, HookDecision, HookOutput, PermissionDecision, PreToolUseOutput};
-pub use runner::dispatch_hook;
+pub use runner::{
+    EventOutput, dispatch_event, dispatch_event_with_context, dispatch_hook,
+    dispatch_hook_with_context,
+};
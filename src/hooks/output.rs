@@ -22,6 +22,10 @@ pub struct HookOutput {
     /// Used by `SessionStart` and `UserPromptSubmit` to inject `additionalContext`.
     #[serde(rename = "hookSpecificOutput", skip_serializing_if = "Option::is_none")]
     pub hook_specific_output: Option<ContextOutput>,
+
+    /// Warning shown to the user (not the agent).
+    #[serde(rename = "systemMessage", skip_serializing_if = "Option::is_none")]
+    pub system_message: Option<String>,
 }
 
 /// Hook decision type.
@@ -54,6 +58,7 @@ impl HookOutput {
             decision: None,
             reason: None,
             hook_specific_output: None,
+            system_message: None,
         }
     }
 
@@ -64,6 +69,7 @@ impl HookOutput {
             decision: Some(HookDecision::Block),
             reason: Some(reason.to_string()),
             hook_specific_output: None,
+            system_message: None,
         }
     }
 
@@ -79,6 +85,18 @@ impl HookOutput {
                 hook_event_name: hook_event_name.to_string(),
                 additional_context: context.to_string(),
             }),
+            system_message: None,
+        }
+    }
+
+    /// Create an approve decision with a warning shown to the user.
+    #[must_use]
+    pub fn approve_with_message(message: &str) -> Self {
+        Self {
+            decision: None,
+            reason: None,
+            hook_specific_output: None,
+            system_message: Some(message.to_string()),
         }
     }
 }
@@ -1,31 +1,135 @@
 //! Hook dispatch logic.
 
 use crate::config::Config;
+use crate::core::safety::handle_error;
 use crate::core::{
-    handle_session_end, handle_session_start, handle_stop_with_config, handle_subagent_stop,
-    handle_user_prompt_with_config,
+    Context, handle_notification_with_context, handle_pre_compact_with_context,
+    handle_pre_tool_use_with_context, handle_session_end_with_context,
+    handle_session_start_with_context, handle_stop_with_context, handle_subagent_stop_with_context,
+    handle_user_prompt_with_context,
 };
-use crate::hooks::{HookInput, HookOutput};
-use crate::storage::MessageStore;
+use crate::hooks::{
+    HookEvent, HookInput, HookOutput, NotificationEvent, PreCompactEvent, PreToolUseOutput,
+    SessionEndEvent, SessionStartEvent, StopEvent, SubagentStopEvent, UserPromptSubmitEvent,
+};
+use crate::storage::{IncidentKind, MessageStore};
+use serde::Serialize;
+
+/// Output of a dispatched event: `PreToolUse` answers with a permission
+/// decision, every other event with a `HookOutput`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum EventOutput {
+    /// Output of a non-tool hook.
+    Hook(HookOutput),
+
+    /// Output of the pre-tool-use hook.
+    PreToolUse(PreToolUseOutput),
+}
+
+/// Dispatch a typed event to its handler.
+pub fn dispatch_event(event: &HookEvent, store: &dyn MessageStore, config: &Config) -> EventOutput {
+    dispatch_event_with_context(event, store, config, &Context::system())
+}
+
+/// Dispatch a typed event with an explicit clock and randomness source.
+pub fn dispatch_event_with_context(
+    event: &HookEvent,
+    store: &dyn MessageStore,
+    config: &Config,
+    ctx: &Context,
+) -> EventOutput {
+    let output = match event {
+        HookEvent::PreToolUse(event) => {
+            return EventOutput::PreToolUse(handle_pre_tool_use_with_context(
+                event, config, store, ctx,
+            ));
+        }
+        // roz does not act on tool results
+        HookEvent::PostToolUse(_) => HookOutput::approve(),
+        HookEvent::SessionStart(event) => handle_session_start_with_context(event, store, ctx),
+        HookEvent::SessionEnd(event) => handle_session_end_with_context(event, store, ctx),
+        HookEvent::UserPromptSubmit(event) => {
+            handle_user_prompt_with_context(event, store, config, ctx)
+        }
+        HookEvent::Stop(event) => handle_stop_with_context(event, store, config, ctx),
+        HookEvent::SubagentStop(event) => {
+            handle_subagent_stop_with_context(event, store, config, ctx)
+        }
+        HookEvent::PreCompact(event) => handle_pre_compact_with_context(event, store, config, ctx),
+        HookEvent::Notification(event) => {
+            handle_notification_with_context(event, store, config, ctx)
+        }
+    };
+    EventOutput::Hook(output)
+}
 
 /// Dispatch a hook by name.
 ///
-/// Returns the appropriate `HookOutput` for the given hook.
+/// Returns the appropriate `HookOutput` for the given hook. Used for input
+/// without a `hook_event_name`, which is read into the hook's event with the
+/// old defaults for missing fields; see [`dispatch_event`] otherwise.
 pub fn dispatch_hook(
     name: &str,
     input: &HookInput,
     store: &dyn MessageStore,
     config: &Config,
+) -> HookOutput {
+    dispatch_hook_with_context(name, input, store, config, &Context::system())
+}
+
+/// Dispatch a hook by name with an explicit clock and randomness source.
+pub fn dispatch_hook_with_context(
+    name: &str,
+    input: &HookInput,
+    store: &dyn MessageStore,
+    config: &Config,
+    ctx: &Context,
 ) -> HookOutput {
     match name {
-        "session-start" => handle_session_start(input, store),
-        "session-end" => handle_session_end(input, store),
-        "user-prompt" => handle_user_prompt_with_config(input, store, config),
-        "stop" => handle_stop_with_config(input, store, config),
-        "subagent-stop" => handle_subagent_stop(input, store),
+        "session-start" => {
+            handle_session_start_with_context(&SessionStartEvent::from_input(input), store, ctx)
+        }
+        "session-end" => {
+            handle_session_end_with_context(&SessionEndEvent::from_input(input), store, ctx)
+        }
+        "user-prompt" => handle_user_prompt_with_context(
+            &UserPromptSubmitEvent::from_input(input),
+            store,
+            config,
+            ctx,
+        ),
+        "stop" => handle_stop_with_context(&StopEvent::from_input(input), store, config, ctx),
+        "subagent-stop" => handle_subagent_stop_with_context(
+            &SubagentStopEvent::from_input(input),
+            store,
+            config,
+            ctx,
+        ),
+        "pre-compact" => {
+            handle_pre_compact_with_context(&PreCompactEvent::from_input(input), store, config, ctx)
+        }
+        "notification" => handle_notification_with_context(
+            &NotificationEvent::from_input(input),
+            store,
+            config,
+            ctx,
+        ),
         _ => {
-            eprintln!("roz: warning: unknown hook: {name}");
-            HookOutput::approve() // Fail open for unknown hooks
+            // An unrecognized name may be a misconfigured Stop hook, so it
+            // follows the safety mode like any other error
+            match handle_error(
+                store,
+                config,
+                name,
+                Some(input.into()),
+                IncidentKind::UnknownHook,
+                &format!("unknown hook: {name}"),
+                ctx,
+            ) {
+                Some(diagnostic) => HookOutput::block(&diagnostic),
+                None => HookOutput::approve(), // Fail open for unknown hooks
+            }
         }
     }
 }
@@ -55,6 +159,10 @@ mod tests {
             last_assistant_message: None,
             stop_hook_active: None,
             reason: None,
+            trigger: None,
+            custom_instructions: None,
+            message: None,
+            notification_type: None,
         }
     }
 
@@ -135,6 +243,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dispatch_event_uses_event_not_name() {
+        let store = MemoryBackend::new();
+        let config = Config::default();
+        let event = HookEvent::parse(
+            r##"{"session_id":"test-event","cwd":"/tmp","hook_event_name":"UserPromptSubmit","prompt":"#roz go"}"##,
+        )
+        .unwrap();
+        dispatch_event(&event, &store, &config);
+        assert!(
+            store
+                .get_session("test-event")
+                .unwrap()
+                .unwrap()
+                .review
+                .enabled
+        );
+
+        let event = HookEvent::parse(
+            r#"{"session_id":"test-event","cwd":"/tmp","hook_event_name":"Stop","stop_hook_active":false}"#,
+        )
+        .unwrap();
+        let EventOutput::Hook(output) = dispatch_event(&event, &store, &config) else {
+            panic!("expected hook output");
+        };
+        assert!(matches!(
+            output.decision,
+            Some(crate::hooks::HookDecision::Block)
+        ));
+
+        let event = HookEvent::parse(
+            r#"{"session_id":"test-event","cwd":"/tmp","hook_event_name":"PreToolUse","tool_name":"Read","tool_input":{}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            dispatch_event(&event, &store, &config),
+            EventOutput::PreToolUse(_)
+        ));
+    }
+
     #[test]
     fn dispatch_unknown_hook() {
         let store = MemoryBackend::new();
@@ -147,5 +295,25 @@ mod tests {
             output.decision.is_none(),
             "expected approve (decision=None)"
         );
+
+        let incidents = store.incidents_since(chrono::DateTime::UNIX_EPOCH).unwrap();
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(incidents[0].kind, crate::storage::IncidentKind::UnknownHook);
+        assert_eq!(incidents[0].hook, "unknown");
+    }
+
+    #[test]
+    fn dispatch_unknown_hook_fail_closed_blocks() {
+        let store = MemoryBackend::new();
+        let mut config = Config::default();
+        config.safety.mode = crate::config::SafetyMode::FailClosed;
+        let input = make_input("test-123");
+
+        let output = dispatch_hook("stpo", &input, &store, &config);
+        assert!(matches!(
+            output.decision,
+            Some(crate::hooks::HookDecision::Block)
+        ));
+        assert!(output.reason.unwrap().contains("unknown hook: stpo"));
     }
 }
@@ -6,10 +6,13 @@
 pub mod cli;
 pub mod config;
 pub mod core;
+pub mod engine;
 pub mod error;
 pub mod hooks;
 pub mod storage;
 pub mod template;
+pub mod transcript;
 
 pub use config::Config;
+pub use engine::{EventSink, RozEngine, RozEngineBuilder};
 pub use error::{Error, Result};
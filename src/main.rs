@@ -1,7 +1,9 @@
 //! roz CLI - Quality gate for Claude Code.
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use roz::cli;
+use roz::cli::format::OutputFormat;
+use std::path::PathBuf;
 use std::process::ExitCode;
 
 /// Get the version string.
@@ -26,19 +28,41 @@ fn version() -> &'static str {
     })
 }
 
+/// Settings scope for install/uninstall (`--user` is the default).
+fn install_scope(project: bool) -> cli::install::InstallScope {
+    if project {
+        cli::install::InstallScope::Project
+    } else {
+        cli::install::InstallScope::User
+    }
+}
+
+/// Session for `roz tail`: the given ID, or the latest in the current directory.
+fn tail_target(session_id: Option<String>) -> roz::Result<cli::tail::TailTarget> {
+    match session_id {
+        Some(id) => Ok(cli::tail::TailTarget::Session(id)),
+        None => Ok(cli::tail::TailTarget::Cwd(std::env::current_dir()?)),
+    }
+}
+
 #[derive(Parser)]
 #[command(name = "roz")]
 #[command(author, version = version(), about = "Quality gate for Claude Code", long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Output format for reporting commands (list, status, breaker status, doctor, trace, search, tail, watch, replay, context, stats, clean).
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    format: OutputFormat,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// [Internal] Run a hook (JSON stdin/stdout). Called by Claude Code hooks.
     Hook {
-        /// Hook name (session-start, user-prompt, stop, subagent-stop).
+        /// Hook name (session-start, user-prompt, pre-tool-use, stop, subagent-stop,
+        /// session-end, pre-compact, notification).
         name: String,
     },
 
@@ -66,6 +90,69 @@ enum Commands {
     Context {
         /// Session ID.
         session_id: String,
+
+        /// Include user turns, the final assistant message and the tool calls
+        /// of the review cycle from the session's transcript.
+        #[arg(long)]
+        transcript: bool,
+    },
+
+    /// [User] Approve a session as a human, overriding the reviewer.
+    Approve {
+        /// Session ID.
+        session_id: String,
+
+        /// Why the work is approved (recorded in the audit trail).
+        #[arg(long)]
+        reason: String,
+    },
+
+    /// [User] Reject a session as a human, sending a message to the agent.
+    Reject {
+        /// Session ID.
+        session_id: String,
+
+        /// What the agent needs to fix.
+        #[arg(long)]
+        message: String,
+    },
+
+    /// [User] Inspect, reset or trip a session's circuit breaker.
+    Breaker {
+        #[command(subcommand)]
+        command: BreakerCommand,
+    },
+
+    /// [User] Add roz hooks to Claude Code settings.json.
+    Install {
+        /// Install into .claude/settings.json in the current directory.
+        #[arg(long, conflicts_with = "user")]
+        project: bool,
+
+        /// Install into ~/.claude/settings.json (default).
+        #[arg(long)]
+        user: bool,
+    },
+
+    /// [User] Remove roz hooks from Claude Code settings.json.
+    Uninstall {
+        /// Remove from .claude/settings.json in the current directory.
+        #[arg(long, conflicts_with = "user")]
+        project: bool,
+
+        /// Remove from ~/.claude/settings.json (default).
+        #[arg(long)]
+        user: bool,
+    },
+
+    /// [User] Check the roz installation and run a synthetic review.
+    Doctor,
+
+    /// [User] Show review state for the current project's session.
+    Status {
+        /// Session ID. Defaults to the latest session started in this directory.
+        #[arg(long)]
+        session: Option<String>,
     },
 
     /// [User] List recent sessions.
@@ -89,6 +176,81 @@ enum Commands {
         /// Show verbose output with payloads.
         #[arg(short, long)]
         verbose: bool,
+
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
+
+    /// [User] Find trace events across all sessions.
+    Search {
+        #[command(flatten)]
+        filter: FilterArgs,
+
+        /// Only sessions recorded in this directory or below it.
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+
+        /// Keep only the most recent N matches.
+        #[arg(short, long)]
+        limit: Option<usize>,
+    },
+
+    /// [User] Follow a session's trace events as the hooks write them.
+    Tail {
+        /// Session ID.
+        #[arg(required_unless_present = "cwd", conflicts_with = "cwd")]
+        session_id: Option<String>,
+
+        /// Follow the latest session started in this directory.
+        #[arg(long)]
+        cwd: bool,
+
+        /// Only show these event types, comma-separated (e.g. `gate_blocked,roz_decision`).
+        #[arg(long = "type", value_delimiter = ',')]
+        types: Vec<String>,
+
+        /// Only show events whose payload contains this text.
+        #[arg(long)]
+        grep: Option<String>,
+
+        /// Number of existing events to show first.
+        #[arg(short = 'n', long, default_value = "10")]
+        lines: usize,
+
+        /// Poll interval in milliseconds.
+        #[arg(long, default_value = "500")]
+        interval_ms: u64,
+    },
+
+    /// [User] Live table of active sessions.
+    Watch {
+        /// Sessions updated within this duration count as active (e.g., "1h", "30m").
+        #[arg(long, default_value = "1h")]
+        active: String,
+
+        /// Refresh interval in milliseconds.
+        #[arg(long, default_value = "1000")]
+        interval_ms: u64,
+    },
+
+    /// [User] Export a session's hook recording as a replay fixture.
+    Record {
+        /// Session ID.
+        session_id: String,
+
+        /// Write the fixture to this file instead of stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// [User] Replay a recorded fixture and report diverging outputs.
+    Replay {
+        /// Fixture file from `roz record`.
+        fixture: PathBuf,
+
+        /// Config file to replay with instead of ~/.roz/config.toml.
+        #[arg(long)]
+        config: Option<PathBuf>,
     },
 
     /// [User] Remove old sessions.
@@ -107,12 +269,73 @@ enum Commands {
         /// Number of days to look back. Defaults to 30.
         #[arg(long, default_value = "30")]
         days: u32,
+
+        /// Resolved attempts each template needs before a winner is named.
+        #[arg(long, default_value = "30")]
+        min_samples: u32,
+    },
+}
+
+/// Trace event filters shared by `trace` and `search`.
+#[derive(Args)]
+struct FilterArgs {
+    /// Only these event types, comma-separated (e.g. `gate_blocked,roz_decision`).
+    #[arg(long = "type", value_delimiter = ',')]
+    types: Vec<String>,
+
+    /// Only events at or after this time (RFC 3339, YYYY-MM-DD, or a duration ago like "7d").
+    #[arg(long)]
+    since: Option<String>,
+
+    /// Only events at or before this time (same formats as --since).
+    #[arg(long)]
+    until: Option<String>,
+
+    /// Only events whose payload contains this text.
+    #[arg(long)]
+    grep: Option<String>,
+}
+
+impl FilterArgs {
+    fn parse(&self) -> roz::Result<cli::trace::TraceFilter> {
+        cli::trace::TraceFilter::parse(
+            &self.types,
+            self.since.as_deref(),
+            self.until.as_deref(),
+            self.grep.as_deref(),
+        )
+    }
+}
+
+#[derive(Subcommand)]
+enum BreakerCommand {
+    /// Show the circuit breaker state.
+    Status {
+        /// Session ID. Defaults to the latest session started in this directory.
+        session_id: Option<String>,
+    },
+
+    /// Reset the circuit breaker and gate breakers without waiting for the cooldown.
+    Reset {
+        /// Session ID.
+        session_id: String,
+    },
+
+    /// Trip the circuit breaker by hand.
+    Trip {
+        /// Session ID.
+        session_id: String,
+
+        /// Why the breaker is tripped (recorded in the trace).
+        #[arg(long)]
+        reason: String,
     },
 }
 
 fn main() -> ExitCode {
     let cli = Cli::parse();
 
+    let format = cli.format;
     let result = match cli.command {
         Commands::Hook { name } => cli::hook::run(&name),
         Commands::Decide {
@@ -128,15 +351,59 @@ fn main() -> ExitCode {
             message.as_deref(),
             opinions.as_deref(),
         ),
-        Commands::Context { session_id } => cli::context::run(&session_id),
-        Commands::List { limit } => cli::list::run(limit),
+        Commands::Context {
+            session_id,
+            transcript,
+        } => cli::context::run(&session_id, transcript, format),
+        Commands::Approve { session_id, reason } => cli::approve::approve(&session_id, &reason),
+        Commands::Reject {
+            session_id,
+            message,
+        } => cli::approve::reject(&session_id, &message),
+        Commands::Breaker { command } => match command {
+            BreakerCommand::Status { session_id } => {
+                cli::breaker::status(session_id.as_deref(), format)
+            }
+            BreakerCommand::Reset { session_id } => cli::breaker::reset(&session_id),
+            BreakerCommand::Trip { session_id, reason } => cli::breaker::trip(&session_id, &reason),
+        },
+        Commands::Install { project, .. } => cli::install::install(install_scope(project)),
+        Commands::Uninstall { project, .. } => cli::install::uninstall(install_scope(project)),
+        Commands::Doctor => cli::doctor::run(format),
+        Commands::Status { session } => cli::status::run(session.as_deref(), format),
+        Commands::List { limit } => cli::list::run(limit, format),
         Commands::Debug { session_id } => cli::debug::run(&session_id),
         Commands::Trace {
             session_id,
             verbose,
-        } => cli::trace::run(&session_id, verbose),
-        Commands::Clean { before, all } => cli::clean::run(&before, all),
-        Commands::Stats { days } => cli::stats::run(days),
+            filter,
+        } => filter
+            .parse()
+            .and_then(|filter| cli::trace::run(&session_id, &filter, verbose, format)),
+        Commands::Search { filter, cwd, limit } => filter
+            .parse()
+            .and_then(|filter| cli::search::run(&filter, cwd.as_deref(), limit, format)),
+        Commands::Tail {
+            session_id,
+            cwd: _,
+            types,
+            grep,
+            lines,
+            interval_ms,
+        } => tail_target(session_id).and_then(|target| {
+            let filter = cli::trace::TraceFilter::parse(&types, None, None, grep.as_deref())?;
+            cli::tail::run(target, filter, lines, interval_ms, format)
+        }),
+        Commands::Watch {
+            active,
+            interval_ms,
+        } => cli::watch::run(&active, interval_ms, format),
+        Commands::Record { session_id, output } => cli::record::run(&session_id, output.as_deref()),
+        Commands::Replay { fixture, config } => {
+            cli::replay::run(&fixture, config.as_deref(), format)
+        }
+        Commands::Clean { before, all } => cli::clean::run(&before, all, format),
+        Commands::Stats { days, min_samples } => cli::stats::run(days, min_samples, format),
     };
 
     match result {
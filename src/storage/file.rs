@@ -1,10 +1,33 @@
 //! File-based storage backend.
 
+use crate::cli::record::recording_path;
 use crate::core::SessionState;
 use crate::error::{Error, Result};
-use crate::storage::traits::{MessageStore, SessionSummary};
-use std::fs;
-use std::path::PathBuf;
+use crate::storage::traits::{Incident, MessageStore, SessionSummary, UnknownDecision};
+use chrono::{DateTime, Utc};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Maximum number of unknown-session decisions kept on disk.
+const MAX_UNKNOWN_DECISIONS: usize = 100;
+
+/// Maximum number of incidents kept on disk.
+const MAX_INCIDENTS: usize = 1000;
+
+/// Lower bound on the size of one serialized incident. The timestamp, hook
+/// and kind alone take more than this.
+const MIN_INCIDENT_BYTES: u64 = 64;
+
+/// File name of the global incident log.
+const INCIDENTS_FILE: &str = "incidents.jsonl";
+
+/// How long to wait for another process to release a lock file.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Age after which a lock file is assumed to belong to a crashed process.
+const LOCK_STALE_AFTER: Duration = Duration::from_secs(30);
 
 /// File-based storage backend with atomic writes.
 #[derive(Debug)]
@@ -32,6 +55,37 @@ impl FileBackend {
             .join(format!("{session_id}.json"))
     }
 
+    /// Get the path to the unknown-session decision log.
+    fn unknown_decisions_path(&self) -> PathBuf {
+        self.base_dir.join("unknown_decisions.jsonl")
+    }
+
+    /// Read all unknown-session decisions, skipping malformed lines.
+    fn read_unknown_decisions(&self) -> Result<Vec<UnknownDecision>> {
+        let path = self.unknown_decisions_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let contents = fs::read_to_string(&path)?;
+        Ok(contents
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// Read all incidents, skipping malformed lines.
+    fn read_incidents(&self) -> Result<Vec<Incident>> {
+        let path = self.base_dir.join(INCIDENTS_FILE);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let contents = fs::read_to_string(&path)?;
+        Ok(contents
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
     /// Validate that a session ID is safe to use as a filename.
     ///
     /// Returns true if the `session_id`:
@@ -39,7 +93,7 @@ impl FileBackend {
     /// - Contains only alphanumeric characters, hyphens, and underscores
     /// - Does not start with a dot (hidden files)
     /// - Does not contain path separators
-    fn is_valid_session_id(session_id: &str) -> bool {
+    pub(crate) fn is_valid_session_id(session_id: &str) -> bool {
         !session_id.is_empty()
             && !session_id.starts_with('.')
             && session_id
@@ -108,7 +162,9 @@ impl MessageStore for FileBackend {
                             session_id: state.session_id,
                             first_prompt: state.review.user_prompts.first().cloned(),
                             created_at: state.created_at,
+                            updated_at: state.updated_at,
                             event_count: state.trace.len(),
+                            cwd: state.cwd,
                         });
                     }
                 }
@@ -116,11 +172,33 @@ impl MessageStore for FileBackend {
         }
 
         // Sort by created_at descending (most recent first)
-        sessions.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.created_at));
         sessions.truncate(limit);
         Ok(sessions)
     }
 
+    fn sessions_updated_since(&self, since: DateTime<Utc>) -> Result<Vec<String>> {
+        // File modification times stand in for `updated_at`, so only the
+        // directory is read. A write always lands after the `updated_at` it
+        // stores, so no updated session is missed; sessions saved without
+        // bumping `updated_at`, and unparseable files, are included too.
+        let since = SystemTime::from(since);
+        let mut ids = Vec::new();
+        for entry in fs::read_dir(self.base_dir.join("sessions"))? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|e| e != "json") {
+                continue;
+            }
+            let modified = fs::metadata(&path).and_then(|meta| meta.modified());
+            if modified.is_ok_and(|modified| modified >= since) {
+                if let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) {
+                    ids.push(id.to_string());
+                }
+            }
+        }
+        Ok(ids)
+    }
+
     fn delete_session(&self, session_id: &str) -> Result<()> {
         // Validate session_id to prevent path traversal
         if !Self::is_valid_session_id(session_id) {
@@ -133,8 +211,141 @@ impl MessageStore for FileBackend {
         if path.exists() {
             fs::remove_file(&path)?;
         }
+
+        // A recording of a session that no longer exists cannot be replayed
+        match fs::remove_file(recording_path(&self.base_dir, session_id)) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn record_unknown_decision(&self, record: &UnknownDecision) -> Result<()> {
+        // Concurrent hooks read, trim and rewrite the same log, so serialize
+        // them or one would drop the other's entry
+        let _lock = LockFile::acquire(&self.unknown_decisions_path().with_extension("lock"))?;
+        let mut records = self.read_unknown_decisions()?;
+        records.push(record.clone());
+
+        // Keep the log bounded - only recent entries matter for classification
+        if records.len() > MAX_UNKNOWN_DECISIONS {
+            let excess = records.len() - MAX_UNKNOWN_DECISIONS;
+            records.drain(0..excess);
+        }
+
+        let mut contents = String::new();
+        for r in &records {
+            contents.push_str(&serde_json::to_string(r)?);
+            contents.push('\n');
+        }
+
+        let path = self.unknown_decisions_path();
+        let temp = path.with_extension(format!("{}.tmp", std::process::id()));
+        fs::write(&temp, &contents)?;
+        fs::rename(&temp, &path)?;
+
+        Ok(())
+    }
+
+    fn unknown_decisions_since(&self, since: DateTime<Utc>) -> Result<Vec<UnknownDecision>> {
+        Ok(self
+            .read_unknown_decisions()?
+            .into_iter()
+            .filter(|r| r.timestamp >= since)
+            .collect())
+    }
+
+    fn record_incident(&self, incident: &Incident) -> Result<()> {
+        append_incident(&self.base_dir, incident)?;
+
+        // Trim only once the log is well over the limit. The file size bounds
+        // the line count, so most failures cost an append and a stat rather
+        // than parsing the whole log
+        let size = fs::metadata(self.base_dir.join(INCIDENTS_FILE))?.len();
+        if size <= (MAX_INCIDENTS * 2) as u64 * MIN_INCIDENT_BYTES {
+            return Ok(());
+        }
+        let incidents = self.read_incidents()?;
+        if incidents.len() > MAX_INCIDENTS * 2 {
+            let mut contents = String::new();
+            for i in &incidents[incidents.len() - MAX_INCIDENTS..] {
+                contents.push_str(&serde_json::to_string(i)?);
+                contents.push('\n');
+            }
+            let path = self.base_dir.join(INCIDENTS_FILE);
+            let temp = path.with_extension("tmp");
+            fs::write(&temp, &contents)?;
+            fs::rename(&temp, &path)?;
+        }
+
         Ok(())
     }
+
+    fn incidents_since(&self, since: DateTime<Utc>) -> Result<Vec<Incident>> {
+        Ok(self
+            .read_incidents()?
+            .into_iter()
+            .filter(|i| i.timestamp >= since)
+            .collect())
+    }
+}
+
+/// An exclusive lock held by creating a file, removed on drop.
+///
+/// A lock file left behind by a crashed process is broken once it is older
+/// than `LOCK_STALE_AFTER`.
+struct LockFile(PathBuf);
+
+impl LockFile {
+    fn acquire(path: &Path) -> Result<Self> {
+        let start = Instant::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(_) => return Ok(Self(path.to_path_buf())),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e.into()),
+            }
+            let stale = fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.elapsed().ok())
+                .is_some_and(|age| age > LOCK_STALE_AFTER);
+            if stale {
+                let _ = fs::remove_file(path);
+            } else if start.elapsed() > LOCK_TIMEOUT {
+                return Err(Error::Storage(std::io::Error::new(
+                    ErrorKind::TimedOut,
+                    format!("timed out waiting for {}", path.display()),
+                )));
+            } else {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Append an incident to `base_dir/incidents.jsonl`.
+///
+/// Usable without a `FileBackend`, for failures that happen before one exists.
+///
+/// # Errors
+///
+/// Returns an error if the log cannot be written.
+pub fn append_incident(base_dir: &Path, incident: &Incident) -> Result<()> {
+    fs::create_dir_all(base_dir)?;
+    let mut line = serde_json::to_string(incident)?;
+    line.push('\n');
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(base_dir.join(INCIDENTS_FILE))?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
 }
 
 /// Get the default roz home directory.
@@ -154,6 +365,7 @@ pub fn get_roz_home() -> PathBuf {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::IncidentKind;
     use tempfile::TempDir;
 
     fn create_test_backend() -> (FileBackend, TempDir) {
@@ -236,6 +448,29 @@ mod tests {
         assert_eq!(sessions[0].session_id, "session-1");
     }
 
+    #[test]
+    fn sessions_updated_since_uses_file_mtime() {
+        let (store, temp_dir) = create_test_backend();
+        let sessions = temp_dir.path().join("sessions");
+        let before = Utc::now() - chrono::Duration::seconds(5);
+
+        store.put_session(&SessionState::new("fresh")).unwrap();
+        store.put_session(&SessionState::new("old")).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(sessions.join("old.json"))
+            .unwrap()
+            .set_modified(SystemTime::from(before - chrono::Duration::hours(1)))
+            .unwrap();
+        // Not parsed, so even a corrupted session counts
+        fs::write(sessions.join("broken.json"), "{ not json").unwrap();
+        fs::write(sessions.join("orphan.tmp"), "{}").unwrap();
+
+        let mut ids = store.sessions_updated_since(before).unwrap();
+        ids.sort();
+        assert_eq!(ids, ["broken", "fresh"]);
+    }
+
     #[test]
     fn delete_session_removes_file() {
         let (store, temp_dir) = create_test_backend();
@@ -250,6 +485,20 @@ mod tests {
         assert!(!path.exists());
     }
 
+    #[test]
+    fn delete_session_removes_recording() {
+        let (store, temp_dir) = create_test_backend();
+        let recording = recording_path(temp_dir.path(), "test-123");
+        let kept = recording_path(temp_dir.path(), "test-456");
+        fs::create_dir_all(recording.parent().unwrap()).unwrap();
+        fs::write(&recording, "{}\n").unwrap();
+        fs::write(&kept, "{}\n").unwrap();
+
+        store.delete_session("test-123").unwrap();
+        assert!(!recording.exists());
+        assert!(kept.exists());
+    }
+
     #[test]
     fn delete_nonexistent_session_succeeds() {
         let (store, _temp) = create_test_backend();
@@ -390,6 +639,147 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unknown_decisions_round_trip() {
+        let (store, _temp) = create_test_backend();
+        let now = Utc::now();
+
+        store
+            .record_unknown_decision(&UnknownDecision {
+                session_id: "../not-a-session".to_string(),
+                timestamp: now,
+                cwd: None,
+            })
+            .unwrap();
+
+        let records = store
+            .unknown_decisions_since(now - chrono::Duration::seconds(1))
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].session_id, "../not-a-session");
+        assert!(
+            store
+                .unknown_decisions_since(now + chrono::Duration::seconds(1))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn concurrent_unknown_decisions_are_all_kept() {
+        let (store, temp) = create_test_backend();
+        let now = Utc::now();
+
+        std::thread::scope(|s| {
+            for t in 0..4 {
+                let store = &store;
+                s.spawn(move || {
+                    for i in 0..10 {
+                        store
+                            .record_unknown_decision(&UnknownDecision {
+                                session_id: format!("bad-{t}-{i}"),
+                                timestamp: now,
+                                cwd: None,
+                            })
+                            .unwrap();
+                    }
+                });
+            }
+        });
+
+        let records = store
+            .unknown_decisions_since(now - chrono::Duration::seconds(1))
+            .unwrap();
+        assert_eq!(records.len(), 40);
+        assert!(!temp.path().join("unknown_decisions.lock").exists());
+    }
+
+    #[test]
+    fn unknown_decisions_break_a_stale_lock() {
+        let (store, temp) = create_test_backend();
+        let lock = temp.path().join("unknown_decisions.lock");
+        let file = fs::File::create(&lock).unwrap();
+        file.set_modified(SystemTime::now() - LOCK_STALE_AFTER * 2)
+            .unwrap();
+
+        store
+            .record_unknown_decision(&UnknownDecision {
+                session_id: "bad".to_string(),
+                timestamp: Utc::now(),
+                cwd: None,
+            })
+            .unwrap();
+        assert!(!lock.exists());
+    }
+
+    #[test]
+    fn unknown_decisions_log_is_bounded() {
+        let (store, _temp) = create_test_backend();
+        let now = Utc::now();
+
+        for i in 0..(MAX_UNKNOWN_DECISIONS + 5) {
+            store
+                .record_unknown_decision(&UnknownDecision {
+                    session_id: format!("bad-{i}"),
+                    timestamp: now,
+                    cwd: None,
+                })
+                .unwrap();
+        }
+
+        let records = store
+            .unknown_decisions_since(now - chrono::Duration::seconds(1))
+            .unwrap();
+        assert_eq!(records.len(), MAX_UNKNOWN_DECISIONS);
+        assert_eq!(records[0].session_id, "bad-5");
+    }
+
+    fn test_incident(session_id: &str) -> Incident {
+        Incident {
+            timestamp: Utc::now(),
+            hook: "stop".to_string(),
+            kind: IncidentKind::StorageWrite,
+            session_id: Some(session_id.to_string()),
+            message: "failed to save state".to_string(),
+            blocked: false,
+        }
+    }
+
+    #[test]
+    fn incidents_round_trip() {
+        let (store, temp) = create_test_backend();
+        let now = Utc::now();
+
+        store.record_incident(&test_incident("s1")).unwrap();
+        append_incident(temp.path(), &test_incident("s2")).unwrap();
+
+        let incidents = store
+            .incidents_since(now - chrono::Duration::seconds(1))
+            .unwrap();
+        assert_eq!(incidents.len(), 2);
+        assert_eq!(incidents[0].session_id.as_deref(), Some("s1"));
+        assert_eq!(incidents[1].kind, IncidentKind::StorageWrite);
+        assert!(temp.path().join(INCIDENTS_FILE).exists());
+    }
+
+    #[test]
+    fn incidents_log_is_bounded() {
+        let (store, _temp) = create_test_backend();
+
+        for i in 0..=(MAX_INCIDENTS * 2) {
+            store
+                .record_incident(&test_incident(&format!("s{i}")))
+                .unwrap();
+        }
+
+        let incidents = store.incidents_since(DateTime::UNIX_EPOCH).unwrap();
+        assert_eq!(incidents.len(), MAX_INCIDENTS);
+        assert_eq!(
+            incidents.last().unwrap().session_id.as_deref(),
+            Some(format!("s{}", MAX_INCIDENTS * 2).as_str())
+        );
+    }
+
     // ========================================================================
     // Session ID Validation Tests
     // ========================================================================
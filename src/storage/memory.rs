@@ -2,7 +2,8 @@
 
 use crate::core::SessionState;
 use crate::error::Result;
-use crate::storage::traits::{MessageStore, SessionSummary};
+use crate::storage::traits::{Incident, MessageStore, SessionSummary, UnknownDecision};
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use std::sync::RwLock;
 
@@ -10,6 +11,8 @@ use std::sync::RwLock;
 #[derive(Debug, Default)]
 pub struct MemoryBackend {
     sessions: RwLock<HashMap<String, SessionState>>,
+    unknown_decisions: RwLock<Vec<UnknownDecision>>,
+    incidents: RwLock<Vec<Incident>>,
 }
 
 impl MemoryBackend {
@@ -40,12 +43,14 @@ impl MessageStore for MemoryBackend {
                 session_id: state.session_id.clone(),
                 first_prompt: state.review.user_prompts.first().cloned(),
                 created_at: state.created_at,
+                updated_at: state.updated_at,
                 event_count: state.trace.len(),
+                cwd: state.cwd.clone(),
             })
             .collect();
 
         // Sort by created_at descending (most recent first)
-        summaries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.created_at));
         summaries.truncate(limit);
         Ok(summaries)
     }
@@ -55,6 +60,36 @@ impl MessageStore for MemoryBackend {
         sessions.remove(session_id);
         Ok(())
     }
+
+    fn record_unknown_decision(&self, record: &UnknownDecision) -> Result<()> {
+        let mut records = self.unknown_decisions.write().unwrap();
+        records.push(record.clone());
+        Ok(())
+    }
+
+    fn unknown_decisions_since(&self, since: DateTime<Utc>) -> Result<Vec<UnknownDecision>> {
+        let records = self.unknown_decisions.read().unwrap();
+        Ok(records
+            .iter()
+            .filter(|r| r.timestamp >= since)
+            .cloned()
+            .collect())
+    }
+
+    fn record_incident(&self, incident: &Incident) -> Result<()> {
+        let mut incidents = self.incidents.write().unwrap();
+        incidents.push(incident.clone());
+        Ok(())
+    }
+
+    fn incidents_since(&self, since: DateTime<Utc>) -> Result<Vec<Incident>> {
+        let incidents = self.incidents.read().unwrap();
+        Ok(incidents
+            .iter()
+            .filter(|i| i.timestamp >= since)
+            .cloned()
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -79,6 +114,20 @@ mod tests {
         assert_eq!(retrieved.session_id, "test-123");
     }
 
+    #[test]
+    fn sessions_updated_since_filters_on_updated_at() {
+        let store = MemoryBackend::new();
+        let now = Utc::now();
+        let mut old = SessionState::new("old");
+        old.updated_at = now - chrono::Duration::hours(1);
+        store.put_session(&old).unwrap();
+        let mut fresh = SessionState::new("fresh");
+        fresh.updated_at = now;
+        store.put_session(&fresh).unwrap();
+
+        assert_eq!(store.sessions_updated_since(now).unwrap(), ["fresh"]);
+    }
+
     #[test]
     fn list_sessions_empty() {
         let store = MemoryBackend::new();
@@ -130,6 +179,63 @@ mod tests {
         store.delete_session("nonexistent").unwrap();
     }
 
+    #[test]
+    fn unknown_decisions_filtered_by_time() {
+        let store = MemoryBackend::new();
+        let now = Utc::now();
+
+        store
+            .record_unknown_decision(&UnknownDecision {
+                session_id: "old".to_string(),
+                timestamp: now - chrono::Duration::hours(1),
+                cwd: None,
+            })
+            .unwrap();
+        store
+            .record_unknown_decision(&UnknownDecision {
+                session_id: "new".to_string(),
+                timestamp: now,
+                cwd: None,
+            })
+            .unwrap();
+
+        let recent = store
+            .unknown_decisions_since(now - chrono::Duration::minutes(1))
+            .unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].session_id, "new");
+    }
+
+    #[test]
+    fn incidents_filtered_by_time() {
+        use crate::storage::{Incident, IncidentKind};
+
+        let store = MemoryBackend::new();
+        let now = Utc::now();
+
+        for (hook, timestamp) in [
+            ("stop", now - chrono::Duration::hours(1)),
+            ("pre-tool-use", now),
+        ] {
+            store
+                .record_incident(&Incident {
+                    timestamp,
+                    hook: hook.to_string(),
+                    kind: IncidentKind::StorageRead,
+                    session_id: None,
+                    message: "storage error".to_string(),
+                    blocked: false,
+                })
+                .unwrap();
+        }
+
+        let recent = store
+            .incidents_since(now - chrono::Duration::minutes(1))
+            .unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].hook, "pre-tool-use");
+    }
+
     #[test]
     fn concurrent_reads() {
         use std::sync::Arc;
@@ -6,4 +6,4 @@ pub mod traits;
 
 pub use file::FileBackend;
 pub use memory::MemoryBackend;
-pub use traits::{MessageStore, SessionSummary};
+pub use traits::{Incident, IncidentKind, MessageStore, SessionSummary, UnknownDecision};
@@ -3,6 +3,8 @@
 use crate::core::SessionState;
 use crate::error::Result;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 /// Storage backend for session state.
 pub trait MessageStore: Send + Sync {
@@ -27,16 +29,146 @@ pub trait MessageStore: Send + Sync {
     /// Returns an error if the storage operation fails.
     fn list_sessions(&self, limit: usize) -> Result<Vec<SessionSummary>>;
 
+    /// IDs of sessions updated at or after `since`, in no particular order.
+    ///
+    /// The default filters [`list_sessions`](Self::list_sessions) by
+    /// `updated_at`. Backends may answer from cheaper metadata for polling, as
+    /// long as they never leave a session out; extra sessions are allowed, so
+    /// callers that need the exact cut re-check `updated_at`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage operation fails.
+    fn sessions_updated_since(&self, since: DateTime<Utc>) -> Result<Vec<String>> {
+        Ok(self
+            .list_sessions(usize::MAX)?
+            .into_iter()
+            .filter(|summary| summary.updated_at >= since)
+            .map(|summary| summary.session_id)
+            .collect())
+    }
+
     /// Delete a session.
     ///
     /// # Errors
     ///
     /// Returns an error if the storage operation fails.
     fn delete_session(&self, session_id: &str) -> Result<()>;
+
+    /// Record a decision that was posted for a session that doesn't exist.
+    ///
+    /// Used to classify reviewer runs that failed because of a wrong `SESSION_ID`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage operation fails.
+    fn record_unknown_decision(&self, record: &UnknownDecision) -> Result<()>;
+
+    /// List unknown-session decisions recorded at or after `since`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage operation fails.
+    fn unknown_decisions_since(&self, since: DateTime<Utc>) -> Result<Vec<UnknownDecision>>;
+
+    /// Record an incident (a hook could not check review state).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage operation fails.
+    fn record_incident(&self, incident: &Incident) -> Result<()>;
+
+    /// List incidents recorded at or after `since`, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage operation fails.
+    fn incidents_since(&self, since: DateTime<Utc>) -> Result<Vec<Incident>>;
+}
+
+/// A `roz decide` call that referenced an unknown session ID.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnknownDecision {
+    /// The session ID that was passed to `roz decide`.
+    pub session_id: String,
+
+    /// When the decision was attempted.
+    pub timestamp: DateTime<Utc>,
+
+    /// Working directory of the `roz decide` call, used to tell apart
+    /// reviewers of different projects (absent in older records).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+}
+
+/// A hook that could not check review state because of an error.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Incident {
+    /// When the incident happened.
+    pub timestamp: DateTime<Utc>,
+
+    /// Hook that hit the error (e.g. `stop`, `pre-tool-use`).
+    pub hook: String,
+
+    /// What went wrong.
+    pub kind: IncidentKind,
+
+    /// Session the hook ran for, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+
+    /// Error message.
+    pub message: String,
+
+    /// Whether the hook blocked (`fail_closed`) instead of approving.
+    #[serde(default)]
+    pub blocked: bool,
+}
+
+/// Category of an incident.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentKind {
+    /// Hook input was not valid JSON.
+    InputParse,
+
+    /// Config file failed to parse; defaults were used.
+    Config,
+
+    /// The storage directory could not be opened.
+    StorageInit,
+
+    /// Reading session state failed.
+    StorageRead,
+
+    /// Writing session state failed.
+    StorageWrite,
+
+    /// The hook referenced a session that does not exist.
+    SessionNotFound,
+
+    /// The hook name is not recognized.
+    UnknownHook,
+}
+
+impl IncidentKind {
+    /// Short name (matches the serialized form).
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::InputParse => "input_parse",
+            Self::Config => "config",
+            Self::StorageInit => "storage_init",
+            Self::StorageRead => "storage_read",
+            Self::StorageWrite => "storage_write",
+            Self::SessionNotFound => "session_not_found",
+            Self::UnknownHook => "unknown_hook",
+        }
+    }
 }
 
 /// Summary information for a session.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SessionSummary {
     /// Session identifier.
     pub session_id: String,
@@ -47,6 +179,12 @@ pub struct SessionSummary {
     /// When the session was created.
     pub created_at: DateTime<Utc>,
 
+    /// When the session was last updated.
+    pub updated_at: DateTime<Utc>,
+
     /// Number of trace events.
     pub event_count: usize,
+
+    /// Working directory the session last ran in.
+    pub cwd: Option<PathBuf>,
 }
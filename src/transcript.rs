@@ -0,0 +1,505 @@
+//! Claude Code transcript reader.
+//!
+//! A transcript is JSONL: one entry per line, each with a `type` (`user`,
+//! `assistant`, `system`, `summary`, ...), a `timestamp` and, for messages, a
+//! `message` whose `content` is a string or a list of blocks (`text`,
+//! `tool_use`, `tool_result`, `thinking`). Lines that fail to parse and entry
+//! types roz does not use are skipped, so format drift thins the excerpt
+//! instead of failing it.
+
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Size limits for a transcript excerpt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TranscriptLimits {
+    /// Most recent user turns kept.
+    pub max_user_turns: usize,
+
+    /// Most recent tool calls kept.
+    pub max_tool_calls: usize,
+
+    /// Characters kept from each user turn and from the final summary.
+    pub max_text_chars: usize,
+
+    /// Characters kept from each tool call's JSON input.
+    pub max_input_chars: usize,
+}
+
+impl Default for TranscriptLimits {
+    fn default() -> Self {
+        Self {
+            max_user_turns: 20,
+            max_tool_calls: 200,
+            max_text_chars: 4000,
+            max_input_chars: 300,
+        }
+    }
+}
+
+/// What the reviewer needs from a transcript to compare claims with actions.
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+pub struct TranscriptExcerpt {
+    /// User turns, oldest first.
+    pub user_turns: Vec<UserTurn>,
+
+    /// Older user turns dropped by the limit.
+    pub omitted_user_turns: usize,
+
+    /// The assistant's last text message in the review cycle.
+    pub final_summary: Option<String>,
+
+    /// Tool calls made during the review cycle, oldest first.
+    pub tool_calls: Vec<ToolCall>,
+
+    /// Older tool calls dropped by the limit.
+    pub omitted_tool_calls: usize,
+}
+
+/// A prompt typed by the user.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct UserTurn {
+    /// When the turn was recorded.
+    pub timestamp: Option<DateTime<Utc>>,
+
+    /// Prompt text (truncated to the limit).
+    pub text: String,
+}
+
+/// A tool call made by the assistant.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct ToolCall {
+    /// When the call was recorded.
+    pub timestamp: Option<DateTime<Utc>>,
+
+    /// Tool name (e.g. `Edit`, `Bash`).
+    pub name: String,
+
+    /// Compact JSON input (truncated to the limit).
+    pub input: String,
+
+    /// Whether the result was an error (`None` if no result was recorded).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_error: Option<bool>,
+}
+
+/// Read a transcript file.
+///
+/// User turns come from the whole transcript; tool calls and the final
+/// summary only from entries at or after `since` (the review cycle start).
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be opened or read.
+pub fn read_transcript(
+    path: &Path,
+    since: Option<DateTime<Utc>>,
+    limits: &TranscriptLimits,
+) -> Result<TranscriptExcerpt> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut builder = Builder::default();
+    for line in reader.lines() {
+        let line = line?;
+        if let Ok(entry) = serde_json::from_str::<Value>(&line) {
+            builder.add(&entry, since, limits);
+        }
+    }
+    Ok(builder.finish(limits))
+}
+
+/// A tool call with its full input, for checking what an agent did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolUse {
+    /// Tool name (e.g. `Read`, `Bash`).
+    pub name: String,
+
+    /// Tool input as sent by the agent.
+    pub input: Value,
+}
+
+/// Read every tool call in a transcript, oldest first.
+///
+/// Unlike [`read_transcript`] this keeps sidechain entries, so it also works
+/// on a subagent's own transcript (`agent_transcript_path`).
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be opened or read.
+pub fn read_tool_uses(path: &Path) -> Result<Vec<ToolUse>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut uses = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let Ok(entry) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        if entry.get("type").and_then(Value::as_str) != Some("assistant") {
+            continue;
+        }
+        let Some(message) = entry.get("message") else {
+            continue;
+        };
+        uses.extend(
+            blocks(message)
+                .filter(|b| b.get("type").and_then(Value::as_str) == Some("tool_use"))
+                .filter_map(|b| {
+                    Some(ToolUse {
+                        name: b.get("name")?.as_str()?.to_string(),
+                        input: b.get("input").cloned().unwrap_or(Value::Null),
+                    })
+                }),
+        );
+    }
+    Ok(uses)
+}
+
+/// Parse transcript text that is already in memory.
+#[must_use]
+pub fn parse_transcript(
+    jsonl: &str,
+    since: Option<DateTime<Utc>>,
+    limits: &TranscriptLimits,
+) -> TranscriptExcerpt {
+    let mut builder = Builder::default();
+    for entry in jsonl
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+    {
+        builder.add(&entry, since, limits);
+    }
+    builder.finish(limits)
+}
+
+/// Accumulates an excerpt one entry at a time.
+#[derive(Default)]
+struct Builder {
+    excerpt: TranscriptExcerpt,
+
+    /// Tool call index by `tool_use` ID, for matching results.
+    calls_by_id: HashMap<String, usize>,
+
+    /// Message ID of the final summary; one message spans several entries.
+    summary_message: Option<String>,
+}
+
+impl Builder {
+    fn add(&mut self, entry: &Value, since: Option<DateTime<Utc>>, limits: &TranscriptLimits) {
+        // Subagent turns and injected meta messages are not the conversation
+        if flag(entry, "isSidechain") || flag(entry, "isMeta") {
+            return;
+        }
+        let timestamp = entry
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc));
+        let in_cycle = match (since, timestamp) {
+            (Some(since), Some(at)) => at >= since,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        let Some(message) = entry.get("message") else {
+            return;
+        };
+
+        match entry.get("type").and_then(Value::as_str) {
+            Some("user") => self.add_user(message, timestamp, in_cycle, limits),
+            Some("assistant") if in_cycle => {
+                self.add_assistant(message, timestamp, limits);
+            }
+            _ => {}
+        }
+    }
+
+    fn add_user(
+        &mut self,
+        message: &Value,
+        timestamp: Option<DateTime<Utc>>,
+        in_cycle: bool,
+        limits: &TranscriptLimits,
+    ) {
+        let mut text = Vec::new();
+        for block in blocks(message) {
+            match block.get("type").and_then(Value::as_str) {
+                Some("text") => text.extend(block.get("text").and_then(Value::as_str)),
+                Some("tool_result") if in_cycle => {
+                    let call = block
+                        .get("tool_use_id")
+                        .and_then(Value::as_str)
+                        .and_then(|id| self.calls_by_id.get(id));
+                    if let Some(&index) = call {
+                        self.excerpt.tool_calls[index].is_error = Some(flag(block, "is_error"));
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some(prompt) = message.get("content").and_then(Value::as_str) {
+            text.push(prompt);
+        }
+
+        let text = text.join("\n");
+        if !text.trim().is_empty() {
+            self.excerpt.user_turns.push(UserTurn {
+                timestamp,
+                text: truncate(&text, limits.max_text_chars),
+            });
+        }
+    }
+
+    fn add_assistant(
+        &mut self,
+        message: &Value,
+        timestamp: Option<DateTime<Utc>>,
+        limits: &TranscriptLimits,
+    ) {
+        let message_id = message.get("id").and_then(Value::as_str);
+        for block in blocks(message) {
+            match block.get("type").and_then(Value::as_str) {
+                Some("text") => {
+                    let Some(text) = block.get("text").and_then(Value::as_str) else {
+                        continue;
+                    };
+                    let same_message =
+                        message_id.is_some() && self.summary_message.as_deref() == message_id;
+                    let summary = match (&self.excerpt.final_summary, same_message) {
+                        (Some(previous), true) => format!("{previous}\n\n{text}"),
+                        _ => text.to_string(),
+                    };
+                    self.excerpt.final_summary = Some(truncate(&summary, limits.max_text_chars));
+                    self.summary_message = message_id.map(str::to_string);
+                }
+                Some("tool_use") => {
+                    if let Some(id) = block.get("id").and_then(Value::as_str) {
+                        self.calls_by_id
+                            .insert(id.to_string(), self.excerpt.tool_calls.len());
+                    }
+                    let input = block.get("input").map(Value::to_string).unwrap_or_default();
+                    self.excerpt.tool_calls.push(ToolCall {
+                        timestamp,
+                        name: block
+                            .get("name")
+                            .and_then(Value::as_str)
+                            .unwrap_or("unknown")
+                            .to_string(),
+                        input: truncate(&input, limits.max_input_chars),
+                        is_error: None,
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Keep the most recent turns and calls within the limits.
+    fn finish(mut self, limits: &TranscriptLimits) -> TranscriptExcerpt {
+        let excerpt = &mut self.excerpt;
+        excerpt.omitted_user_turns = excerpt
+            .user_turns
+            .len()
+            .saturating_sub(limits.max_user_turns);
+        excerpt.user_turns.drain(..excerpt.omitted_user_turns);
+        excerpt.omitted_tool_calls = excerpt
+            .tool_calls
+            .len()
+            .saturating_sub(limits.max_tool_calls);
+        excerpt.tool_calls.drain(..excerpt.omitted_tool_calls);
+        self.excerpt
+    }
+}
+
+/// Content blocks of a message (none for plain string content).
+fn blocks(message: &Value) -> impl Iterator<Item = &Value> {
+    message
+        .get("content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+fn flag(value: &Value, key: &str) -> bool {
+    value.get(key).and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// Truncate to `max` characters, noting how much was cut.
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((cut, _)) => format!(
+            "{}... [truncated, {} more chars]",
+            &text[..cut],
+            text[cut..].chars().count()
+        ),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn jsonl(entries: &[Value]) -> String {
+        entries
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn user(at: &str, content: &Value) -> Value {
+        json!({"type": "user", "timestamp": at, "message": {"role": "user", "content": content}})
+    }
+
+    fn assistant(at: &str, id: &str, content: &Value) -> Value {
+        json!({
+            "type": "assistant",
+            "timestamp": at,
+            "message": {"id": id, "role": "assistant", "content": content},
+        })
+    }
+
+    fn sample() -> String {
+        jsonl(&[
+            json!({"type": "summary", "summary": "Earlier work"}),
+            user("2026-01-01T10:00:00Z", &json!("#roz fix the login bug")),
+            json!({"type": "user", "isMeta": true, "timestamp": "2026-01-01T10:00:01Z",
+                   "message": {"role": "user", "content": "Caveat: injected"}}),
+            assistant(
+                "2026-01-01T10:00:02Z",
+                "msg_1",
+                &json!([{"type": "tool_use", "id": "tu_1", "name": "Read",
+                         "input": {"file_path": "src/login.rs"}}]),
+            ),
+            user(
+                "2026-01-01T10:00:03Z",
+                &json!([{"type": "tool_result", "tool_use_id": "tu_1", "content": "..."}]),
+            ),
+            assistant(
+                "2026-01-01T10:05:00Z",
+                "msg_2",
+                &json!([{"type": "tool_use", "id": "tu_2", "name": "Bash",
+                         "input": {"command": "cargo test"}}]),
+            ),
+            user(
+                "2026-01-01T10:05:10Z",
+                &json!([{"type": "tool_result", "tool_use_id": "tu_2", "is_error": true}]),
+            ),
+            assistant(
+                "2026-01-01T10:06:00Z",
+                "msg_3",
+                &json!([{"type": "thinking", "thinking": "hmm"}, {"type": "text", "text": "Fixed the bug."}]),
+            ),
+            assistant(
+                "2026-01-01T10:06:01Z",
+                "msg_3",
+                &json!([{"type": "text", "text": "All tests pass."}]),
+            ),
+            json!({"type": "assistant", "isSidechain": true, "timestamp": "2026-01-01T10:07:00Z",
+                   "message": {"id": "msg_4", "content": [{"type": "text", "text": "subagent"}]}}),
+            "not json".into(),
+        ])
+    }
+
+    #[test]
+    fn extracts_user_turns_summary_and_tool_calls() {
+        let excerpt = parse_transcript(&sample(), None, &TranscriptLimits::default());
+
+        assert_eq!(excerpt.user_turns.len(), 1);
+        assert_eq!(excerpt.user_turns[0].text, "#roz fix the login bug");
+        assert_eq!(
+            excerpt.final_summary.as_deref(),
+            Some("Fixed the bug.\n\nAll tests pass.")
+        );
+
+        let calls: Vec<_> = excerpt
+            .tool_calls
+            .iter()
+            .map(|c| (c.name.as_str(), c.is_error))
+            .collect();
+        assert_eq!(calls, [("Read", Some(false)), ("Bash", Some(true))]);
+        assert_eq!(excerpt.tool_calls[1].input, r#"{"command":"cargo test"}"#);
+    }
+
+    #[test]
+    fn review_cycle_bounds_tool_calls_but_not_user_turns() {
+        let since = "2026-01-01T10:05:00Z".parse().unwrap();
+        let excerpt = parse_transcript(&sample(), Some(since), &TranscriptLimits::default());
+
+        assert_eq!(excerpt.user_turns.len(), 1);
+        assert_eq!(excerpt.tool_calls.len(), 1);
+        assert_eq!(excerpt.tool_calls[0].name, "Bash");
+        assert!(excerpt.final_summary.is_some());
+    }
+
+    #[test]
+    fn limits_keep_the_most_recent_entries() {
+        let limits = TranscriptLimits {
+            max_user_turns: 1,
+            max_tool_calls: 1,
+            max_text_chars: 5,
+            max_input_chars: 8,
+        };
+        let text = jsonl(&[
+            user("2026-01-01T10:00:00Z", &json!("first")),
+            user("2026-01-01T10:01:00Z", &json!("second prompt")),
+        ]) + "\n"
+            + &sample();
+        let excerpt = parse_transcript(&text, None, &limits);
+
+        assert_eq!(excerpt.omitted_user_turns, 2);
+        assert_eq!(
+            excerpt.user_turns[0].text,
+            "#roz ... [truncated, 17 more chars]"
+        );
+        assert_eq!(excerpt.omitted_tool_calls, 1);
+        assert!(excerpt.tool_calls[0].input.starts_with(r#"{"comman..."#));
+    }
+
+    #[test]
+    fn read_transcript_from_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("transcript.jsonl");
+        std::fs::write(&path, sample()).unwrap();
+
+        let excerpt = read_transcript(&path, None, &TranscriptLimits::default()).unwrap();
+        assert_eq!(
+            excerpt,
+            parse_transcript(&sample(), None, &TranscriptLimits::default())
+        );
+        assert!(
+            read_transcript(
+                &temp.path().join("missing"),
+                None,
+                &TranscriptLimits::default()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn read_tool_uses_keeps_full_input_and_sidechains() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("agent.jsonl");
+        let long = "x".repeat(1000);
+        let text = jsonl(&[
+            json!({"type": "assistant", "isSidechain": true, "message": {"content": [
+                {"type": "text", "text": "Checking"},
+                {"type": "tool_use", "id": "a", "name": "Bash", "input": {"command": "roz context s"}},
+                {"type": "tool_use", "id": "b", "name": "Write", "input": {"content": long}},
+            ]}}),
+            user("2026-01-01T10:00:00Z", &json!("ignored")),
+        ]);
+        std::fs::write(&path, text).unwrap();
+
+        let uses = read_tool_uses(&path).unwrap();
+        assert_eq!(uses.len(), 2);
+        assert_eq!(uses[0].name, "Bash");
+        assert_eq!(uses[1].input["content"].as_str().unwrap().len(), 1000);
+    }
+}
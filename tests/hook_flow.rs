@@ -1,15 +1,19 @@
 //! Integration tests for the full hook flow.
 
 use chrono::{Duration, Utc};
+use roz::cli::decide::{record_decision, record_human_decision, record_unknown_session};
 use roz::core::state::{
     AttemptOutcome, Decision, DecisionRecord, EventType, ReviewAttempt, SessionState,
 };
 use roz::core::{
-    handle_session_end, handle_session_start, handle_stop, handle_stop_with_config,
+    Context, handle_session_end, handle_session_start, handle_stop, handle_stop_with_config,
     handle_subagent_stop, handle_user_prompt,
 };
-use roz::hooks::{HookDecision, HookInput};
-use roz::storage::{MemoryBackend, MessageStore};
+use roz::hooks::{
+    HookDecision, HookInput, SessionEndEvent, SessionStartEvent, StopEvent, SubagentStopEvent,
+    UserPromptSubmitEvent,
+};
+use roz::storage::{MemoryBackend, MessageStore, UnknownDecision};
 use std::path::PathBuf;
 
 fn make_input(session_id: &str) -> HookInput {
@@ -31,6 +35,10 @@ fn make_input(session_id: &str) -> HookInput {
         last_assistant_message: None,
         stop_hook_active: None,
         reason: None,
+        trigger: None,
+        custom_instructions: None,
+        message: None,
+        notification_type: None,
     }
 }
 
@@ -42,7 +50,7 @@ fn full_flow_user_prompt_to_complete() {
     // Step 1: User prompt with #roz prefix enables review
     let mut input = make_input(session_id);
     input.prompt = Some("#roz fix the authentication bug".to_string());
-    let output = handle_user_prompt(&input, &store);
+    let output = handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
     assert!(
         output.decision.is_none(),
         "expected approve (decision=None)"
@@ -55,7 +63,7 @@ fn full_flow_user_prompt_to_complete() {
 
     // Step 2: Stop hook blocks because review is pending
     let input = make_input(session_id);
-    let output = handle_stop(&input, &store);
+    let output = handle_stop(&StopEvent::from_input(&input), &store);
     assert!(matches!(output.decision, Some(HookDecision::Block)));
     assert!(output.reason.is_some());
     assert!(output.reason.as_ref().unwrap().contains("roz:roz"));
@@ -66,6 +74,8 @@ fn full_flow_user_prompt_to_complete() {
     state.review.decision_history.push(DecisionRecord {
         decision: state.review.decision.clone(),
         timestamp: now,
+        decided_by: None,
+        rejected: false,
     });
     state.review.decision = Decision::Complete {
         summary: "All changes look good".to_string(),
@@ -76,7 +86,7 @@ fn full_flow_user_prompt_to_complete() {
 
     // Step 4: Stop hook now approves
     let input = make_input(session_id);
-    let output = handle_stop(&input, &store);
+    let output = handle_stop(&StopEvent::from_input(&input), &store);
     assert!(
         output.decision.is_none(),
         "expected approve (decision=None)"
@@ -91,11 +101,11 @@ fn full_flow_with_issues() {
     // Step 1: Enable review
     let mut input = make_input(session_id);
     input.prompt = Some("#roz add new feature".to_string());
-    handle_user_prompt(&input, &store);
+    handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
 
     // Step 2: First stop blocks
     let input = make_input(session_id);
-    let output = handle_stop(&input, &store);
+    let output = handle_stop(&StopEvent::from_input(&input), &store);
     assert!(matches!(output.decision, Some(HookDecision::Block)));
 
     // Step 3: Roz finds issues
@@ -109,7 +119,7 @@ fn full_flow_with_issues() {
 
     // Step 4: Stop hook still blocks with issue message
     let input = make_input(session_id);
-    let output = handle_stop(&input, &store);
+    let output = handle_stop(&StopEvent::from_input(&input), &store);
     assert!(matches!(output.decision, Some(HookDecision::Block)));
     assert!(
         output
@@ -130,7 +140,7 @@ fn full_flow_with_issues() {
 
     // Step 6: Stop hook approves
     let input = make_input(session_id);
-    let output = handle_stop(&input, &store);
+    let output = handle_stop(&StopEvent::from_input(&input), &store);
     assert!(
         output.decision.is_none(),
         "expected approve (decision=None)"
@@ -168,7 +178,7 @@ fn subagent_stop_validates_decision_in_review_cycle() {
     input.agent_id = Some("agent-abc-123".to_string());
     input.last_assistant_message = Some("Review complete.".to_string());
 
-    let output = handle_subagent_stop(&input, &store);
+    let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
     assert!(
         output.decision.is_none(),
         "expected approve (decision=None)"
@@ -202,7 +212,7 @@ fn subagent_stop_rejects_stale_decision() {
     input.agent_type = Some("roz:roz".to_string());
     input.agent_id = Some("agent-abc".to_string());
 
-    let output = handle_subagent_stop(&input, &store);
+    let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
     assert!(matches!(output.decision, Some(HookDecision::Block)));
     assert!(
         output
@@ -232,7 +242,7 @@ fn subagent_stop_rejects_future_decision() {
     input.agent_type = Some("roz:roz".to_string());
     input.agent_id = Some("agent-abc".to_string());
 
-    let output = handle_subagent_stop(&input, &store);
+    let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
     assert!(matches!(output.decision, Some(HookDecision::Block)));
     assert!(output.reason.as_ref().unwrap().contains("in the future"));
 }
@@ -244,7 +254,7 @@ fn subagent_stop_non_roz_agent_approves() {
     let mut input = make_input("some-session");
     input.agent_type = Some("other:agent".to_string());
 
-    let output = handle_subagent_stop(&input, &store);
+    let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
     assert!(
         output.decision.is_none(),
         "non-roz agents should be approved"
@@ -272,7 +282,7 @@ fn subagent_stop_gate_flow_validates_review_started() {
     input.agent_type = Some("roz:roz".to_string());
     input.agent_id = Some("agent-abc".to_string());
 
-    let output = handle_subagent_stop(&input, &store);
+    let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
     assert!(
         output.decision.is_none(),
         "expected approve for gate flow with valid timing"
@@ -287,20 +297,20 @@ fn block_count_increments() {
     // Enable review
     let mut input = make_input(session_id);
     input.prompt = Some("#roz test".to_string());
-    handle_user_prompt(&input, &store);
+    handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
 
     // Block multiple times
     let input = make_input(session_id);
-    handle_stop(&input, &store);
+    handle_stop(&StopEvent::from_input(&input), &store);
 
     let state = store.get_session(session_id).unwrap().unwrap();
     assert_eq!(state.review.block_count, 1);
 
-    handle_stop(&input, &store);
+    handle_stop(&StopEvent::from_input(&input), &store);
     let state = store.get_session(session_id).unwrap().unwrap();
     assert_eq!(state.review.block_count, 2);
 
-    handle_stop(&input, &store);
+    handle_stop(&StopEvent::from_input(&input), &store);
     let state = store.get_session(session_id).unwrap().unwrap();
     assert_eq!(state.review.block_count, 3);
 }
@@ -313,11 +323,11 @@ fn no_review_means_no_block() {
     // User prompt WITHOUT #roz prefix
     let mut input = make_input(session_id);
     input.prompt = Some("fix the bug".to_string());
-    handle_user_prompt(&input, &store);
+    handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
 
     // Stop should approve immediately
     let input = make_input(session_id);
-    let output = handle_stop(&input, &store);
+    let output = handle_stop(&StopEvent::from_input(&input), &store);
     assert!(
         output.decision.is_none(),
         "expected approve (decision=None)"
@@ -330,7 +340,7 @@ fn no_review_means_no_block() {
 
 use roz::config::{ApprovalScope, Config, GatesConfig, ReviewConfig};
 use roz::core::handle_pre_tool_use;
-use roz::hooks::PermissionDecision;
+use roz::hooks::{PermissionDecision, PreToolUseEvent};
 use serde_json::json;
 
 fn make_gate_input(session_id: &str, tool_name: &str) -> HookInput {
@@ -352,6 +362,10 @@ fn make_gate_input(session_id: &str, tool_name: &str) -> HookInput {
         last_assistant_message: None,
         stop_hook_active: None,
         reason: None,
+        trigger: None,
+        custom_instructions: None,
+        message: None,
+        notification_type: None,
     }
 }
 
@@ -381,7 +395,7 @@ fn gate_session_scope_allows_after_approval() {
 
     // First gate call should block
     let input = make_gate_input(session_id, "mcp__test__action1");
-    let output = handle_pre_tool_use(&input, &config, &store);
+    let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
     assert_eq!(
         output.hook_specific_output.permission_decision,
         PermissionDecision::Deny
@@ -398,7 +412,7 @@ fn gate_session_scope_allows_after_approval() {
 
     // Second gate call (different tool) should allow (session scope)
     let input = make_gate_input(session_id, "mcp__test__action2");
-    let output = handle_pre_tool_use(&input, &config, &store);
+    let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
     assert_eq!(
         output.hook_specific_output.permission_decision,
         PermissionDecision::Allow
@@ -423,7 +437,7 @@ fn gate_prompt_scope_resets_on_new_prompt() {
 
     // Gate call should allow (approval is valid, prompt hasn't changed)
     let input = make_gate_input(session_id, "mcp__test__action");
-    let output = handle_pre_tool_use(&input, &config, &store);
+    let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
     assert_eq!(
         output.hook_specific_output.permission_decision,
         PermissionDecision::Allow
@@ -436,7 +450,7 @@ fn gate_prompt_scope_resets_on_new_prompt() {
 
     // Gate call should now block (new prompt invalidates approval)
     let input = make_gate_input(session_id, "mcp__test__action");
-    let output = handle_pre_tool_use(&input, &config, &store);
+    let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
     assert_eq!(
         output.hook_specific_output.permission_decision,
         PermissionDecision::Deny
@@ -460,7 +474,7 @@ fn gate_tool_scope_requires_fresh_review_each_time() {
 
     // Gate call should STILL block (tool scope always requires fresh review)
     let input = make_gate_input(session_id, "mcp__test__action");
-    let output = handle_pre_tool_use(&input, &config, &store);
+    let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
     assert_eq!(
         output.hook_specific_output.permission_decision,
         PermissionDecision::Deny
@@ -479,7 +493,7 @@ fn gate_non_matching_tool_allows() {
 
     // Non-matching tool should be allowed
     let input = make_gate_input(session_id, "Read");
-    let output = handle_pre_tool_use(&input, &config, &store);
+    let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
     assert_eq!(
         output.hook_specific_output.permission_decision,
         PermissionDecision::Allow
@@ -498,7 +512,7 @@ fn gate_empty_tools_allows_all() {
 
     // Any tool should be allowed when no gates configured
     let input = make_gate_input(session_id, "mcp__anything__action");
-    let output = handle_pre_tool_use(&input, &config, &store);
+    let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
     assert_eq!(
         output.hook_specific_output.permission_decision,
         PermissionDecision::Allow
@@ -518,7 +532,7 @@ fn gate_circuit_breaker_allows() {
 
     // Should allow due to circuit breaker
     let input = make_gate_input(session_id, "mcp__test__action");
-    let output = handle_pre_tool_use(&input, &config, &store);
+    let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
     assert_eq!(
         output.hook_specific_output.permission_decision,
         PermissionDecision::Allow
@@ -537,7 +551,7 @@ fn full_gate_flow_block_approve_allow() {
 
     // Step 2: Gate blocks on matching tool
     let input = make_gate_input(session_id, "mcp__tissue__close_issue");
-    let output = handle_pre_tool_use(&input, &config, &store);
+    let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
     assert_eq!(
         output.hook_specific_output.permission_decision,
         PermissionDecision::Deny
@@ -563,7 +577,7 @@ fn full_gate_flow_block_approve_allow() {
 
     // Step 5: Gate now allows
     let input = make_gate_input(session_id, "mcp__tissue__close_issue");
-    let output = handle_pre_tool_use(&input, &config, &store);
+    let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
     assert_eq!(
         output.hook_specific_output.permission_decision,
         PermissionDecision::Allow
@@ -582,11 +596,11 @@ fn stop_hook_creates_pending_attempt() {
     // Enable review
     let mut input = make_input(session_id);
     input.prompt = Some("#roz test attempt tracking".to_string());
-    handle_user_prompt(&input, &store);
+    handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
 
     // Stop hook should create a pending attempt when blocking
     let input = make_input(session_id);
-    let output = handle_stop(&input, &store);
+    let output = handle_stop(&StopEvent::from_input(&input), &store);
     assert!(matches!(output.decision, Some(HookDecision::Block)));
 
     // Verify attempt was created with Pending outcome
@@ -607,11 +621,11 @@ fn decide_updates_attempt_outcome_for_stats() {
     // Enable review
     let mut input = make_input(session_id);
     input.prompt = Some("#roz test stats tracking".to_string());
-    handle_user_prompt(&input, &store);
+    handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
 
     // Stop hook blocks and creates pending attempt
     let input = make_input(session_id);
-    handle_stop(&input, &store);
+    handle_stop(&StopEvent::from_input(&input), &store);
 
     // Verify pending attempt exists
     let state = store.get_session(session_id).unwrap().unwrap();
@@ -630,6 +644,8 @@ fn decide_updates_attempt_outcome_for_stats() {
     state.review.decision_history.push(DecisionRecord {
         decision: state.review.decision.clone(),
         timestamp: now,
+        decided_by: None,
+        rejected: false,
     });
     state.review.gate_approved_at = Some(now);
 
@@ -669,6 +685,207 @@ fn decide_updates_attempt_outcome_for_stats() {
     }
 }
 
+#[test]
+fn second_stop_without_reviewer_marks_not_spawned() {
+    let store = MemoryBackend::new();
+    let session_id = "outcome-not-spawned";
+
+    let mut input = make_input(session_id);
+    input.prompt = Some("#roz implement feature".to_string());
+    handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
+
+    // Block twice without ever spawning roz:roz
+    let input = make_input(session_id);
+    handle_stop(&StopEvent::from_input(&input), &store);
+    handle_stop(&StopEvent::from_input(&input), &store);
+
+    let state = store.get_session(session_id).unwrap().unwrap();
+    assert_eq!(state.review.attempts.len(), 2);
+    assert_eq!(state.review.attempts[0].outcome, AttemptOutcome::NotSpawned);
+    assert_eq!(state.review.attempts[1].outcome, AttemptOutcome::Pending);
+}
+
+#[test]
+fn reviewer_exit_without_decision_marks_no_decision() {
+    let store = MemoryBackend::new();
+    let session_id = "outcome-no-decision";
+
+    let mut input = make_input(session_id);
+    input.prompt = Some("#roz implement feature".to_string());
+    handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
+    handle_stop(&StopEvent::from_input(&make_input(session_id)), &store);
+
+    // Reviewer runs but never calls roz decide
+    let mut input = make_input(session_id);
+    input.agent_type = Some("roz:roz".to_string());
+    let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
+    assert!(matches!(output.decision, Some(HookDecision::Block)));
+
+    let state = store.get_session(session_id).unwrap().unwrap();
+    assert_eq!(state.review.attempts[0].outcome, AttemptOutcome::NoDecision);
+
+    // The next stop must not reclassify it as NotSpawned
+    handle_stop(&StopEvent::from_input(&make_input(session_id)), &store);
+    let state = store.get_session(session_id).unwrap().unwrap();
+    assert_eq!(state.review.attempts[0].outcome, AttemptOutcome::NoDecision);
+    assert_eq!(state.review.attempts[1].outcome, AttemptOutcome::Pending);
+}
+
+#[test]
+fn reviewer_recovers_after_no_decision_block() {
+    let store = MemoryBackend::new();
+    let session_id = "outcome-recovered";
+
+    let mut input = make_input(session_id);
+    input.prompt = Some("#roz implement feature".to_string());
+    handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
+    handle_stop(&StopEvent::from_input(&make_input(session_id)), &store);
+
+    let mut input = make_input(session_id);
+    input.agent_type = Some("roz:roz".to_string());
+    handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
+
+    // Blocked reviewer continues and posts a decision
+    record_decision(&store, session_id, "COMPLETE", "Verified", None, None).unwrap();
+
+    let state = store.get_session(session_id).unwrap().unwrap();
+    assert!(matches!(
+        state.review.attempts[0].outcome,
+        AttemptOutcome::Success { .. }
+    ));
+}
+
+#[test]
+fn decide_with_wrong_session_id_marks_bad_session_id() {
+    let store = MemoryBackend::new();
+    let session_id = "outcome-bad-id";
+
+    let mut input = make_input(session_id);
+    input.prompt = Some("#roz implement feature".to_string());
+    handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
+    handle_stop(&StopEvent::from_input(&make_input(session_id)), &store);
+
+    // Reviewer copies the wrong ID, running `roz decide` from the session's project
+    let cwd = PathBuf::from("/work/project");
+    assert!(record_decision(&store, "outcome-typo", "COMPLETE", "ok", None, None).is_err());
+    record_unknown_session(&store, "outcome-typo", Some(&cwd), &Context::system());
+
+    let mut input = make_input(session_id);
+    input.cwd = cwd;
+    input.agent_type = Some("roz:roz".to_string());
+    let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
+    assert!(matches!(output.decision, Some(HookDecision::Block)));
+    let reason = output.reason.unwrap();
+    assert!(reason.contains("outcome-typo"));
+    assert!(reason.contains(session_id));
+
+    let state = store.get_session(session_id).unwrap().unwrap();
+    assert_eq!(
+        state.review.attempts[0].outcome,
+        AttemptOutcome::BadSessionId
+    );
+}
+
+#[test]
+fn unknown_session_id_is_blamed_only_on_the_same_project() {
+    let store = MemoryBackend::new();
+    for (session_id, cwd) in [("project-a", "/work/a"), ("project-b", "/work/b")] {
+        let mut input = make_input(session_id);
+        input.cwd = PathBuf::from(cwd);
+        input.prompt = Some("#roz implement feature".to_string());
+        handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
+        handle_stop(&StopEvent::from_input(&input), &store);
+    }
+
+    // Project B's reviewer mistypes its session ID
+    store
+        .record_unknown_decision(&UnknownDecision {
+            session_id: "project-typo".to_string(),
+            timestamp: Utc::now(),
+            cwd: Some(PathBuf::from("/work/b/src")),
+        })
+        .unwrap();
+
+    let subagent_stop = |session_id: &str, cwd: &str| {
+        let mut input = make_input(session_id);
+        input.cwd = PathBuf::from(cwd);
+        input.agent_type = Some("roz:roz".to_string());
+        let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
+        let state = store.get_session(session_id).unwrap().unwrap();
+        (
+            output.reason.unwrap(),
+            state.review.attempts[0].outcome.clone(),
+        )
+    };
+
+    let (reason, outcome) = subagent_stop("project-a", "/work/a");
+    assert!(!reason.contains("project-typo"));
+    assert_eq!(outcome, AttemptOutcome::NoDecision);
+
+    let (reason, outcome) = subagent_stop("project-b", "/work/b");
+    assert!(reason.contains("project-typo"));
+    assert_eq!(outcome, AttemptOutcome::BadSessionId);
+}
+
+#[test]
+fn unknown_session_id_is_blamed_on_the_deepest_project() {
+    let store = MemoryBackend::new();
+    for (session_id, cwd) in [("outer", "/work/b"), ("nested", "/work/b/nested")] {
+        let mut input = make_input(session_id);
+        input.cwd = PathBuf::from(cwd);
+        input.prompt = Some("#roz implement feature".to_string());
+        handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
+        handle_stop(&StopEvent::from_input(&input), &store);
+    }
+
+    // The nested project's reviewer mistypes its session ID
+    store
+        .record_unknown_decision(&UnknownDecision {
+            session_id: "nested-typo".to_string(),
+            timestamp: Utc::now(),
+            cwd: Some(PathBuf::from("/work/b/nested/src")),
+        })
+        .unwrap();
+
+    let subagent_stop = |session_id: &str, cwd: &str| {
+        let mut input = make_input(session_id);
+        input.cwd = PathBuf::from(cwd);
+        input.agent_type = Some("roz:roz".to_string());
+        let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
+        let state = store.get_session(session_id).unwrap().unwrap();
+        (
+            output.reason.unwrap(),
+            state.review.attempts[0].outcome.clone(),
+        )
+    };
+
+    let (reason, outcome) = subagent_stop("outer", "/work/b");
+    assert!(!reason.contains("nested-typo"));
+    assert_eq!(outcome, AttemptOutcome::NoDecision);
+
+    let (reason, outcome) = subagent_stop("nested", "/work/b/nested");
+    assert!(reason.contains("nested-typo"));
+    assert_eq!(outcome, AttemptOutcome::BadSessionId);
+}
+
+#[test]
+fn session_end_marks_pending_attempt_not_spawned() {
+    let store = MemoryBackend::new();
+    let session_id = "outcome-session-end";
+
+    let mut input = make_input(session_id);
+    input.prompt = Some("#roz implement feature".to_string());
+    handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
+    handle_stop(&StopEvent::from_input(&make_input(session_id)), &store);
+
+    let mut input = make_input(session_id);
+    input.reason = Some("logout".to_string());
+    handle_session_end(&SessionEndEvent::from_input(&input), &store);
+
+    let state = store.get_session(session_id).unwrap().unwrap();
+    assert_eq!(state.review.attempts[0].outcome, AttemptOutcome::NotSpawned);
+}
+
 // SessionEnd integration tests
 
 #[test]
@@ -679,19 +896,19 @@ fn full_lifecycle_start_prompt_end() {
     // Step 1: Session starts
     let mut input = make_input(session_id);
     input.source = Some("startup".to_string());
-    let output = handle_session_start(&input, &store);
+    let output = handle_session_start(&SessionStartEvent::from_input(&input), &store);
     assert!(output.decision.is_none());
 
     // Step 2: User sends a prompt
     let mut input = make_input(session_id);
     input.prompt = Some("#roz fix the bug".to_string());
-    let output = handle_user_prompt(&input, &store);
+    let output = handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
     assert!(output.decision.is_none());
 
     // Step 3: Session ends
     let mut input = make_input(session_id);
     input.reason = Some("logout".to_string());
-    let output = handle_session_end(&input, &store);
+    let output = handle_session_end(&SessionEndEvent::from_input(&input), &store);
     assert!(output.decision.is_none());
 
     // Verify full trace: SessionStart, UserPrompt, SessionEnd
@@ -715,7 +932,7 @@ fn session_end_without_start_approves() {
     // Session end without any prior session - should fail open
     let mut input = make_input("no-start-session");
     input.reason = Some("clear".to_string());
-    let output = handle_session_end(&input, &store);
+    let output = handle_session_end(&SessionEndEvent::from_input(&input), &store);
     assert!(output.decision.is_none(), "should fail open");
 
     // No session should be created
@@ -730,11 +947,11 @@ fn session_end_preserves_review_state() {
     // Start session and enable review
     let mut input = make_input(session_id);
     input.source = Some("startup".to_string());
-    handle_session_start(&input, &store);
+    handle_session_start(&SessionStartEvent::from_input(&input), &store);
 
     let mut input = make_input(session_id);
     input.prompt = Some("#roz do the thing".to_string());
-    handle_user_prompt(&input, &store);
+    handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
 
     // Verify review is enabled before session end
     let state = store.get_session(session_id).unwrap().unwrap();
@@ -743,7 +960,7 @@ fn session_end_preserves_review_state() {
     // End the session
     let mut input = make_input(session_id);
     input.reason = Some("prompt_input_exit".to_string());
-    handle_session_end(&input, &store);
+    handle_session_end(&SessionEndEvent::from_input(&input), &store);
 
     // Review state should be preserved
     let state = store.get_session(session_id).unwrap().unwrap();
@@ -763,18 +980,19 @@ fn stop_hook_active_loop_trips_circuit_breaker_early() {
     // Step 1: Enable review
     let mut input = make_input(session_id);
     input.prompt = Some("#roz test stop_hook_active loop".to_string());
-    handle_user_prompt(&input, &store);
+    handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
 
     // Step 2: First stop blocks normally (stop_hook_active=false)
     let config = Config {
         circuit_breaker: roz::config::CircuitBreakerConfig {
             max_blocks: 3,
             cooldown_seconds: 300,
+            ..roz::config::CircuitBreakerConfig::default()
         },
         ..Config::default()
     };
     let input = make_input(session_id);
-    let output = handle_stop_with_config(&input, &store, &config);
+    let output = handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
     assert!(matches!(output.decision, Some(HookDecision::Block)));
 
     let state = store.get_session(session_id).unwrap().unwrap();
@@ -782,7 +1000,7 @@ fn stop_hook_active_loop_trips_circuit_breaker_early() {
 
     // Step 3: Second stop blocks normally (stop_hook_active=false)
     let input = make_input(session_id);
-    let output = handle_stop_with_config(&input, &store, &config);
+    let output = handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
     assert!(matches!(output.decision, Some(HookDecision::Block)));
 
     let state = store.get_session(session_id).unwrap().unwrap();
@@ -792,7 +1010,7 @@ fn stop_hook_active_loop_trips_circuit_breaker_early() {
     // block_count is already 2 >= 2 → trips BEFORE incrementing
     let mut input = make_input(session_id);
     input.stop_hook_active = Some(true);
-    let output = handle_stop_with_config(&input, &store, &config);
+    let output = handle_stop_with_config(&StopEvent::from_input(&input), &store, &config);
 
     // Should approve because circuit breaker tripped early
     assert!(
@@ -831,9 +1049,300 @@ fn stop_hook_active_does_not_affect_subagent_stop() {
     input.agent_id = Some("agent-sha-test".to_string());
     input.stop_hook_active = Some(true);
 
-    let output = handle_subagent_stop(&input, &store);
+    let output = handle_subagent_stop(&SubagentStopEvent::from_input(&input), &store);
     assert!(
         output.decision.is_none(),
         "subagent-stop should approve normally regardless of stop_hook_active"
     );
 }
+
+// ============================================================================
+// Human override tests
+// ============================================================================
+
+#[test]
+fn human_approve_unblocks_stop_and_gate() {
+    let store = MemoryBackend::new();
+    let session_id = "human-approve";
+    let config = make_config_with_gates(vec!["mcp__test__*"], ApprovalScope::Session);
+
+    store.put_session(&SessionState::new(session_id)).unwrap();
+
+    // Gate blocks and enables review
+    let input = make_gate_input(session_id, "mcp__test__deploy");
+    let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
+    assert_eq!(
+        output.hook_specific_output.permission_decision,
+        PermissionDecision::Deny
+    );
+
+    record_human_decision(
+        &store,
+        session_id,
+        Decision::Complete {
+            summary: "Checked the deploy by hand".to_string(),
+            second_opinions: None,
+        },
+    )
+    .unwrap();
+
+    let output = handle_pre_tool_use(&PreToolUseEvent::from_input(&input), &config, &store);
+    assert_eq!(
+        output.hook_specific_output.permission_decision,
+        PermissionDecision::Allow
+    );
+
+    let output = handle_stop_with_config(
+        &StopEvent::from_input(&make_input(session_id)),
+        &store,
+        &config,
+    );
+    assert!(output.decision.is_none());
+}
+
+#[test]
+fn human_reject_blocks_after_circuit_breaker_trip() {
+    let store = MemoryBackend::new();
+    let session_id = "human-reject";
+
+    let mut state = SessionState::new(session_id);
+    state.review.enabled = false;
+    state.review.circuit_breaker_tripped = true;
+    state.review.circuit_breaker_tripped_at = Some(Utc::now());
+    state.review.block_count = 3;
+    store.put_session(&state).unwrap();
+
+    // Breaker tripped: stop approves
+    let output = handle_stop(&StopEvent::from_input(&make_input(session_id)), &store);
+    assert!(output.decision.is_none());
+
+    record_human_decision(
+        &store,
+        session_id,
+        Decision::Issues {
+            summary: "The migration drops a column".to_string(),
+            message_to_agent: Some("The migration drops a column".to_string()),
+        },
+    )
+    .unwrap();
+
+    let output = handle_stop(&StopEvent::from_input(&make_input(session_id)), &store);
+    assert!(matches!(output.decision, Some(HookDecision::Block)));
+    assert!(
+        output
+            .reason
+            .unwrap()
+            .contains("The migration drops a column")
+    );
+}
+
+#[test]
+fn reviewer_issues_rounds_do_not_count_as_not_spawned() {
+    let store = MemoryBackend::new();
+    let session_id = "per-cause";
+
+    let mut input = make_input(session_id);
+    input.prompt = Some("#roz refactor".to_string());
+    handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
+
+    // Two stops without a reviewer: one short of the not-spawned limit
+    for _ in 0..2 {
+        let output = handle_stop(&StopEvent::from_input(&make_input(session_id)), &store);
+        assert!(matches!(output.decision, Some(HookDecision::Block)));
+    }
+
+    // The reviewer shows up and finds issues, twice
+    for _ in 0..2 {
+        record_decision(&store, session_id, "ISSUES", "Bug", Some("Fix it"), None).unwrap();
+        let output = handle_stop(&StopEvent::from_input(&make_input(session_id)), &store);
+        assert!(matches!(output.decision, Some(HookDecision::Block)));
+    }
+
+    let state = store.get_session(session_id).unwrap().unwrap();
+    assert_eq!(state.review.block_count, 4);
+    assert_eq!(state.review.not_spawned_blocks, 0);
+    assert_eq!(state.review.issues_blocks, 2);
+    assert!(!state.review.circuit_breaker_tripped);
+
+    // Approval clears the issues loop
+    record_decision(&store, session_id, "COMPLETE", "Fixed", None, None).unwrap();
+    let state = store.get_session(session_id).unwrap().unwrap();
+    assert_eq!(state.review.issues_blocks, 0);
+}
+
+#[test]
+fn stop_event_missing_stop_hook_active_still_blocks_pending_review() {
+    use roz::cli::hook::respond;
+    use roz::config::Config;
+
+    // Fail-open (the default) must not turn a malformed Stop into an approval
+    let store = MemoryBackend::new();
+    let config = Config::default();
+    let ctx = Context::system();
+    let session_id = "stop-missing-field";
+    let mut input = make_input(session_id);
+    input.prompt = Some("#roz implement feature".to_string());
+    handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
+
+    let stdin = format!(r#"{{"session_id":"{session_id}","cwd":"/tmp","hook_event_name":"Stop"}}"#);
+    let output = respond("stop", &stdin, &config, None, &store, &ctx).unwrap();
+    assert_eq!(output["decision"], "block");
+    let state = store.get_session(session_id).unwrap().unwrap();
+    assert_eq!(state.review.block_count, 1);
+
+    // The reviewer's stop is still checked for a decision
+    let stdin = format!(
+        r#"{{"session_id":"{session_id}","cwd":"/tmp","hook_event_name":"SubagentStop","agent_type":"roz:roz"}}"#
+    );
+    let output = respond("subagent-stop", &stdin, &config, None, &store, &ctx).unwrap();
+    assert_eq!(output["decision"], "block");
+}
+
+#[test]
+fn recorded_session_replays_without_divergence() {
+    use roz::cli::decide::record_decision_with_context;
+    use roz::cli::hook::respond;
+    use roz::cli::record::RecordedStep;
+    use roz::cli::replay::{load_fixture, replay};
+    use roz::config::Config;
+    use roz::core::{FixedClock, SeededRng};
+    use serde_json::{Value, json};
+
+    let mut config = Config::default();
+    config.review.gates.tools = vec!["Bash:git push*".to_string()];
+    config.review.gates.approval_ttl_seconds = Some(150);
+
+    // Record a session from last month on a clock that moves a minute per step
+    let store = MemoryBackend::new();
+    let clock = FixedClock::new(Utc::now() - Duration::days(30));
+    let rng = SeededRng::new(42);
+    let ctx = Context::new(&clock, &rng);
+    let session_id = "replay-flow";
+    let hook_input = |extra: Value| {
+        let mut input = json!({"session_id": session_id, "cwd": "/tmp"});
+        input
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        input
+    };
+    let push = json!({"tool_name": "Bash", "tool_input": {"command": "git push"}});
+
+    let mut steps = Vec::new();
+    for (hook, input) in [
+        (
+            "user-prompt",
+            hook_input(json!({"prompt": "#roz fix the bug"})),
+        ),
+        ("stop", hook_input(json!({}))),
+        (
+            "decide",
+            json!({"session_id": session_id, "decision": "COMPLETE", "summary": "Looks good"}),
+        ),
+        ("stop", hook_input(json!({}))),
+        ("pre-tool-use", hook_input(push.clone())),
+        ("pre-tool-use", hook_input(push.clone())),
+    ] {
+        clock.advance(Duration::minutes(1));
+        let output = if hook == "decide" {
+            record_decision_with_context(
+                &store,
+                session_id,
+                "COMPLETE",
+                "Looks good",
+                None,
+                None,
+                &ctx,
+            )
+            .unwrap();
+            json!({"ok": true})
+        } else {
+            respond(hook, &input.to_string(), &config, None, &store, &ctx).unwrap()
+        };
+        steps.push(RecordedStep {
+            timestamp: ctx.now(),
+            hook: hook.to_string(),
+            input,
+            output,
+        });
+    }
+
+    let temp = tempfile::TempDir::new().unwrap();
+    let path = temp.path().join("fixture.jsonl");
+    let lines: Vec<String> = steps
+        .iter()
+        .map(|step| serde_json::to_string(step).unwrap())
+        .collect();
+    std::fs::write(&path, lines.join("\n")).unwrap();
+
+    let fixture = load_fixture(&path).unwrap();
+    assert_eq!(fixture[1].output["decision"], "block");
+    let permission =
+        |step: &RecordedStep| step.output["hookSpecificOutput"]["permissionDecision"].clone();
+    // Approved two minutes earlier, then past the 150s TTL
+    assert_eq!(permission(&fixture[4]), "allow");
+    assert_eq!(permission(&fixture[5]), "deny");
+
+    let report = replay(&fixture, &config).unwrap();
+    assert_eq!(report.steps, 6);
+    assert!(report.divergences.is_empty(), "{:?}", report.divergences);
+}
+
+#[test]
+fn rejected_complete_restores_the_whole_review_state() {
+    use roz::config::Config;
+    use roz::core::handle_subagent_stop_with_config;
+
+    let store = MemoryBackend::new();
+    let session_id = "evidence-restore";
+
+    let mut input = make_input(session_id);
+    input.prompt = Some("#roz refactor".to_string());
+    handle_user_prompt(&UserPromptSubmitEvent::from_input(&input), &store);
+    handle_stop(&StopEvent::from_input(&make_input(session_id)), &store);
+    record_decision(&store, session_id, "ISSUES", "Bug", Some("Fix it"), None).unwrap();
+    handle_stop(&StopEvent::from_input(&make_input(session_id)), &store);
+
+    // Everything a COMPLETE resets, as it stood before the reviewer decided
+    let mut state = store.get_session(session_id).unwrap().unwrap();
+    state.review.trip_count = 2;
+    state.review.not_spawned_blocks = 1;
+    state
+        .review
+        .gate_denials
+        .insert("Bash:git push*".to_string(), 3);
+    state
+        .review
+        .gate_tripped_at
+        .insert("Bash:git push*".to_string(), Utc::now());
+    state.review.activity.files.insert("src/lib.rs".to_string());
+    state.review.activity.lines_changed = 40;
+    store.put_session(&state).unwrap();
+    let before = serde_json::to_value(&state.review).unwrap();
+
+    record_decision(&store, session_id, "COMPLETE", "Looks good", None, None).unwrap();
+
+    let temp = tempfile::TempDir::new().unwrap();
+    let transcript = temp.path().join("agent.jsonl");
+    std::fs::write(&transcript, "").unwrap();
+    let mut config = Config::default();
+    config.review.evidence.require_context = true;
+    let mut input = make_input(session_id);
+    input.agent_type = Some("roz:roz".to_string());
+    input.agent_transcript_path = Some(transcript);
+    let output =
+        handle_subagent_stop_with_config(&SubagentStopEvent::from_input(&input), &store, &config);
+    assert!(matches!(output.decision, Some(HookDecision::Block)));
+
+    // Only the rejected attempt was added to the history
+    let mut state = store.get_session(session_id).unwrap().unwrap();
+    let rejected = state.review.decision_history.pop().unwrap();
+    assert!(rejected.rejected);
+    assert!(matches!(rejected.decision, Decision::Complete { .. }));
+    assert_eq!(serde_json::to_value(&state.review).unwrap(), before);
+    assert!(state.review.before_complete.is_none());
+    assert_eq!(
+        state.review.attempts.last().unwrap().outcome,
+        AttemptOutcome::Pending
+    );
+}